@@ -1,18 +1,28 @@
-use std::{sync::Arc, time::Duration};
+use std::{io::Cursor, path::Path, sync::Arc, time::Duration};
 
-use app::App;
-use clap::{Parser, Subcommand};
-use db::{SqlValueType, SqliteDatabase};
+use app::{App, Router};
+use clap::{Parser, Subcommand, ValueEnum};
+use db::{
+    BlobEncoding, ConnectionOptions, Database, DatabaseSource, Databases, SearchIndexConfig,
+    SqliteDatabase,
+};
+use deflate::{DeflateStream, PerMessageDeflate};
 use futures::{future::poll_fn, SinkExt, StreamExt};
-use responses::ErrorResponse;
+use responses::{ApiResponse, ErrorResponse};
+use rest::RestAdapterLayer;
 use tokio::{
+    io::{AsyncBufRead, AsyncRead, AsyncReadExt, AsyncWrite, BufReader},
     net::{TcpListener, TcpStream},
     sync::{mpsc, Mutex},
 };
-use tokio_stream::wrappers::UnboundedReceiverStream;
-use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{Request as HandshakeRequest, Response as HandshakeResponse},
+    http::{header, HeaderValue, Request as HttpRequest, Response as HttpResponse},
+    Message as WsMessage,
+};
 use tower::{limit::RateLimitLayer, Service, ServiceBuilder};
-use websocket::WebSocketAdapterLayer;
+use websocket::{Encoding, WebSocketAdapterLayer};
 
 type BoxStr = Box<str>;
 type BoxList<T> = Box<[T]>;
@@ -20,9 +30,24 @@ type BoxList<T> = Box<[T]>;
 #[derive(Debug, Parser)]
 #[command(version, about)]
 struct Args {
-    /// Path to the sqlite database file.
-    #[arg(env)]
-    database: BoxStr,
+    /// The sqlite database files, comma separated, as `path` or `name=path`, or directories
+    /// of them. The first one is the default.
+    #[arg(env = "DATABASE", action = clap::ArgAction::Set, num_args = 1, value_delimiter = ',', required = true)]
+    databases: Vec<DatabaseSource>,
+
+    /// How blob values are represented in json, also used for the generated client's types.
+    #[arg(long, env, global = true, value_enum, default_value_t = BlobEncoding::Base64)]
+    blob_encoding: BlobEncoding,
+
+    /// Full-text search indexes to keep on tables, as `table:column,column`.
+    /// `serve` creates them, the generated clients get typed `Search` requests for them.
+    #[arg(long, env, global = true, value_delimiter = ';')]
+    search_index: Vec<SearchIndexConfig>,
+
+    /// Databases to attach to each database, comma separated, as `path` or `schema=path`.
+    /// Their tables are addressed as `schema.table`, plain names look in the main database first.
+    #[arg(long, env, global = true, value_delimiter = ',')]
+    attach: Vec<DatabaseSource>,
 
     #[clap(subcommand)]
     command: Command,
@@ -30,20 +55,134 @@ struct Args {
 
 #[derive(Debug, Subcommand)]
 enum Command {
-    /// Start thw WebSocket server.
+    /// Start the WebSocket and HTTP server.
     Serve {
         /// The address to bind to.
         #[arg(short, long, env, default_value = "127.0.0.1:3030")]
         address: BoxStr,
+
+        /// Apply the pending migrations in this directory before starting.
+        #[arg(long, env)]
+        migrations_dir: Option<BoxStr>,
+
+        /// Token that WebSocket clients present to create, alter and drop tables,
+        /// as a bearer token or an `access_token` query parameter.
+        #[arg(long, env)]
+        admin_token: Option<BoxStr>,
+
+        /// What to do when a client was generated for a different schema.
+        #[arg(long, env, value_enum, default_value_t = SchemaCheck::Warn)]
+        schema_check: SchemaCheck,
+
+        /// Compression level for WebSocket messages from 1 to 9,
+        /// 0 turns permessage-deflate off.
+        #[arg(long, env, default_value_t = 6, value_parser = clap::value_parser!(u32).range(0..=9))]
+        compression_level: u32,
+
+        /// Messages smaller than this many bytes are sent uncompressed.
+        #[arg(long, env, default_value_t = 1024)]
+        compression_threshold: usize,
+
+        #[command(flatten)]
+        connection: ConnectionOptions,
+    },
+    /// Apply the pending SQL migrations in a directory, named `<version>_<name>.sql`.
+    Migrate {
+        /// The directory the migration files are in.
+        #[arg(long, env, global = true, default_value = "migrations")]
+        migrations_dir: BoxStr,
+
+        /// Run the pending migrations in a transaction that is rolled back.
+        #[arg(long, env)]
+        dry_run: bool,
+
+        #[clap(subcommand)]
+        command: Option<MigrateCommand>,
     },
     /// Generate client library.
     Generate {
+        /// Emit plain TypeScript types and type guards instead of zod schemas,
+        /// so the client doesn't depend on `zod` and `nanoid`.
+        #[arg(long, env)]
+        no_runtime_validation: bool,
+
         /// The output path for the generated client.
-        #[arg(short, long, env, default_value = "jabroni.ts")]
-        out_path: BoxStr,
+        /// Defaults to `jabroni` with an extension matching the format.
+        #[arg(short, long, env)]
+        out_path: Option<BoxStr>,
+
+        /// The kind of client library to generate.
+        #[arg(
+            short,
+            long,
+            env,
+            visible_alias = "lang",
+            value_enum,
+            default_value_t = ClientFormat::Typescript
+        )]
+        format: ClientFormat,
     },
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SchemaCheck {
+    /// Log a warning and serve the client anyway.
+    Warn,
+    /// Send a `SchemaMismatch` error and close the connection.
+    Reject,
+}
+
+/// How connections are handled, shared by all of them.
+#[derive(Debug, Clone)]
+struct ServeOptions {
+    admin_token: Option<Arc<str>>,
+    schema_check: SchemaCheck,
+    compression: Option<deflate::Compression>,
+}
+
+#[derive(Debug, Subcommand)]
+enum MigrateCommand {
+    /// List the migrations and whether they were applied.
+    Status,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ClientFormat {
+    /// A TypeScript client library validated with zod.
+    Typescript,
+    /// JSON Schemas for every request and response type.
+    JsonSchema,
+    /// An OpenAPI document describing the HTTP endpoints.
+    Openapi,
+    /// A Rust module with serde types and a tokio-tungstenite client.
+    Rust,
+    /// A Python module with pydantic models and an asyncio websockets client.
+    Python,
+}
+
+impl ClientFormat {
+    const fn default_out_path(self) -> &'static str {
+        match self {
+            ClientFormat::Typescript => "jabroni.ts",
+            ClientFormat::JsonSchema => "jabroni.schema.json",
+            ClientFormat::Openapi => "jabroni.openapi.json",
+            ClientFormat::Rust => "jabroni.rs",
+            ClientFormat::Python => "jabroni.py",
+        }
+    }
+}
+
+/// `jabroni.ts` becomes `jabroni_{database}.ts`, so it can still be imported as a module.
+fn namespaced_path(out_path: &str, database: &str) -> BoxStr {
+    let path = Path::new(out_path);
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let file_name = match file_name.split_once('.') {
+        Some((stem, extension)) => format!("{stem}_{database}.{extension}"),
+        None => format!("{file_name}_{database}"),
+    };
+    path.with_file_name(file_name).to_string_lossy().into()
+}
+
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
@@ -57,26 +196,116 @@ async fn main() -> color_eyre::Result<()> {
 
     let args = Args::parse();
 
-    let db = SqliteDatabase::new(args.database).await?;
+    // Only `serve` tunes its connections, the other commands use sqlite's defaults.
+    let connection = match &args.command {
+        Command::Serve { connection, .. } => connection.clone(),
+        _ => ConnectionOptions::default(),
+    };
+    let databases = Databases::open(
+        args.databases,
+        args.attach,
+        connection,
+        args.blob_encoding,
+        args.search_index,
+    )
+    .await?;
+    // Migrations are only applied to the default database.
+    let (_, db) = databases.default();
 
     match args.command {
-        Command::Serve { address } => {
+        Command::Serve {
+            address,
+            migrations_dir,
+            admin_token,
+            schema_check,
+            compression_level,
+            compression_threshold,
+            connection: _,
+        } => {
+            if let Some(dir) = migrations_dir {
+                migrations::migrate(db, Path::new(dir.as_ref()), false).await?;
+            }
+            for (_, db) in databases.iter() {
+                db.create_search_indexes().await?;
+            }
+
             tokio::select! {
                 _ = tokio::signal::ctrl_c() => {
                     tracing::info!("shutting down gracefully due to CTRL+C signal");
                 }
-                _ = start(&address, db) => {
+                _ = start(&address, databases.clone(), ServeOptions {
+                    admin_token: admin_token.map(Into::into),
+                    schema_check,
+                    compression: (compression_level > 0).then_some(deflate::Compression {
+                        level: compression_level,
+                        threshold: compression_threshold,
+                    }),
+                }) => {
                     tracing::error!("server exited");
                 }
             }
         }
-        Command::Generate { out_path } => generate_client(db, out_path).await?,
+        Command::Generate {
+            out_path,
+            format,
+            no_runtime_validation,
+        } => {
+            let out_path = out_path.unwrap_or_else(|| format.default_out_path().into());
+            for (name, db) in databases.iter() {
+                // With several databases each gets its own client, connecting to it by name.
+                let (out_path, database) = if databases.len() > 1 {
+                    (namespaced_path(&out_path, name), Some(name.as_ref()))
+                } else {
+                    (out_path.clone(), None)
+                };
+                let db = db.clone();
+
+                match format {
+                    ClientFormat::Typescript => {
+                        typescript_client::generate_typescript_client(
+                            db,
+                            out_path,
+                            database,
+                            !no_runtime_validation,
+                        )
+                        .await?
+                    }
+                    ClientFormat::JsonSchema => {
+                        json_schema::generate_json_schema(db, out_path, database).await?
+                    }
+                    ClientFormat::Openapi => {
+                        json_schema::generate_openapi(db, out_path, database).await?
+                    }
+                    ClientFormat::Rust => {
+                        rust_client::generate_rust_client(db, out_path, database).await?
+                    }
+                    ClientFormat::Python => {
+                        python_client::generate_python_client(db, out_path, database).await?
+                    }
+                }
+            }
+        }
+        Command::Migrate {
+            migrations_dir,
+            dry_run,
+            command,
+        } => {
+            let dir = Path::new(migrations_dir.as_ref());
+            match command {
+                Some(MigrateCommand::Status) => migrations::status(db, dir).await?,
+                None => migrations::migrate(db, dir, dry_run).await?,
+            }
+        }
     };
 
     Ok(())
 }
 
-async fn start(address: &str, db: SqliteDatabase) -> color_eyre::Result<()> {
+async fn start(
+    address: &str,
+    databases: Databases,
+    options: ServeOptions,
+) -> color_eyre::Result<()> {
     use color_eyre::eyre::Context;
 
     let listener = TcpListener::bind(address)
@@ -87,10 +316,11 @@ async fn start(address: &str, db: SqliteDatabase) -> color_eyre::Result<()> {
     loop {
         match listener.accept().await {
             Ok((stream, peer_addr)) => {
-                let db = db.clone();
+                let databases = databases.clone();
+                let options = options.clone();
                 tokio::spawn(async move {
                     tracing::info!("accepting connection to {peer_addr}");
-                    accept_connection(stream, db).await;
+                    accept_connection(stream, databases, &options).await;
                 });
             }
             Err(err) => {
@@ -100,35 +330,203 @@ async fn start(address: &str, db: SqliteDatabase) -> color_eyre::Result<()> {
     }
 }
 
-async fn accept_connection(stream: TcpStream, db: SqliteDatabase) {
+async fn accept_connection(stream: TcpStream, databases: Databases, options: &ServeOptions) {
     tracing::info!("accepted connection");
 
-    let (mut ws_tx, mut ws_rx) = match tokio_tungstenite::accept_async(stream).await {
-        Ok(ws_stream) => {
-            tracing::info!("new websocket connection established");
-            ws_stream.split()
+    let (reader, writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    let head = match rest::read_head(&mut reader).await {
+        Ok(head) if head.is_empty() => {
+            tracing::warn!("connection closed before a request was sent");
+            return;
         }
+        Ok(head) => head,
+        Err(err) => {
+            tracing::error!("failed to read http request: {err}");
+            return;
+        }
+    };
+
+    let request = match rest::parse_head(&head) {
+        Ok(request) => request,
         Err(err) => {
-            tracing::error!("failed to establish websocket connection: {err}");
+            tracing::error!("failed to parse http request: {err}");
             return;
         }
     };
 
-    let (tx, rx) = mpsc::unbounded_channel();
-    let mut rx = UnboundedReceiverStream::new(rx);
+    if rest::is_websocket_upgrade(&request) {
+        // WebSockets connect to a database by its name. Any other path, like `/`, connects to
+        // the default database.
+        let name = rest::percent_decode(request.uri().path().trim_matches('/'));
+        let (database, db) = match databases.get(&name) {
+            Some(db) => (name, db.clone()),
+            None => {
+                let (default, db) = databases.default().clone();
+                if !name.is_empty() {
+                    tracing::warn!(
+                        "websocket connection to unknown database {name}, using {default}"
+                    );
+                }
+                (default, db)
+            }
+        };
+
+        let admin = options
+            .admin_token
+            .as_deref()
+            .is_some_and(|token| rest::has_access_token(&request, token));
+
+        // Requests can name other databases, the client's fingerprint is checked against each.
+        let mut mismatch = None;
+        let mut router = router(&databases, database.clone(), admin);
+        if let Some(client) = rest::schema_fingerprint(&request) {
+            for (name, other) in databases.iter() {
+                let server = match other.schema_fingerprint().await {
+                    Ok(server) if server != client => server,
+                    Ok(_) => continue,
+                    Err(err) => {
+                        tracing::error!(
+                            "failed to compute the schema fingerprint of {name}: {err}"
+                        );
+                        continue;
+                    }
+                };
+
+                if *name == database {
+                    let client = client.clone();
+                    mismatch = Some(ErrorResponse::SchemaMismatch { server, client });
+                } else if let SchemaCheck::Reject = options.schema_check {
+                    router = router.without(name);
+                } else {
+                    tracing::warn!("client was generated for a different schema than {name}'s");
+                }
+            }
+        }
+
+        let encoding = Encoding::negotiate(&request);
+        let deflate = options
+            .compression
+            .and_then(|compression| PerMessageDeflate::negotiate(&request, compression));
+
+        let stream = tokio::io::join(Cursor::new(head).chain(reader), writer);
+        accept_websocket(
+            stream,
+            router,
+            db,
+            admin,
+            mismatch,
+            options.schema_check,
+            encoding,
+            deflate,
+        )
+        .await;
+    } else {
+        accept_http(request, reader, writer, databases).await;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn accept_websocket<S>(
+    stream: S,
+    router: Router<SqliteDatabase>,
+    db: SqliteDatabase,
+    admin: bool,
+    mismatch: Option<ErrorResponse>,
+    schema_check: SchemaCheck,
+    encoding: Encoding,
+    deflate: Option<PerMessageDeflate>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let extension = deflate.as_ref().map(PerMessageDeflate::response_header);
+    // The error type is tungstenite's.
+    #[allow(clippy::result_large_err)]
+    let accept_protocol = |_: &HandshakeRequest, mut response: HandshakeResponse| {
+        if let Some(protocol) = encoding.protocol() {
+            response.headers_mut().insert(
+                header::SEC_WEBSOCKET_PROTOCOL,
+                HeaderValue::from_static(protocol),
+            );
+        }
+        if let Some(extension) = extension {
+            response
+                .headers_mut()
+                .insert(header::SEC_WEBSOCKET_EXTENSIONS, extension);
+        }
+        Ok(response)
+    };
+
+    let stream = DeflateStream::new(stream);
+    let (mut ws_tx, mut ws_rx) =
+        match tokio_tungstenite::accept_hdr_async(stream, accept_protocol).await {
+            Ok(mut ws_stream) => {
+                tracing::info!("new websocket connection established");
+                if let Some(deflate) = deflate {
+                    tracing::debug!("compressing messages with permessage-deflate");
+                    ws_stream.get_mut().enable(deflate);
+                }
+                ws_stream.split()
+            }
+            Err(err) => {
+                tracing::error!("failed to establish websocket connection: {err}");
+                return;
+            }
+        };
+
+    if let Some(error) = mismatch {
+        tracing::warn!("client was generated for a different schema: {error:?}");
+
+        if let SchemaCheck::Reject = schema_check {
+            ws_tx
+                .send(encoding.message(&error))
+                .await
+                .map_err(|e| tracing::error!("failed to send message to client: {e}"))
+                .unwrap_or_default();
+            ws_tx
+                .close()
+                .await
+                .map_err(|e| tracing::error!("failed to close websocket: {e}"))
+                .unwrap_or_default();
+            return;
+        }
+    }
+
+    // Bounded, so streamed responses are only read as fast as the client takes them.
+    let (tx, rx) = mpsc::channel(16);
+    let mut rx = ReceiverStream::new(rx);
 
     tokio::spawn(async move {
         while let Some(message) = rx.next().await {
-            ws_tx.send(message).await.unwrap_or_else(|err| {
+            if let Err(err) = ws_tx.send(message).await {
+                // Dropping the receiver also stops any responses still being streamed.
                 tracing::error!("websocket send error: {err}");
-            });
+                break;
+            }
         }
     });
 
+    let mut schema_changes = db.schema_changes();
+    let schema_tx = tx.clone();
+    // It holds a sender, which would keep the writer task alive after the connection ends.
+    let _notifier = AbortOnDrop(tokio::spawn(async move {
+        while let Some(change) = schema_changes.next().await {
+            let msg = encoding.message(&ApiResponse::SchemaChanged(change));
+            if schema_tx.send(msg).await.is_err() {
+                break;
+            }
+        }
+    }));
+
     let svc = ServiceBuilder::new()
         .layer(RateLimitLayer::new(1, Duration::from_secs(1)))
-        .layer(WebSocketAdapterLayer)
-        .service(App::new(db));
+        .layer(WebSocketAdapterLayer::new(
+            db.blob_encoding(),
+            encoding,
+            websocket::capabilities(admin),
+        ))
+        .service(router);
 
     let svc = Arc::new(Mutex::new(svc));
 
@@ -136,7 +534,7 @@ async fn accept_connection(stream: TcpStream, db: SqliteDatabase) {
         let tx = tx.clone();
 
         let request = match result {
-            Ok(WsMessage::Text(body)) => body,
+            Ok(message @ (WsMessage::Text(_) | WsMessage::Binary(_))) => message,
             Ok(WsMessage::Close(_)) => {
                 tracing::warn!("connection is closed");
                 break;
@@ -144,13 +542,8 @@ async fn accept_connection(stream: TcpStream, db: SqliteDatabase) {
             Ok(_) => {
                 tracing::warn!("received unsupported websocket message type");
 
-                let error = ErrorResponse::NonTextMessage;
-                let msg = WsMessage::text(
-                    serde_json::to_string(&error)
-                        .expect("failed to serialize error response to json"),
-                );
-
-                tx.send(msg)
+                tx.send(encoding.message(&ErrorResponse::NonTextMessage))
+                    .await
                     .map_err(|e| tracing::error!("failed to send message to client: {e}"))
                     .unwrap_or_default();
                 break;
@@ -168,10 +561,15 @@ async fn accept_connection(stream: TcpStream, db: SqliteDatabase) {
 
         let mut locked = svc.lock().await;
         match locked.call(request).await {
-            Ok(msg) => {
-                tx.send(msg)
-                    .map_err(|e| tracing::error!("failed to send message to client: {e}"))
-                    .unwrap_or_default();
+            Ok(mut messages) => {
+                tokio::spawn(async move {
+                    while let Some(msg) = messages.next().await {
+                        if let Err(e) = tx.send(msg).await {
+                            tracing::error!("failed to send message to client: {e}");
+                            break;
+                        }
+                    }
+                });
             }
             Err(err) => {
                 tracing::error!("failed to process message: {err}")
@@ -180,8 +578,93 @@ async fn accept_connection(stream: TcpStream, db: SqliteDatabase) {
     }
 }
 
+/// Aborts the task once dropped, even when a panic unwinds past it.
+struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+fn router(databases: &Databases, default: BoxStr, admin: bool) -> Router<SqliteDatabase> {
+    let apps = databases
+        .iter()
+        .map(|(name, db)| (name.clone(), App::new(db.clone()).with_admin(admin)));
+    Router::new(apps, default)
+}
+
+async fn accept_http<R, W>(
+    mut request: HttpRequest<()>,
+    mut reader: R,
+    mut writer: W,
+    databases: Databases,
+) where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    tracing::info!("new http connection established");
+
+    let mut svc = ServiceBuilder::new()
+        .layer(RateLimitLayer::new(1, Duration::from_secs(1)))
+        .layer(RestAdapterLayer)
+        .service(router(&databases, databases.default().0.clone(), false));
+
+    loop {
+        let mut keep_alive = rest::is_keep_alive(&request);
+
+        let response = match rest::read_body(&mut reader, &request).await {
+            Ok(body) => {
+                poll_fn(|ctx| svc.poll_ready(ctx))
+                    .await
+                    .unwrap_or_else(|e| tracing::error!("service failed to become ready: {e}"));
+
+                match svc.call(request.map(|_| body)).await {
+                    Ok(response) => response,
+                    Err(err) => {
+                        tracing::error!("failed to process request: {err}");
+                        break;
+                    }
+                }
+            }
+            Err(err) => {
+                tracing::error!("failed to read http request body: {err}");
+                keep_alive = false;
+                rest::error_response(
+                    err.status(),
+                    ErrorResponse::bad_request(err.to_string().into()),
+                )
+            }
+        };
+
+        if let Err(err) = rest::write_response(&mut writer, response, keep_alive).await {
+            tracing::error!("failed to send http response: {err}");
+            break;
+        }
+
+        if !keep_alive {
+            break;
+        }
+
+        request = match rest::read_head(&mut reader).await {
+            Ok(head) if head.is_empty() => break,
+            Ok(head) => match rest::parse_head(&head) {
+                Ok(request) => request,
+                Err(err) => {
+                    tracing::error!("failed to parse http request: {err}");
+                    break;
+                }
+            },
+            Err(err) => {
+                tracing::error!("failed to read http request: {err}");
+                break;
+            }
+        };
+    }
+}
+
 mod requests {
-    use std::{collections::HashMap, fmt::Display};
+    use std::{collections::HashMap, fmt::Display, num::NonZeroUsize};
 
     use serde::Deserialize;
     use serde_json::Value as JsonValue;
@@ -197,6 +680,44 @@ mod requests {
         BatchInsertRow(BatchInsertRowRequest),
         DeleteRow(DeleteRowRequest),
         UpdateRow(UpdateRowRequest),
+        Search(SearchRequest),
+        ReadBlob(ReadBlobRequest),
+        WriteBlob(WriteBlobRequest),
+        DescribeSchema(DescribeSchemaRequest),
+        DescribeTable(DescribeTableRequest),
+        CreateTable(CreateTableRequest),
+        AddColumn(AddColumnRequest),
+        CreateIndex(CreateIndexRequest),
+        DropTable(DropTableRequest),
+    }
+
+    impl ApiRequest {
+        pub fn request_id(&self) -> &BoxStr {
+            match self {
+                ApiRequest::ListRows(req) => &req.request_id,
+                ApiRequest::GetRow(req) => &req.request_id,
+                ApiRequest::InsertRow(req) => &req.request_id,
+                ApiRequest::BatchInsertRow(req) => &req.request_id,
+                ApiRequest::DeleteRow(req) => &req.request_id,
+                ApiRequest::UpdateRow(req) => &req.request_id,
+                ApiRequest::Search(req) => &req.request_id,
+                ApiRequest::ReadBlob(req) => &req.request_id,
+                ApiRequest::WriteBlob(req) => &req.request_id,
+                ApiRequest::DescribeSchema(req) => &req.request_id,
+                ApiRequest::DescribeTable(req) => &req.request_id,
+                ApiRequest::CreateTable(req) => &req.request_id,
+                ApiRequest::AddColumn(req) => &req.request_id,
+                ApiRequest::CreateIndex(req) => &req.request_id,
+                ApiRequest::DropTable(req) => &req.request_id,
+            }
+        }
+    }
+
+    /// A request for one of the served databases, the connection's own when it names none.
+    #[derive(Debug)]
+    pub struct RoutedRequest {
+        pub database: Option<BoxStr>,
+        pub request: ApiRequest,
     }
 
     #[derive(Debug, Deserialize)]
@@ -205,6 +726,9 @@ mod requests {
         pub select: BoxList<BoxStr>,
         pub sort: Option<SortInfo>,
         pub page: Option<Pagination>,
+        /// Stream the rows in `ListRowsChunk` messages of this many rows, followed by a `ListRowsEnd`.
+        #[serde(default)]
+        pub chunk_size: Option<NonZeroUsize>,
         pub request_id: BoxStr,
     }
 
@@ -271,26 +795,212 @@ mod requests {
         pub data: HashMap<BoxStr, JsonValue>,
         pub request_id: BoxStr,
     }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SearchRequest {
+        pub table: BoxStr,
+        /// An FTS5 query, e.g. `love NOT "love me"` or `Name: rock*`.
+        pub query: BoxStr,
+        pub select: BoxList<BoxStr>,
+        #[serde(default)]
+        pub highlight: Option<Highlight>,
+        #[serde(default)]
+        pub snippet: Option<Snippet>,
+        pub page: Option<Pagination>,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct ReadBlobRequest {
+        pub table: BoxStr,
+        pub key: JsonValue,
+        pub column: BoxStr,
+        #[serde(default)]
+        pub offset: Option<usize>,
+        /// Reads up to the end of the blob when missing.
+        #[serde(default)]
+        pub length: Option<usize>,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct WriteBlobRequest {
+        pub table: BoxStr,
+        pub key: JsonValue,
+        pub column: BoxStr,
+        #[serde(default)]
+        pub offset: Option<usize>,
+        pub data: JsonValue,
+        /// Replaces the blob with this many zero bytes before writing, writes can't change
+        /// the size of a blob.
+        #[serde(default)]
+        pub size: Option<usize>,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct DescribeSchemaRequest {
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct DescribeTableRequest {
+        pub table: BoxStr,
+        pub request_id: BoxStr,
+    }
+
+    /// Tables need a primary key, the columns marked as `primary_key` make it up in order.
+    #[derive(Debug, Deserialize)]
+    pub struct CreateTableRequest {
+        pub table: BoxStr,
+        pub columns: BoxList<ColumnDefinition>,
+        #[serde(default)]
+        pub strict: bool,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct AddColumnRequest {
+        pub table: BoxStr,
+        pub column: ColumnDefinition,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct CreateIndexRequest {
+        pub table: BoxStr,
+        pub name: BoxStr,
+        pub columns: BoxList<BoxStr>,
+        #[serde(default)]
+        pub unique: bool,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct DropTableRequest {
+        pub table: BoxStr,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct ColumnDefinition {
+        pub name: BoxStr,
+        /// e.g. `INTEGER`, `TEXT` or `NUMERIC(10,2)`, untyped when empty.
+        #[serde(default)]
+        pub declared_type: BoxStr,
+        #[serde(default)]
+        pub not_null: bool,
+        #[serde(default)]
+        pub primary_key: bool,
+        #[serde(default)]
+        pub unique: bool,
+        /// A literal, `null` and a missing default are the same.
+        #[serde(default)]
+        pub default: Option<JsonValue>,
+        #[serde(default)]
+        pub references: Option<ColumnReference>,
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    pub struct ColumnReference {
+        pub table: BoxStr,
+        /// The primary key of `table` when missing.
+        #[serde(default)]
+        pub column: Option<BoxStr>,
+    }
+
+    /// Not an [`ApiRequest`], the WebSocket connection answers it itself.
+    #[derive(Debug, Deserialize)]
+    pub struct HelloRequest {
+        pub protocol_version: u32,
+        /// Everything the server supports when empty.
+        #[serde(default)]
+        pub capabilities: BoxList<BoxStr>,
+        pub request_id: BoxStr,
+    }
+
+    /// Returns the indexed columns with every match wrapped in `open` and `close`.
+    #[derive(Debug, Deserialize)]
+    #[serde(default)]
+    pub struct Highlight {
+        pub open: BoxStr,
+        pub close: BoxStr,
+    }
+
+    impl Default for Highlight {
+        fn default() -> Self {
+            Self {
+                open: "<b>".into(),
+                close: "</b>".into(),
+            }
+        }
+    }
+
+    /// Returns the fragment of each indexed column with the most matches, at most
+    /// `tokens` long, with `ellipsis` where it was cut off.
+    #[derive(Debug, Deserialize)]
+    #[serde(default)]
+    pub struct Snippet {
+        pub open: BoxStr,
+        pub close: BoxStr,
+        pub ellipsis: BoxStr,
+        pub tokens: u8,
+    }
+
+    impl Default for Snippet {
+        fn default() -> Self {
+            Self {
+                open: "<b>".into(),
+                close: "</b>".into(),
+                ellipsis: "…".into(),
+                tokens: 16,
+            }
+        }
+    }
 }
 
 mod responses {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, num::NonZeroU32};
 
     use serde::Serialize;
+    use serde_json::Value as JsonValue;
 
-    use crate::{BoxList, BoxStr};
-
-    pub type Row = HashMap<BoxStr, serde_json::Value>;
+    use crate::{db::Affinity, BoxList, BoxStr};
+
+    /// A row's values by column name.
+    #[derive(Debug, Default, Serialize)]
+    pub struct Row {
+        #[serde(flatten)]
+        pub values: HashMap<BoxStr, JsonValue>,
+        /// The columns SQLite returned a blob for. Blob columns can also hold text and numbers,
+        /// which are sent as they are.
+        #[serde(skip)]
+        pub blobs: BoxList<BoxStr>,
+    }
 
     #[derive(Debug, Serialize)]
     #[serde(tag = "type")]
     pub enum ApiResponse {
         ListRows(ListRowsResponse),
+        ListRowsChunk(ListRowsResponse),
+        ListRowsEnd(ListRowsEndResponse),
         GetRow(GetRowResponse),
         InsertRow(InsertRowResponse),
         BatchInsertRow(InsertRowResponse),
         DeleteRow(DeleteRowResponse),
         UpdateRow(UpdateRowResponse),
+        Search(SearchResponse),
+        ReadBlob(ReadBlobResponse),
+        WriteBlob(WriteBlobResponse),
+        DescribeSchema(DescribeSchemaResponse),
+        DescribeTable(DescribeTableResponse),
+        CreateTable(SchemaChangeResponse),
+        AddColumn(SchemaChangeResponse),
+        CreateIndex(SchemaChangeResponse),
+        DropTable(SchemaChangeResponse),
+        /// Sent to every WebSocket connection after an admin changed the schema.
+        SchemaChanged(SchemaChanged),
+        Hello(HelloResponse),
     }
 
     #[derive(Debug, Serialize)]
@@ -298,6 +1008,15 @@ mod responses {
         pub table: BoxStr,
         pub rows: BoxList<Row>,
         pub request_id: BoxStr,
+        #[serde(skip)]
+        pub blob_columns: BoxList<BoxStr>,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct ListRowsEndResponse {
+        pub table: BoxStr,
+        pub total_rows: usize,
+        pub request_id: BoxStr,
     }
 
     #[derive(Debug, Serialize)]
@@ -305,57 +1024,312 @@ mod responses {
         pub table: BoxStr,
         pub row: Row,
         pub request_id: BoxStr,
+        #[serde(skip)]
+        pub blob_columns: BoxList<BoxStr>,
     }
 
     #[derive(Debug, Serialize)]
-    pub struct InsertRowResponse {
+    pub struct SearchResponse {
         pub table: BoxStr,
-        pub inserted_rows: usize,
+        pub hits: BoxList<SearchHit>,
         pub request_id: BoxStr,
+        #[serde(skip)]
+        pub blob_columns: BoxList<BoxStr>,
     }
 
     #[derive(Debug, Serialize)]
-    pub struct DeleteRowResponse {
+    pub struct SearchHit {
+        pub row: Row,
+        /// The match's `bm25()` score, lower is better.
+        pub rank: f64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub highlights: Option<HashMap<BoxStr, Option<BoxStr>>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub snippets: Option<HashMap<BoxStr, Option<BoxStr>>>,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct ReadBlobResponse {
         pub table: BoxStr,
-        pub deleted_rows: usize,
+        pub column: BoxStr,
+        pub offset: usize,
+        pub data: JsonValue,
+        /// Size of the whole blob.
+        pub size: usize,
         pub request_id: BoxStr,
     }
 
     #[derive(Debug, Serialize)]
-    pub struct UpdateRowResponse {
+    pub struct WriteBlobResponse {
         pub table: BoxStr,
-        pub updated_rows: usize,
+        pub column: BoxStr,
+        pub written_bytes: usize,
+        pub size: usize,
         pub request_id: BoxStr,
     }
 
     #[derive(Debug, Serialize)]
-    #[serde(tag = "type")]
-    pub enum ErrorResponse {
-        BadRequest { message: BoxStr },
-        NonTextMessage,
+    pub struct SchemaChangeResponse {
+        pub table: BoxStr,
+        pub request_id: BoxStr,
     }
 
-    impl ErrorResponse {
-        pub const fn bad_request(message: BoxStr) -> Self {
-            Self::BadRequest { message }
-        }
+    #[derive(Debug, Serialize)]
+    pub struct HelloResponse {
+        pub protocol_version: u32,
+        pub capabilities: BoxList<BoxStr>,
+        pub request_id: BoxStr,
     }
-}
 
-mod db {
-    use std::{collections::HashMap, fmt::Display};
+    #[derive(Debug, Clone, Serialize)]
+    pub struct SchemaChanged {
+        pub table: BoxStr,
+        pub change: SchemaChangeKind,
+    }
 
-    use r2d2::Pool;
-    use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
-    use rusqlite::types::Value as SqlValue;
-    use serde_json::Value as JsonValue;
+    #[derive(Debug, Clone, Copy, Serialize)]
+    pub enum SchemaChangeKind {
+        CreateTable,
+        AddColumn,
+        CreateIndex,
+        DropTable,
+    }
 
-    use crate::{
-        requests::{Pagination, SortOrder},
-        responses::Row,
-        BoxList, BoxStr,
+    #[derive(Debug, Serialize)]
+    pub struct DescribeSchemaResponse {
+        pub tables: BoxList<TableDescription>,
+        pub views: BoxList<ViewDescription>,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct DescribeTableResponse {
+        pub table: TableDescription,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct TableDescription {
+        pub name: BoxStr,
+        pub columns: BoxList<ColumnDescription>,
+        pub indexes: BoxList<IndexDescription>,
+        pub foreign_keys: BoxList<ForeignKeyDescription>,
+        pub strict: bool,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct ViewDescription {
+        pub name: BoxStr,
+        pub columns: BoxList<ColumnDescription>,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct ColumnDescription {
+        pub name: BoxStr,
+        pub declared_type: BoxStr,
+        pub affinity: Affinity,
+        pub nullable: bool,
+        /// The SQL expression of the column's default value.
+        pub default: Option<BoxStr>,
+        /// Position of the column in the primary key, starting at 1.
+        pub primary_key: Option<NonZeroU32>,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct IndexDescription {
+        pub name: BoxStr,
+        /// `null` for indexed expressions.
+        pub columns: BoxList<Option<BoxStr>>,
+        pub unique: bool,
+        pub origin: IndexOrigin,
+        /// Whether the index has a `WHERE` clause.
+        pub partial: bool,
+    }
+
+    /// What created an index, SQLite creates them for `UNIQUE` and `PRIMARY KEY` constraints.
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "snake_case")]
+    pub enum IndexOrigin {
+        CreateIndex,
+        Unique,
+        PrimaryKey,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct ForeignKeyDescription {
+        pub columns: BoxList<BoxStr>,
+        /// The referenced table.
+        pub table: BoxStr,
+        /// The referenced columns, `null` when the key refers to the primary key of `table`.
+        pub references: BoxList<Option<BoxStr>>,
+        pub on_update: BoxStr,
+        pub on_delete: BoxStr,
+    }
+
+    /// Responses carrying values of blob columns, which binary WebSocket frames send as raw bytes.
+    pub trait BlobCells {
+        fn blob_cells(&mut self) -> Vec<&mut JsonValue>;
+    }
+
+    impl BlobCells for ApiResponse {
+        fn blob_cells(&mut self) -> Vec<&mut JsonValue> {
+            let (rows, blob_columns): (Vec<&mut Row>, _) = match self {
+                ApiResponse::ListRows(ListRowsResponse {
+                    rows, blob_columns, ..
+                })
+                | ApiResponse::ListRowsChunk(ListRowsResponse {
+                    rows, blob_columns, ..
+                }) => (rows.iter_mut().collect(), blob_columns),
+                ApiResponse::GetRow(GetRowResponse {
+                    row, blob_columns, ..
+                }) => (vec![row], blob_columns),
+                ApiResponse::Search(SearchResponse {
+                    hits, blob_columns, ..
+                }) => (
+                    hits.iter_mut().map(|hit| &mut hit.row).collect(),
+                    blob_columns,
+                ),
+                ApiResponse::ReadBlob(ReadBlobResponse { data, .. }) => return vec![data],
+                _ => return Vec::new(),
+            };
+
+            let blob_columns: &BoxList<_> = blob_columns;
+            rows.into_iter()
+                .flat_map(|Row { values, blobs }| {
+                    let blobs: &BoxList<_> = blobs;
+                    values.iter_mut().filter(move |(column, _)| {
+                        blob_columns.contains(column) && blobs.contains(column)
+                    })
+                })
+                .map(|(_, value)| value)
+                .collect()
+        }
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct InsertRowResponse {
+        pub table: BoxStr,
+        pub inserted_rows: usize,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct DeleteRowResponse {
+        pub table: BoxStr,
+        pub deleted_rows: usize,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Serialize)]
+    pub struct UpdateRowResponse {
+        pub table: BoxStr,
+        pub updated_rows: usize,
+        pub request_id: BoxStr,
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "type")]
+    pub enum ErrorResponse {
+        BadRequest {
+            message: BoxStr,
+        },
+        NonTextMessage,
+        RouteNotFound {
+            path: BoxStr,
+        },
+        MethodNotAllowed {
+            method: BoxStr,
+        },
+        /// The request was handled without producing a response.
+        InternalError,
+        /// The client was generated for a database with different tables or columns.
+        SchemaMismatch {
+            server: BoxStr,
+            client: BoxStr,
+        },
+        UnsupportedProtocolVersion {
+            supported: BoxList<u32>,
+        },
+    }
+
+    impl ErrorResponse {
+        pub const fn bad_request(message: BoxStr) -> Self {
+            Self::BadRequest { message }
+        }
+    }
+
+    /// An error tagged with the id of the request that caused it, when known.
+    #[derive(Debug, Serialize)]
+    pub struct WithRequestId<E> {
+        #[serde(flatten)]
+        pub error: E,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub request_id: Option<BoxStr>,
+    }
+
+    impl<E> WithRequestId<E> {
+        pub const fn new(error: E, request_id: Option<BoxStr>) -> Self {
+            Self { error, request_id }
+        }
+    }
+
+    /// How errors are sent from protocol version 2 on, so clients can tell them apart
+    /// from responses without knowing every type of error.
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "type", rename = "Error")]
+    pub struct ErrorEnvelope<E> {
+        pub error: E,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub request_id: Option<BoxStr>,
+    }
+}
+
+mod db {
+    use std::{
+        collections::HashMap,
+        fmt::Display,
+        num::{NonZeroU32, NonZeroUsize},
+        path::Path,
+        sync::{Arc, RwLock},
+        time::Duration,
+    };
+
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use clap::ValueEnum;
+    use futures::{
+        stream::{self, BoxStream},
+        StreamExt,
+    };
+    use r2d2::Pool;
+    use r2d2_sqlite::{rusqlite, SqliteConnectionManager};
+    use rusqlite::{params_from_iter, DatabaseName, OptionalExtension};
+    use serde::Serialize;
+    use serde_json::Value as JsonValue;
+    use sha2::{Digest, Sha256};
+    use thiserror::Error;
+    use tokio::sync::{
+        broadcast,
+        mpsc::{self, error::SendTimeoutError},
+    };
+    use tokio_stream::wrappers::ReceiverStream;
+
+    use rusqlite::types::Type;
+    pub use rusqlite::types::Value as SqlValue;
+
+    use crate::{
+        migrations::{AppliedMigration, Migration},
+        requests::{ColumnDefinition, ColumnReference, Highlight, Pagination, Snippet, SortOrder},
+        responses::{
+            ColumnDescription, ForeignKeyDescription, IndexDescription, IndexOrigin, Row,
+            SchemaChangeKind, SchemaChanged, SearchHit, TableDescription, ViewDescription,
+        },
+        BoxList, BoxStr,
     };
 
+    /// Where `jabroni migrate` records the migrations it applied.
+    const MIGRATIONS_TABLE: &str = "jabroni_migrations";
+
+    #[derive(Eq, PartialEq, Hash, Clone, Debug)]
     pub struct TableName(BoxStr);
     #[derive(Eq, PartialEq, Hash, Clone, Debug)]
     pub struct ColumnName(BoxStr);
@@ -383,31 +1357,537 @@ mod db {
         pub fn as_str(&self) -> &str {
             &self.0
         }
+
+        /// The schema of a table in an attached database, and the table's name in it.
+        pub fn split(&self) -> (Option<&str>, &str) {
+            match self.0.split_once('.') {
+                Some((schema, table)) => (Some(schema), table),
+                None => (None, &self.0),
+            }
+        }
+
+        /// The schema, `main` for tables that aren't in an attached database.
+        pub fn schema(&self) -> &str {
+            self.split().0.unwrap_or("main")
+        }
+
+        /// `PRAGMA {pragma}` on the table. The schema of an attached table goes before the
+        /// pragma's name, `PRAGMA table_info(archive.albums)` isn't valid.
+        pub fn pragma(&self, pragma: &str) -> String {
+            match self.split() {
+                (Some(schema), table) => format!("PRAGMA {schema}.{pragma}({table})"),
+                (None, table) => format!("PRAGMA {pragma}({table})"),
+            }
+        }
+
+        /// The database and name incremental blob I/O opens the table with.
+        fn blob_table(&self) -> (DatabaseName<'_>, &str) {
+            match self.split() {
+                (Some(schema), table) => (DatabaseName::Attached(schema), table),
+                (None, table) => (DatabaseName::Main, table),
+            }
+        }
+
+        /// A name that also works as an identifier, `archive_albums` for `archive.albums`.
+        pub fn identifier(&self) -> String {
+            self.0.replace('.', "_")
+        }
     }
 
-    #[derive(Debug, Clone)]
+    /// Type affinity of a column, determined from its declared type the way SQLite does it.
+    /// See <https://www.sqlite.org/datatype3.html#determination_of_column_affinity>.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+    #[serde(rename_all = "UPPERCASE")]
+    pub enum Affinity {
+        Integer,
+        Text,
+        Blob,
+        Real,
+        Numeric,
+    }
+
+    impl Affinity {
+        pub fn from_declared_type(declared: &str) -> Self {
+            let declared = declared.to_uppercase();
+            let contains_any = |names: &[&str]| names.iter().any(|name| declared.contains(name));
+
+            if declared.contains("INT") {
+                Affinity::Integer
+            } else if contains_any(&["CHAR", "CLOB", "TEXT"]) {
+                Affinity::Text
+            } else if declared.is_empty() || declared.contains("BLOB") {
+                Affinity::Blob
+            } else if contains_any(&["REAL", "FLOA", "DOUB"]) {
+                Affinity::Real
+            } else {
+                Affinity::Numeric
+            }
+        }
+    }
+
+    /// Logical type of a column. Well known declared types like `BOOLEAN` or `DATETIME` get
+    /// their own type, everything else falls back to the column's affinity.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
     pub enum SqlValueType {
         Null,
         Integer,
         Real,
         Text,
         Blob,
+        Numeric,
+        Boolean,
+        Date,
+        DateTime,
+        Decimal,
+        Json,
     }
     type SqlValueWithIsNull = (SqlValueType, bool);
 
-    fn str_to_sql_value_type(s: BoxStr) -> SqlValueType {
-        match s.as_ref() {
+    impl SqlValueType {
+        /// A name that stays the same across releases, schema fingerprints are made of them.
+        pub const fn as_str(self) -> &'static str {
+            match self {
+                SqlValueType::Null => "Null",
+                SqlValueType::Integer => "Integer",
+                SqlValueType::Real => "Real",
+                SqlValueType::Text => "Text",
+                SqlValueType::Blob => "Blob",
+                SqlValueType::Numeric => "Numeric",
+                SqlValueType::Boolean => "Boolean",
+                SqlValueType::Date => "Date",
+                SqlValueType::DateTime => "DateTime",
+                SqlValueType::Decimal => "Decimal",
+                SqlValueType::Json => "Json",
+            }
+        }
+    }
+
+    fn str_to_sql_value_type(s: &str) -> SqlValueType {
+        let declared = s.to_uppercase();
+        // Drop size arguments, `DECIMAL(10,2)` is a `DECIMAL`.
+        let name = declared.split('(').next().unwrap_or_default().trim();
+
+        match name {
             "NULL" => SqlValueType::Null,
-            "INTEGER" => SqlValueType::Integer,
-            "REAL" => SqlValueType::Real,
-            "TEXT" => SqlValueType::Text,
-            "BLOB" => SqlValueType::Blob,
-            _ => SqlValueType::Text,
+            "BOOL" | "BOOLEAN" => SqlValueType::Boolean,
+            "DATE" => SqlValueType::Date,
+            "DATETIME" | "TIMESTAMP" => SqlValueType::DateTime,
+            "DECIMAL" => SqlValueType::Decimal,
+            "NUMERIC" if declared.contains('(') => SqlValueType::Decimal,
+            "JSON" | "JSONB" => SqlValueType::Json,
+            // Columns without a declared type hold anything, they aren't blob columns.
+            "" => SqlValueType::Text,
+            _ => match Affinity::from_declared_type(&declared) {
+                Affinity::Integer => SqlValueType::Integer,
+                Affinity::Text => SqlValueType::Text,
+                Affinity::Blob => SqlValueType::Blob,
+                Affinity::Real => SqlValueType::Real,
+                Affinity::Numeric => SqlValueType::Numeric,
+            },
+        }
+    }
+
+    /// Column metadata incoming values are validated against.
+    #[derive(Debug, Clone)]
+    pub struct ColumnInfo {
+        pub name: ColumnName,
+        pub declared_type: BoxStr,
+        pub typ: SqlValueType,
+        pub affinity: Affinity,
+        pub not_null: bool,
+        /// The SQL expression of the default value.
+        pub default: Option<BoxStr>,
+        /// Position in the primary key, starting at 1.
+        pub primary_key: Option<NonZeroU32>,
+        /// Whether this is the `INTEGER PRIMARY KEY` of a rowid table.
+        pub rowid_alias: bool,
+    }
+
+    impl ColumnInfo {
+        /// `INTEGER PRIMARY KEY` columns alias the rowid and get a value assigned when left empty.
+        pub fn is_rowid_alias(&self) -> bool {
+            self.rowid_alias
+        }
+
+        pub fn is_required(&self) -> bool {
+            self.not_null && self.default.is_none() && !self.is_rowid_alias()
+        }
+
+        pub fn describe(&self) -> ColumnDescription {
+            ColumnDescription {
+                name: self.name.as_str().into(),
+                declared_type: self.declared_type.clone(),
+                affinity: self.affinity,
+                nullable: !self.not_null && !self.is_rowid_alias(),
+                default: self.default.clone(),
+                primary_key: self.primary_key,
+            }
+        }
+
+        /// Checks that `value` can be stored in this column without SQLite silently
+        /// changing its meaning, returning why it can't otherwise.
+        pub fn check(&self, value: &JsonValue, strict: bool) -> Result<(), &'static str> {
+            let is_numeric = |s: &str| s.trim().parse::<f64>().is_ok();
+            let is_integral = |n: &serde_json::Number| {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            };
+
+            if value.is_null() {
+                return if self.not_null && !self.is_rowid_alias() {
+                    Err("must not be null")
+                } else {
+                    Ok(())
+                };
+            }
+
+            match (self.typ, value) {
+                (SqlValueType::Json, _) => return Ok(()),
+                // Blobs can be sent as arrays of bytes.
+                (SqlValueType::Blob, JsonValue::Array(_)) => return Ok(()),
+                (_, JsonValue::Array(_) | JsonValue::Object(_)) => {
+                    return Err("expected a scalar value")
+                }
+                (SqlValueType::Boolean, JsonValue::Bool(_)) => return Ok(()),
+                (SqlValueType::Boolean, JsonValue::Number(n))
+                    if matches!(n.as_i64(), Some(0 | 1)) =>
+                {
+                    return Ok(())
+                }
+                (SqlValueType::Boolean, _) => return Err("expected a boolean"),
+                // Dates have numeric affinity, but are usually stored as text.
+                (SqlValueType::Date | SqlValueType::DateTime, _) => return Ok(()),
+                _ => {}
+            }
+
+            // STRICT tables allow anything in `ANY` columns.
+            if strict && self.declared_type.eq_ignore_ascii_case("ANY") {
+                return Ok(());
+            }
+
+            match (self.affinity, value) {
+                (Affinity::Integer, JsonValue::Number(n)) if strict && !is_integral(n) => {
+                    Err("expected an integer")
+                }
+                (Affinity::Integer, JsonValue::String(s))
+                    if strict && s.trim().parse::<i64>().is_err() =>
+                {
+                    Err("expected an integer")
+                }
+                (Affinity::Integer | Affinity::Real | Affinity::Numeric, JsonValue::String(s))
+                    if !is_numeric(s) =>
+                {
+                    Err("expected a number")
+                }
+                (Affinity::Blob, JsonValue::Number(_)) if strict => Err("expected a blob"),
+                // Booleans are stored as 1 and 0, which only numeric columns take as they are.
+                (Affinity::Text, JsonValue::Bool(_)) => Err("expected a string"),
+                (Affinity::Blob, JsonValue::Bool(_)) => Err("expected a blob"),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TableInfo {
+        pub columns: BoxList<ColumnInfo>,
+        /// Whether the table was created with the `STRICT` option.
+        pub strict: bool,
+        /// The FTS5 index `Search` requests on this table are answered from.
+        pub search_index: Option<SearchIndex>,
+    }
+
+    /// An FTS5 table, searched either directly or through the table holding its content.
+    #[derive(Debug, Clone)]
+    pub struct SearchIndex {
+        pub name: TableName,
+        /// The indexed columns, in the order `highlight()` and `snippet()` number them.
+        pub columns: BoxList<ColumnName>,
+        /// The content table's column matching the index's rowid, when the index
+        /// is searched through its content table.
+        pub content_rowid: Option<ColumnName>,
+    }
+
+    /// A search index jabroni creates and keeps in sync with its table, written as
+    /// `table:column,column`.
+    #[derive(Debug, Clone)]
+    pub struct SearchIndexConfig {
+        pub table: BoxStr,
+        pub columns: BoxList<BoxStr>,
+    }
+
+    impl SearchIndexConfig {
+        pub fn index_name(&self) -> String {
+            format!("{}_fts", self.table)
+        }
+    }
+
+    impl std::str::FromStr for SearchIndexConfig {
+        type Err = &'static str;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (table, columns) = s
+                .split_once(':')
+                .ok_or("expected a table and its columns, like `tracks:Name,Composer`")?;
+
+            let columns: BoxList<BoxStr> = columns
+                .split(',')
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .map(Into::into)
+                .collect();
+            if table.trim().is_empty() || columns.is_empty() {
+                return Err("expected a table and at least one column");
+            }
+
+            Ok(Self {
+                table: table.trim().into(),
+                columns,
+            })
+        }
+    }
+
+    /// How the connections to each database are pooled and set up.
+    #[derive(Debug, Clone, Default, clap::Args)]
+    pub struct ConnectionOptions {
+        /// Connections kept open to each database, 10 by default.
+        #[arg(long, env, value_parser = clap::value_parser!(u32).range(1..))]
+        pub pool_size: Option<u32>,
+
+        /// Seconds a request waits for a free connection, 30 by default.
+        #[arg(long, env)]
+        pub connection_timeout: Option<u64>,
+
+        /// Milliseconds a connection waits for a locked database, 5000 by default.
+        #[arg(long, env)]
+        pub busy_timeout: Option<u64>,
+
+        /// The journal mode, `wal` lets reads go on while a write is in progress.
+        #[arg(long, env, value_enum)]
+        pub journal_mode: Option<JournalMode>,
+
+        /// How carefully writes are synced to the disk.
+        #[arg(long, env, value_enum)]
+        pub synchronous: Option<Synchronous>,
+
+        /// Enforce foreign key constraints.
+        #[arg(long, env)]
+        pub foreign_keys: bool,
+
+        /// The page cache size, in pages when positive and in KiB when negative.
+        #[arg(long, env, allow_negative_numbers = true)]
+        pub cache_size: Option<i64>,
+
+        /// More pragmas to set on every connection, comma separated, as `name=value`
+        /// or `schema.name=value`.
+        #[arg(long, env, value_delimiter = ',')]
+        pub pragma: Vec<Pragma>,
+    }
+
+    impl ConnectionOptions {
+        /// Runs after the attached databases are attached, so pragmas can name their schemas
+        /// and the journal mode applies to them too.
+        fn init(&self, conn: &mut rusqlite::Connection) -> Result<(), rusqlite::Error> {
+            if let Some(busy_timeout) = self.busy_timeout {
+                conn.busy_timeout(Duration::from_millis(busy_timeout))?;
+            }
+            if self.foreign_keys {
+                conn.pragma_update(None, "foreign_keys", true)?;
+            }
+            if let Some(journal_mode) = self.journal_mode {
+                conn.pragma_update(None, "journal_mode", journal_mode.as_str())?;
+            }
+            if let Some(synchronous) = self.synchronous {
+                conn.pragma_update(None, "synchronous", synchronous.as_str())?;
+            }
+            if let Some(cache_size) = self.cache_size {
+                conn.pragma_update(None, "cache_size", cache_size)?;
+            }
+            for pragma in &self.pragma {
+                let schema = pragma.schema.as_deref().map(DatabaseName::Attached);
+                conn.pragma_update(schema, &pragma.name, &pragma.value)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// See <https://www.sqlite.org/pragma.html#pragma_journal_mode>.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+    pub enum JournalMode {
+        Delete,
+        Truncate,
+        Persist,
+        Memory,
+        Wal,
+        Off,
+    }
+
+    impl JournalMode {
+        const fn as_str(self) -> &'static str {
+            match self {
+                JournalMode::Delete => "DELETE",
+                JournalMode::Truncate => "TRUNCATE",
+                JournalMode::Persist => "PERSIST",
+                JournalMode::Memory => "MEMORY",
+                JournalMode::Wal => "WAL",
+                JournalMode::Off => "OFF",
+            }
+        }
+    }
+
+    /// See <https://www.sqlite.org/pragma.html#pragma_synchronous>.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+    pub enum Synchronous {
+        Off,
+        Normal,
+        Full,
+        Extra,
+    }
+
+    impl Synchronous {
+        const fn as_str(self) -> &'static str {
+            match self {
+                Synchronous::Off => "OFF",
+                Synchronous::Normal => "NORMAL",
+                Synchronous::Full => "FULL",
+                Synchronous::Extra => "EXTRA",
+            }
+        }
+    }
+
+    /// A pragma set on every connection, written as `name=value` or `schema.name=value`.
+    /// Values that are integers are set as one, anything else as a string.
+    #[derive(Debug, Clone)]
+    pub struct Pragma {
+        schema: Option<BoxStr>,
+        name: BoxStr,
+        value: SqlValue,
+    }
+
+    impl std::str::FromStr for Pragma {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (name, value) = s
+                .split_once('=')
+                .ok_or("expected a pragma and its value, like `mmap_size=268435456`")?;
+            let (schema, name) = match name.trim().split_once('.') {
+                Some((schema, name)) => {
+                    check_identifier(schema)?;
+                    (Some(schema.into()), name)
+                }
+                None => (None, name.trim()),
+            };
+            check_identifier(name)?;
+
+            let value = value.trim();
+            let value = match value.parse() {
+                Ok(value) => SqlValue::Integer(value),
+                Err(_) => SqlValue::Text(value.into()),
+            };
+
+            Ok(Self {
+                schema,
+                name: name.into(),
+                value,
+            })
+        }
+    }
+
+    /// Files a directory of databases is searched for.
+    const DATABASE_EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3"];
+
+    /// A database to serve, written as `path` or `name=path`. Unnamed databases are named
+    /// after their file, and a directory stands for every database file in it.
+    #[derive(Debug, Clone)]
+    pub struct DatabaseSource {
+        pub name: Option<BoxStr>,
+        pub path: BoxStr,
+    }
+
+    impl std::str::FromStr for DatabaseSource {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let (name, path) = match s.split_once('=') {
+                Some((name, path)) => {
+                    check_identifier(name.trim())?;
+                    (Some(name.trim().into()), path.trim())
+                }
+                None => (None, s.trim()),
+            };
+            if path.is_empty() {
+                return Err("expected a path to a database".into());
+            }
+
+            Ok(Self {
+                name,
+                path: path.into(),
+            })
+        }
+    }
+
+    /// Names a database after its file, replacing whatever isn't a letter, digit or
+    /// underscore so the name also works as an identifier in the generated clients.
+    fn database_name(path: &Path) -> BoxStr {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut name: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            name.insert(0, '_');
+        }
+        name.into()
+    }
+
+    /// How blob values are represented in json, both in responses and requests.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+    pub enum BlobEncoding {
+        /// A base64 string.
+        #[default]
+        Base64,
+        /// A hex string.
+        Hex,
+        /// An array of byte values.
+        Array,
+    }
+
+    impl BlobEncoding {
+        pub const fn description(self) -> &'static str {
+            match self {
+                BlobEncoding::Base64 => "base64 encoded blob",
+                BlobEncoding::Hex => "hex encoded blob",
+                BlobEncoding::Array => "blob as an array of bytes",
+            }
+        }
+
+        pub fn encode(self, bytes: &[u8]) -> JsonValue {
+            match self {
+                BlobEncoding::Base64 => JsonValue::String(BASE64_STANDARD.encode(bytes)),
+                BlobEncoding::Hex => JsonValue::String(hex::encode(bytes)),
+                BlobEncoding::Array => serde_json::json!(bytes),
+            }
+        }
+
+        /// Reverses [`BlobEncoding::encode`]. Arrays of bytes are accepted whatever the encoding.
+        pub fn decode(self, value: &JsonValue) -> Option<Vec<u8>> {
+            match (self, value) {
+                (BlobEncoding::Base64, JsonValue::String(s)) => BASE64_STANDARD.decode(s).ok(),
+                (BlobEncoding::Hex, JsonValue::String(s)) => hex::decode(s).ok(),
+                (_, JsonValue::Array(bytes)) => bytes
+                    .iter()
+                    .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect(),
+                _ => None,
+            }
         }
     }
 
     pub trait Database: Clone + Send + 'static {
-        type Error: std::error::Error;
+        type Error: std::error::Error + Send + 'static;
+
+        fn blob_encoding(&self) -> BlobEncoding;
 
         fn check_table_name(
             &self,
@@ -426,6 +1906,11 @@ mod db {
             column_name: &str,
         ) -> impl std::future::Future<Output = Result<Option<ColumnName>, Self::Error>> + Send;
 
+        fn table_info(
+            &self,
+            table_name: &TableName,
+        ) -> impl std::future::Future<Output = Result<Arc<TableInfo>, Self::Error>> + Send;
+
         fn list_rows(
             &self,
             table_name: TableName,
@@ -434,50 +1919,391 @@ mod db {
             page: Option<Pagination>,
         ) -> impl std::future::Future<Output = Result<BoxList<Row>, Self::Error>> + Send;
 
+        /// Like [`Database::list_rows`], but reads the rows incrementally and yields them in chunks.
+        #[allow(clippy::type_complexity)]
+        fn stream_rows(
+            &self,
+            table_name: TableName,
+            column_names: Columns,
+            sort_info: Option<(ColumnName, SortOrder)>,
+            page: Option<Pagination>,
+            chunk_size: NonZeroUsize,
+        ) -> impl std::future::Future<
+            Output = Result<BoxStream<'static, Result<BoxList<Row>, Self::Error>>, Self::Error>,
+        > + Send;
+
         fn get_row(
             &self,
             table_name: TableName,
-            key: JsonValue,
+            key: SqlValue,
             column_names: Columns,
         ) -> impl std::future::Future<Output = Result<Option<Row>, Self::Error>> + Send;
 
+        /// Runs a full-text `query` against the table's search index, best matches first.
+        /// The inner error is FTS5's message for queries it can't parse.
+        fn search(
+            &self,
+            table_name: TableName,
+            column_names: Columns,
+            query: BoxStr,
+            markup: SearchMarkup,
+            page: Option<Pagination>,
+        ) -> impl std::future::Future<Output = Result<Result<BoxList<SearchHit>, BoxStr>, Self::Error>>
+               + Send;
+
+        /// Reads `length` bytes of a blob from `offset` on, or up to its end, along with its size.
+        #[allow(clippy::type_complexity)]
+        fn read_blob(
+            &self,
+            table_name: TableName,
+            key: SqlValue,
+            column_name: ColumnName,
+            offset: usize,
+            length: Option<usize>,
+        ) -> impl std::future::Future<
+            Output = Result<Result<(Vec<u8>, usize), BlobError>, Self::Error>,
+        > + Send;
+
+        /// Writes `data` into a blob at `offset`, after replacing it with `size` zero bytes
+        /// when given. Returns the size of the blob.
+        fn write_blob(
+            &self,
+            table_name: TableName,
+            key: SqlValue,
+            column_name: ColumnName,
+            offset: usize,
+            data: Vec<u8>,
+            size: Option<usize>,
+        ) -> impl std::future::Future<Output = Result<Result<usize, BlobError>, Self::Error>> + Send;
+
+        /// Every table, except internal ones and search indexes, and every view.
+        #[allow(clippy::type_complexity)]
+        fn describe_schema(
+            &self,
+        ) -> impl std::future::Future<
+            Output = Result<(BoxList<TableDescription>, BoxList<ViewDescription>), Self::Error>,
+        > + Send;
+
+        fn describe_table(
+            &self,
+            table_name: &TableName,
+        ) -> impl std::future::Future<Output = Result<TableDescription, Self::Error>> + Send;
+
         fn insert_row(
             &self,
             table_name: TableName,
-            data: HashMap<ColumnName, serde_json::Value>,
+            data: HashMap<ColumnName, SqlValue>,
         ) -> impl std::future::Future<Output = Result<usize, Self::Error>> + Send;
 
         fn batch_insert_row(
             &self,
             table_name: TableName,
-            data: Vec<HashMap<ColumnName, serde_json::Value>>,
+            data: Vec<HashMap<ColumnName, SqlValue>>,
         ) -> impl std::future::Future<Output = Result<usize, Self::Error>> + Send;
 
         fn delete_row(
             &self,
             table_name: TableName,
-            key: JsonValue,
+            key: SqlValue,
         ) -> impl std::future::Future<Output = Result<usize, Self::Error>> + Send;
 
         fn update_row(
             &self,
             table_name: TableName,
-            key: JsonValue,
-            data: HashMap<ColumnName, serde_json::Value>,
+            key: SqlValue,
+            data: HashMap<ColumnName, SqlValue>,
         ) -> impl std::future::Future<Output = Result<Option<usize>, Self::Error>> + Send;
+
+        /// Runs a DDL statement and tells every subscriber about it. The inner error is
+        /// why the change was rejected, either by jabroni or by SQLite.
+        fn change_schema(
+            &self,
+            change: SchemaChange,
+        ) -> impl std::future::Future<Output = Result<Result<(), BoxStr>, Self::Error>> + Send;
+    }
+
+    /// A DDL statement from an admin. Tables, columns and indexes it creates are named
+    /// with plain identifiers, so they never need quoting.
+    #[derive(Debug)]
+    pub enum SchemaChange {
+        CreateTable {
+            table: BoxStr,
+            columns: BoxList<ColumnDefinition>,
+            strict: bool,
+        },
+        AddColumn {
+            table: TableName,
+            column: ColumnDefinition,
+        },
+        CreateIndex {
+            table: TableName,
+            name: BoxStr,
+            columns: Columns,
+            unique: bool,
+        },
+        DropTable {
+            table: TableName,
+        },
+    }
+
+    impl SchemaChange {
+        fn to_sql(&self) -> Result<String, BoxStr> {
+            let sql = match self {
+                SchemaChange::CreateTable {
+                    table,
+                    columns,
+                    strict,
+                } => {
+                    check_identifier(table)?;
+                    let primary_key = columns
+                        .iter()
+                        .filter(|column| column.primary_key)
+                        .map(|column| column.name.as_ref())
+                        .collect::<Vec<_>>();
+                    if primary_key.is_empty() {
+                        return Err("a table needs at least one primary key column".into());
+                    }
+
+                    // A single `INTEGER PRIMARY KEY` column has to be declared inline to alias the rowid.
+                    let inline = primary_key.len() == 1;
+                    let mut definitions = columns
+                        .iter()
+                        .map(|column| column_sql(column, inline))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if !inline {
+                        definitions.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+                    }
+
+                    let strict = if *strict { " STRICT" } else { "" };
+                    format!("CREATE TABLE {table} ({}){strict}", definitions.join(", "))
+                }
+                SchemaChange::AddColumn { table, column } => {
+                    format!(
+                        "ALTER TABLE {table} ADD COLUMN {}",
+                        column_sql(column, true)?
+                    )
+                }
+                SchemaChange::CreateIndex {
+                    table,
+                    name,
+                    columns,
+                    unique,
+                } => {
+                    check_identifier(name)?;
+                    if columns.is_empty() {
+                        return Err("an index needs at least one column".into());
+                    }
+                    let columns = columns.iter().map(ColumnName::as_str).collect::<Vec<_>>();
+                    let unique = if *unique { "UNIQUE " } else { "" };
+                    // The index goes in the table's schema, which is named on the index.
+                    let (name, table) = match table.split() {
+                        (Some(schema), table) => (format!("{schema}.{name}"), table),
+                        (None, table) => (name.to_string(), table),
+                    };
+                    format!(
+                        "CREATE {unique}INDEX {name} ON {table} ({})",
+                        columns.join(", ")
+                    )
+                }
+                SchemaChange::DropTable { table } => format!("DROP TABLE {table}"),
+            };
+
+            Ok(sql)
+        }
+
+        fn to_event(&self) -> SchemaChanged {
+            let (table, change) = match self {
+                SchemaChange::CreateTable { table, .. } => {
+                    (table.clone(), SchemaChangeKind::CreateTable)
+                }
+                SchemaChange::AddColumn { table, .. } => {
+                    (table.0.clone(), SchemaChangeKind::AddColumn)
+                }
+                SchemaChange::CreateIndex { table, .. } => {
+                    (table.0.clone(), SchemaChangeKind::CreateIndex)
+                }
+                SchemaChange::DropTable { table } => (table.0.clone(), SchemaChangeKind::DropTable),
+            };
+            SchemaChanged { table, change }
+        }
+    }
+
+    fn check_identifier(name: &str) -> Result<(), BoxStr> {
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("{name:?} is not a valid name, use letters, digits and underscores").into())
+        }
+    }
+
+    /// A type name of words, optionally followed by one or two numbers in parentheses,
+    /// see <https://www.sqlite.org/syntax/type-name.html>.
+    fn check_declared_type(typ: &str) -> Result<(), BoxStr> {
+        let (name, size) = match typ.split_once('(') {
+            Some((name, size)) => (name, size.strip_suffix(')').map(Some).unwrap_or(Some(""))),
+            None => (typ, None),
+        };
+        let name_valid = name
+            .split_whitespace()
+            .all(|word| check_identifier(word).is_ok());
+        let size_valid = size.is_none_or(|size| {
+            let numbers = size.split(',').collect::<Vec<_>>();
+            numbers.len() <= 2 && numbers.iter().all(|n| n.trim().parse::<f64>().is_ok())
+        });
+
+        if name_valid && size_valid {
+            Ok(())
+        } else {
+            Err(format!("{typ:?} is not a valid column type").into())
+        }
+    }
+
+    fn sql_literal(value: &JsonValue) -> String {
+        let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+        match value {
+            JsonValue::Null => "NULL".into(),
+            JsonValue::Bool(b) => u8::from(*b).to_string(),
+            JsonValue::Number(n) => n.to_string(),
+            JsonValue::String(text) => quote(text),
+            // Stored as text, like values of JSON columns.
+            value @ (JsonValue::Array(_) | JsonValue::Object(_)) => quote(&value.to_string()),
+        }
+    }
+
+    fn column_sql(column: &ColumnDefinition, inline_primary_key: bool) -> Result<String, BoxStr> {
+        check_identifier(&column.name)?;
+        check_declared_type(&column.declared_type)?;
+
+        let mut sql = column.name.to_string();
+        if !column.declared_type.trim().is_empty() {
+            sql.push(' ');
+            sql.push_str(column.declared_type.trim());
+        }
+        if column.primary_key && inline_primary_key {
+            sql.push_str(" PRIMARY KEY");
+        }
+        if column.not_null {
+            sql.push_str(" NOT NULL");
+        }
+        if column.unique {
+            sql.push_str(" UNIQUE");
+        }
+        if let Some(default) = &column.default {
+            sql.push_str(" DEFAULT ");
+            sql.push_str(&sql_literal(default));
+        }
+        if let Some(ColumnReference { table, column }) = &column.references {
+            check_identifier(table)?;
+            sql.push_str(" REFERENCES ");
+            sql.push_str(table);
+            if let Some(column) = column {
+                check_identifier(column)?;
+                sql.push_str(&format!("({column})"));
+            }
+        }
+
+        Ok(sql)
+    }
+
+    /// The `highlight()` and `snippet()` columns to return along with each match.
+    #[derive(Debug, Default)]
+    pub struct SearchMarkup {
+        pub highlight: Option<Highlight>,
+        pub snippet: Option<Snippet>,
+    }
+
+    /// Why a blob can't be read or written.
+    #[derive(Debug)]
+    pub enum BlobError {
+        RowNotFound,
+        /// The column holds some other value in the row, like `NULL`.
+        NotABlob,
+        /// The range goes past the end of the blob, which is `size` bytes long,
+        /// or a resize asks for more than `size` bytes.
+        OutOfBounds {
+            size: usize,
+        },
+    }
+
+    /// SQLite's default `SQLITE_MAX_LENGTH`, the largest blob it will store.
+    const MAX_BLOB_SIZE: usize = 1_000_000_000;
+    /// The most bytes one `ReadBlob` returns, longer reads stop there.
+    const MAX_BLOB_READ: usize = 16 << 20;
+    /// How long a streamed `ListRows` waits for the client to take the next chunk.
+    const CHUNK_SEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// The tables read from a schema so far, and where changes to it are announced.
+    #[derive(Clone)]
+    struct SchemaState {
+        cache: Arc<RwLock<HashMap<TableName, Arc<TableInfo>>>>,
+        changes: broadcast::Sender<SchemaChanged>,
+    }
+
+    impl Default for SchemaState {
+        fn default() -> Self {
+            Self {
+                cache: Default::default(),
+                changes: broadcast::channel(16).0,
+            }
+        }
+    }
+
+    impl SchemaState {
+        fn clear(&self) {
+            self.cache
+                .write()
+                .expect("schema cache lock poisoned")
+                .clear();
+        }
     }
 
     #[derive(Clone)]
     pub struct SqliteDatabase {
         pool: Pool<SqliteConnectionManager>,
+        /// The main database's tables.
+        schema: SchemaState,
+        /// The attached databases' tables, shared by every database they are attached to.
+        attached_schema: SchemaState,
+        blob_encoding: BlobEncoding,
+        search_indexes: Arc<[SearchIndexConfig]>,
+        /// Schemas of the attached databases, in the order they were attached.
+        attached: Arc<[BoxStr]>,
     }
 
     impl SqliteDatabase {
-        pub async fn new(db: BoxStr) -> color_eyre::Result<Self> {
+        /// Every connection attaches the `(schema, path)` databases in `attached`.
+        pub async fn new(
+            db: BoxStr,
+            attached: Arc<[(BoxStr, BoxStr)]>,
+            connection: ConnectionOptions,
+            blob_encoding: BlobEncoding,
+            search_indexes: Vec<SearchIndexConfig>,
+        ) -> color_eyre::Result<Self> {
             use color_eyre::{eyre, eyre::Context};
 
-            let manager = SqliteConnectionManager::file(db.as_ref());
-            let pool = Pool::new(manager).context("failed to create database connection pool")?;
+            let schemas = attached.iter().map(|(schema, _)| schema.clone()).collect();
+            let mut pool = Pool::builder();
+            if let Some(pool_size) = connection.pool_size {
+                pool = pool.max_size(pool_size);
+            }
+            if let Some(connection_timeout) = connection.connection_timeout {
+                pool = pool.connection_timeout(Duration::from_secs(connection_timeout));
+            }
+
+            let manager = SqliteConnectionManager::file(db.as_ref()).with_init(move |conn| {
+                for (schema, path) in attached.iter() {
+                    conn.execute("ATTACH DATABASE ?1 AS ?2", [path, schema])?;
+                }
+                connection.init(conn)
+            });
+            let pool = pool
+                .build(manager)
+                .context("failed to create database connection pool")?;
 
             {
                 let pool = pool.clone();
@@ -503,25 +2329,313 @@ mod db {
                 .context("failed to spawn a tokio task")??;
             }
 
-            Ok(Self { pool })
+            Ok(Self {
+                pool,
+                schema: Default::default(),
+                attached_schema: Default::default(),
+                blob_encoding,
+                search_indexes: search_indexes.into(),
+                attached: schemas,
+            })
+        }
+
+        /// Changes made through [`Database::change_schema`] from now on, including those made
+        /// to the attached databases through any other database.
+        pub fn schema_changes(&self) -> BoxStream<'static, SchemaChanged> {
+            let receivers = [&self.schema, &self.attached_schema].map(|s| s.changes.subscribe());
+            let streams = receivers.map(|receiver| {
+                stream::unfold(receiver, |mut receiver| async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(change) => return Some((change, receiver)),
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        }
+                    }
+                })
+                .boxed()
+            });
+            stream::select_all(streams).boxed()
+        }
+
+        /// The state of the schema `table_name` is in.
+        fn schema_of(&self, table_name: &TableName) -> &SchemaState {
+            match table_name.split() {
+                (Some(_), _) => &self.attached_schema,
+                (None, _) => &self.schema,
+            }
+        }
+
+        /// Creates the configured search indexes, along with the triggers that keep them
+        /// up to date, and fills them from their tables. Indexes that already exist are
+        /// only rebuilt when their columns changed.
+        pub async fn create_search_indexes(&self) -> color_eyre::Result<()> {
+            use color_eyre::{eyre, eyre::Context};
+
+            for config in self.search_indexes.iter() {
+                let table_name = self
+                    .check_table_name(&config.table)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("search index table not found: {}", config.table))?;
+                // The index and its triggers are created in the main schema, where triggers
+                // can't be attached to another schema's table.
+                if let (Some(schema), _) = table_name.split() {
+                    eyre::bail!(
+                        "search indexes can't be created on tables in the attached database {schema}: {table_name}"
+                    );
+                }
+
+                let (columns, not_found) = self
+                    .check_column_names(&table_name, &config.columns)
+                    .await?;
+                if !not_found.is_empty() {
+                    eyre::bail!("search index columns not found in {table_name}: {not_found:?}");
+                }
+
+                let pool = self.pool.clone();
+                let index = config.index_name();
+                tokio::task::spawn_blocking(move || -> eyre::Result<()> {
+                    let conn = pool.get().context("failed to get a connection from pool")?;
+
+                    let columns: Vec<BoxStr> = columns.into_iter().map(|c| c.0).collect();
+                    let existing = conn
+                        .prepare(&format!("PRAGMA table_info({index})"))?
+                        .query_map((), |r| r.get::<_, BoxStr>(1))?
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if existing == columns {
+                        tracing::info!("search index {index} is up to date");
+                        return Ok(());
+                    }
+
+                    let list = columns.join(", ");
+                    let values = |row: &str| {
+                        columns
+                            .iter()
+                            .map(|c| format!("{row}.{c}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    let (new, old) = (values("new"), values("old"));
+
+                    conn.execute_batch(&format!(
+                        r#"
+                        BEGIN;
+                        DROP TABLE IF EXISTS {index};
+                        DROP TRIGGER IF EXISTS {index}_insert;
+                        DROP TRIGGER IF EXISTS {index}_delete;
+                        DROP TRIGGER IF EXISTS {index}_update;
+
+                        CREATE VIRTUAL TABLE {index} USING fts5({list}, content='{table_name}');
+
+                        CREATE TRIGGER {index}_insert AFTER INSERT ON {table_name} BEGIN
+                          INSERT INTO {index}(rowid, {list}) VALUES (new.rowid, {new});
+                        END;
+                        CREATE TRIGGER {index}_delete AFTER DELETE ON {table_name} BEGIN
+                          INSERT INTO {index}({index}, rowid, {list}) VALUES ('delete', old.rowid, {old});
+                        END;
+                        CREATE TRIGGER {index}_update AFTER UPDATE ON {table_name} BEGIN
+                          INSERT INTO {index}({index}, rowid, {list}) VALUES ('delete', old.rowid, {old});
+                          INSERT INTO {index}(rowid, {list}) VALUES (new.rowid, {new});
+                        END;
+
+                        INSERT INTO {index}({index}) VALUES ('rebuild');
+                        COMMIT;
+                        "#
+                    ))
+                    .with_context(|| format!("failed to create search index {index}"))?;
+
+                    tracing::info!("created search index {index} on {table_name}({list})");
+                    eyre::Ok(())
+                })
+                .await
+                .context("failed to spawn a tokio task")??;
+            }
+
+            self.schema.clear();
+            self.attached_schema.clear();
+
+            Ok(())
+        }
+
+        /// The migrations recorded in the bookkeeping table, which is only created once
+        /// migrations are applied.
+        pub async fn applied_migrations(&self) -> color_eyre::Result<Vec<AppliedMigration>> {
+            use color_eyre::{eyre, eyre::Context};
+
+            let pool = self.pool.clone();
+            tokio::task::spawn_blocking(move || {
+                let conn = pool.get().context("failed to get a connection from pool")?;
+
+                let exists = conn
+                    .query_row(
+                        "SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+                        [MIGRATIONS_TABLE],
+                        |r| r.get::<_, bool>(0),
+                    )
+                    .context("failed to look up the migrations table")?;
+                if !exists {
+                    return Ok(Vec::new());
+                }
+
+                let applied = conn
+                    .prepare(&format!(
+                        "SELECT version, name, checksum, applied_at FROM {MIGRATIONS_TABLE} ORDER BY version"
+                    ))?
+                    .query_map((), |r| {
+                        Ok(AppliedMigration {
+                            version: r.get(0)?,
+                            name: r.get(1)?,
+                            checksum: r.get(2)?,
+                            applied_at: r.get(3)?,
+                        })
+                    })?
+                    .collect::<Result<_, _>>()
+                    .context("failed to read applied migrations")?;
+
+                eyre::Ok(applied)
+            })
+            .await
+            .context("failed to spawn a tokio task")?
+        }
+
+        /// Runs `migrations` in order and records them, all in one transaction that is
+        /// rolled back on the first failure, or at the end of a dry run.
+        pub async fn apply_migrations(
+            &self,
+            migrations: Vec<Migration>,
+            dry_run: bool,
+        ) -> color_eyre::Result<()> {
+            use color_eyre::{eyre, eyre::Context};
+
+            let pool = self.pool.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut conn = pool.get().context("failed to get a connection from pool")?;
+                let tx = conn.transaction()?;
+
+                tx.execute_batch(&format!(
+                    r#"
+                    CREATE TABLE IF NOT EXISTS {MIGRATIONS_TABLE} (
+                      version INTEGER PRIMARY KEY,
+                      name TEXT NOT NULL,
+                      checksum TEXT NOT NULL,
+                      applied_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+                    );
+                    "#
+                ))
+                .context("failed to create the migrations table")?;
+
+                for Migration {
+                    version,
+                    name,
+                    sql,
+                    checksum,
+                } in migrations
+                {
+                    tx.execute_batch(&sql)
+                        .with_context(|| format!("migration {name} failed"))?;
+                    tx.execute(
+                        &format!(
+                            "INSERT INTO {MIGRATIONS_TABLE} (version, name, checksum) VALUES (?, ?, ?)"
+                        ),
+                        (version, &name, &checksum),
+                    )?;
+                }
+
+                if dry_run {
+                    tx.rollback()?;
+                } else {
+                    tx.commit()?;
+                }
+                eyre::Ok(())
+            })
+            .await
+            .context("failed to spawn a tokio task")??;
+
+            self.schema.clear();
+            self.attached_schema.clear();
+
+            Ok(())
         }
 
         pub async fn get_tables(&self) -> Result<BoxList<TableName>, rusqlite::Error> {
+            self.get_schema_objects("table").await
+        }
+
+        pub async fn get_views(&self) -> Result<BoxList<TableName>, rusqlite::Error> {
+            self.get_schema_objects("view").await
+        }
+
+        /// Objects of type `typ` in the main database, followed by the ones in each attached
+        /// database as `schema.name`.
+        async fn get_schema_objects(
+            &self,
+            typ: &'static str,
+        ) -> Result<BoxList<TableName>, rusqlite::Error> {
             let pool = self.pool.clone();
+            let attached = self.attached.clone();
             tokio::task::spawn_blocking(move || -> Result<BoxList<TableName>, rusqlite::Error> {
                 let conn = pool.get().expect("failed to get a connection from pool");
 
-                let rows = conn
-                    .prepare(r#"SELECT name FROM sqlite_master WHERE type = "table""#)?
-                    .query_map((), |r| r.get::<_, BoxStr>(0).map(TableName))?
-                    .collect::<Result<BoxList<_>, _>>()?;
+                let mut rows = conn
+                    .prepare("SELECT name FROM sqlite_master WHERE type = ?")?
+                    .query_map([typ], |r| r.get::<_, BoxStr>(0).map(TableName))?
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for schema in attached.iter() {
+                    let names = conn
+                        .prepare(&format!(
+                            "SELECT name FROM {schema}.sqlite_master WHERE type = ?"
+                        ))?
+                        .query_map([typ], |r| r.get::<_, BoxStr>(0))?
+                        .collect::<Result<Vec<_>, _>>()?;
+                    rows.extend(
+                        names
+                            .into_iter()
+                            .map(|name| TableName(format!("{schema}.{name}").into())),
+                    );
+                }
 
-                Ok(rows)
+                Ok(rows.into())
             })
             .await
             .expect("failed to spawn a tokio task")
         }
 
+        /// All tables except the ones sqlite creates for its own bookkeeping, and
+        /// virtual tables like search indexes, which only some requests work on.
+        pub async fn get_user_tables(&self) -> Result<BoxList<TableName>, rusqlite::Error> {
+            let tables = self.get_tables().await?;
+
+            let pool = self.pool.clone();
+            let virtual_tables =
+                tokio::task::spawn_blocking(move || -> Result<Vec<BoxStr>, rusqlite::Error> {
+                    let conn = pool.get().expect("failed to get a connection from pool");
+
+                    let rows = conn
+                        .prepare(
+                            "SELECT iif(schema = 'main', name, schema || '.' || name)
+                             FROM pragma_table_list
+                             WHERE schema != 'temp' AND type IN ('virtual', 'shadow')",
+                        )?
+                        .query_map((), |r| r.get::<_, BoxStr>(0))?
+                        .collect::<Result<_, _>>()?;
+
+                    Ok(rows)
+                })
+                .await
+                .expect("failed to spawn a tokio task")?;
+
+            Ok(tables
+                .into_vec()
+                .into_iter()
+                .filter(|t| {
+                    let (_, name) = t.split();
+                    !name.starts_with("sqlite_") && name != MIGRATIONS_TABLE
+                })
+                .filter(|t| !virtual_tables.iter().any(|v| **v == *t.as_str()))
+                .collect())
+        }
+
         pub async fn get_columns(
             &self,
             TableName(table_name): &TableName,
@@ -547,10 +2661,10 @@ mod db {
 
         pub async fn get_primary_key(
             &self,
-            TableName(table_name): &TableName,
+            table_name: &TableName,
         ) -> Result<ColumnName, rusqlite::Error> {
             let pool = self.pool.clone();
-            let sql = format!(r#"PRAGMA table_info({table_name})"#);
+            let sql = table_name.pragma("table_info");
 
             tokio::task::spawn_blocking(move || -> Result<ColumnName, rusqlite::Error> {
                 let conn = pool.get().expect("failed to get a connection from pool");
@@ -573,17 +2687,19 @@ mod db {
 
         pub async fn get_primary_key_type(
             &self,
-            TableName(table_name): &TableName,
+            table_name: &TableName,
         ) -> Result<SqlValueType, rusqlite::Error> {
             let pool = self.pool.clone();
-            let sql = format!(r#"PRAGMA table_info({table_name})"#);
+            let sql = table_name.pragma("table_info");
 
             tokio::task::spawn_blocking(move || -> Result<SqlValueType, rusqlite::Error> {
                 let conn = pool.get().expect("failed to get a connection from pool");
                 let mut stmt = conn.prepare(&sql)?;
 
                 let primary_keys: BoxList<(SqlValueType, bool)> = stmt
-                    .query_map((), |r| Ok((str_to_sql_value_type(r.get(2)?), r.get(5)?)))?
+                    .query_map((), |r| {
+                        Ok((str_to_sql_value_type(&r.get::<_, BoxStr>(2)?), r.get(5)?))
+                    })?
                     .collect::<Result<_, _>>()?;
 
                 let primary_key_type = primary_keys
@@ -591,7 +2707,7 @@ mod db {
                     .find_map(|(t, pk)| if *pk { Some(t) } else { None })
                     .expect("no column found at primary key index");
 
-                Ok(primary_key_type.clone())
+                Ok(*primary_key_type)
             })
             .await
             .expect("failed to spawn a tokio task")
@@ -599,10 +2715,10 @@ mod db {
 
         pub async fn get_column_types(
             &self,
-            TableName(table_name): &TableName,
+            table_name: &TableName,
         ) -> Result<BoxList<(ColumnName, SqlValueWithIsNull)>, rusqlite::Error> {
             let pool = self.pool.clone();
-            let sql = format!(r#"PRAGMA table_info({table_name})"#);
+            let sql = table_name.pragma("table_info");
 
             tokio::task::spawn_blocking(
                 move || -> Result<BoxList<(ColumnName, SqlValueWithIsNull)>, rusqlite::Error> {
@@ -613,7 +2729,10 @@ mod db {
                         .query_map((), |r| {
                             Ok((
                                 ColumnName(r.get(1)?),
-                                (str_to_sql_value_type(r.get(2)?), r.get(3)? && !r.get(5)?),
+                                (
+                                    str_to_sql_value_type(&r.get::<_, BoxStr>(2)?),
+                                    r.get(3)? && !r.get(5)?,
+                                ),
                             ))
                         })?
                         .collect::<Result<_, _>>()?;
@@ -624,104 +2743,405 @@ mod db {
             .await
             .expect("failed to spawn a tokio task")
         }
-    }
-
-    impl Database for SqliteDatabase {
-        type Error = rusqlite::Error;
 
-        async fn check_table_name(
-            &self,
-            table_name: &str,
-        ) -> Result<Option<TableName>, Self::Error> {
-            let table_name = table_name.to_lowercase();
-            for TableName(table) in self.get_tables().await? {
-                if table.to_lowercase() == table_name {
-                    return Ok(Some(TableName(table)));
+        /// A hash of every user table's columns and their types, which generated clients
+        /// send when connecting so the server notices they were made for another schema.
+        pub async fn schema_fingerprint(&self) -> Result<BoxStr, rusqlite::Error> {
+            let mut tables = self.get_user_tables().await?.into_vec();
+            tables.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+            let mut hasher = Sha256::new();
+            for table in tables {
+                hasher.update(format!("{table}\n"));
+                for (column, (typ, nullable)) in self.get_column_types(&table).await? {
+                    hasher.update(format!("{column}:{}:{nullable}\n", typ.as_str()));
                 }
             }
 
-            Ok(None)
+            Ok(hex::encode(hasher.finalize()).into())
         }
 
-        async fn check_column_names(
+        /// The `tables` that can be searched, with the columns their search index covers.
+        pub async fn get_searchable_tables(
             &self,
-            table_name: &TableName,
-            column_names: &[BoxStr],
-        ) -> Result<(Columns, Vec<BoxStr>), Self::Error> {
-            let all_columns = self.get_columns(table_name).await?;
-
-            Ok(column_names.iter().fold(
-                (
-                    Vec::with_capacity(column_names.len()),
-                    Vec::with_capacity(column_names.len()),
-                ),
-                |(mut found, mut not_found), n| {
-                    match all_columns
-                        .iter()
-                        .find(|column| n.to_lowercase() == column.to_lowercase())
-                        .map(|n| ColumnName(n.clone()))
-                    {
-                        Some(col) => {
-                            found.push(col);
-                        }
-                        None => {
-                            not_found.push(n.clone());
-                        }
-                    };
+            tables: &[TableName],
+        ) -> Result<Vec<(TableName, BoxList<ColumnName>)>, rusqlite::Error> {
+            let mut searchable = Vec::new();
+            for table in tables {
+                if let Some(index) = &self.table_info(table).await?.search_index {
+                    searchable.push((table.clone(), index.columns.clone()));
+                }
+            }
 
-                    (found, not_found)
-                },
-            ))
+            Ok(searchable)
         }
 
-        async fn check_column_name(
+        /// The `tables` with blob columns, and those columns.
+        pub async fn get_blob_columns(
             &self,
-            table_name: &TableName,
-            column_name: &str,
-        ) -> Result<Option<ColumnName>, Self::Error> {
-            let column_name = column_name.to_lowercase();
-            for column in self.get_columns(table_name).await? {
-                if column.to_lowercase() == column_name {
-                    return Ok(Some(ColumnName(column)));
+            tables: &[TableName],
+        ) -> Result<Vec<(TableName, BoxList<ColumnName>)>, rusqlite::Error> {
+            let mut blob_columns = Vec::new();
+            for table in tables {
+                let columns: BoxList<_> = self
+                    .table_info(table)
+                    .await?
+                    .columns
+                    .iter()
+                    .filter(|column| column.typ == SqlValueType::Blob)
+                    .map(|column| column.name.clone())
+                    .collect();
+                if !columns.is_empty() {
+                    blob_columns.push((table.clone(), columns));
                 }
             }
 
-            Ok(None)
+            Ok(blob_columns)
         }
 
-        async fn list_rows(
+        async fn get_value_types(
             &self,
-            TableName(table_name): TableName,
-            column_names: Columns,
-            sort_info: Option<(ColumnName, SortOrder)>,
-            page: Option<Pagination>,
-        ) -> Result<BoxList<Row>, Self::Error> {
-            let pool = self.pool.clone();
-            tokio::task::spawn_blocking(move || -> Result<BoxList<Row>, rusqlite::Error> {
-                let conn = pool.get().expect("failed to get a connection from pool");
+            table_name: &TableName,
+        ) -> Result<HashMap<BoxStr, SqlValueType>, rusqlite::Error> {
+            let info = self.table_info(table_name).await?;
+
+            Ok(info
+                .columns
+                .iter()
+                .map(|column| (column.name.0.clone(), column.typ))
+                .collect())
+        }
+    }
 
-                let selects = if column_names.is_empty() {
-                    "*".into()
-                } else {
-                    column_names
-                        .into_iter()
-                        .map(|c| c.0)
-                        .collect::<BoxList<_>>()
-                        .join(",")
-                };
+    /// Every database being served, by name. The first one is the default, used when a
+    /// connection or request doesn't name one.
+    #[derive(Clone)]
+    pub struct Databases {
+        databases: Arc<[(BoxStr, SqliteDatabase)]>,
+    }
 
-                let sort = sort_info
-                    .map(|(ColumnName(col), sort_order)| format!("ORDER BY {col} {sort_order}"))
-                    .unwrap_or_default();
+    impl Databases {
+        /// With more than one database, each creates the search indexes for the tables it has.
+        pub async fn open(
+            sources: Vec<DatabaseSource>,
+            attach: Vec<DatabaseSource>,
+            connection: ConnectionOptions,
+            blob_encoding: BlobEncoding,
+            search_indexes: Vec<SearchIndexConfig>,
+        ) -> color_eyre::Result<Self> {
+            use color_eyre::{eyre, eyre::Context};
+
+            let mut files = Vec::new();
+            for source in sources {
+                let path = Path::new(source.path.as_ref());
+                if !path.is_dir() {
+                    let name = source.name.unwrap_or_else(|| database_name(path));
+                    files.push((name, source.path));
+                    continue;
+                }
+
+                if let Some(name) = source.name {
+                    eyre::bail!(
+                        "{name}={} is a directory, only files can be named",
+                        source.path
+                    );
+                }
+                let mut paths = std::fs::read_dir(path)
+                    .with_context(|| format!("failed to read database directory {}", source.path))?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                paths.retain(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .is_some_and(|ext| DATABASE_EXTENSIONS.iter().any(|e| ext == *e))
+                });
+                paths.sort();
+                if paths.is_empty() {
+                    eyre::bail!("no database files found in {}", source.path);
+                }
+
+                for path in paths {
+                    files.push((database_name(&path), path.to_string_lossy().into()));
+                }
+            }
+
+            let mut attached: Vec<(BoxStr, BoxStr)> = Vec::with_capacity(attach.len());
+            for source in attach {
+                let path = Path::new(source.path.as_ref());
+                if !path.is_file() {
+                    eyre::bail!("attached database not found: {}", source.path);
+                }
+                let schema = source.name.unwrap_or_else(|| database_name(path));
+                if ["main", "temp"]
+                    .iter()
+                    .any(|s| schema.eq_ignore_ascii_case(s))
+                {
+                    eyre::bail!(
+                        "{schema} is reserved by sqlite, attach {} with another name",
+                        source.path
+                    );
+                }
+                if attached
+                    .iter()
+                    .any(|(other, _)| other.eq_ignore_ascii_case(&schema))
+                {
+                    eyre::bail!("more than one attached database is named {schema}");
+                }
+                tracing::info!("attaching {} as {schema}", source.path);
+                attached.push((schema, source.path));
+            }
+            let attached: Arc<[(BoxStr, BoxStr)]> = attached.into();
+
+            let mut databases: Vec<(BoxStr, SqliteDatabase)> = Vec::with_capacity(files.len());
+            for (name, path) in files {
+                if databases.iter().any(|(other, _)| *other == name) {
+                    eyre::bail!("more than one database is named {name}");
+                }
+                let db = SqliteDatabase::new(
+                    path,
+                    attached.clone(),
+                    connection.clone(),
+                    blob_encoding,
+                    search_indexes.clone(),
+                )
+                .await?;
+                databases.push((name, db));
+            }
+
+            // Every database attaches the same ones, a change to them is seen by all.
+            let attached_schema = SchemaState::default();
+            for (_, db) in databases.iter_mut() {
+                db.attached_schema = attached_schema.clone();
+            }
+
+            if databases.len() > 1 {
+                for (_, db) in databases.iter_mut() {
+                    let mut existing = Vec::new();
+                    for config in db.search_indexes.iter() {
+                        if db.check_table_name(&config.table).await?.is_some() {
+                            existing.push(config.clone());
+                        }
+                    }
+                    db.search_indexes = existing.into();
+                }
+
+                for config in search_indexes.iter() {
+                    let found = databases
+                        .iter()
+                        .any(|(_, db)| db.search_indexes.iter().any(|c| c.table == config.table));
+                    if !found {
+                        eyre::bail!("search index table not found: {}", config.table);
+                    }
+                }
+            }
+
+            Ok(Self {
+                databases: databases.into(),
+            })
+        }
+
+        pub fn get(&self, name: &str) -> Option<&SqliteDatabase> {
+            self.iter()
+                .find(|(other, _)| other.as_ref() == name)
+                .map(|(_, db)| db)
+        }
+
+        /// The name and database used when none is named.
+        pub fn default(&self) -> &(BoxStr, SqliteDatabase) {
+            &self.databases[0]
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = &(BoxStr, SqliteDatabase)> {
+            self.databases.iter()
+        }
+
+        pub fn len(&self) -> usize {
+            self.databases.len()
+        }
+    }
+
+    impl Database for SqliteDatabase {
+        type Error = rusqlite::Error;
+
+        fn blob_encoding(&self) -> BlobEncoding {
+            self.blob_encoding
+        }
 
-                let limit = page
-                    .map(|Pagination { number, size }| {
-                        let offset = (number - 1) * size;
-                        format!("LIMIT {size} OFFSET {offset}")
+        async fn check_table_name(
+            &self,
+            table_name: &str,
+        ) -> Result<Option<TableName>, Self::Error> {
+            let table_name = table_name.to_lowercase();
+            let tables = self.get_tables().await?;
+
+            // Names without a schema can also be of a table in an attached database, the
+            // main database's tables come first, then the attached ones in order.
+            let found = tables
+                .iter()
+                .find(|table| table.as_str().to_lowercase() == table_name)
+                .or_else(|| {
+                    tables.iter().find(|table| {
+                        matches!(table.split(), (Some(_), name) if name.to_lowercase() == table_name)
                     })
-                    .unwrap_or_default();
+                });
+
+            Ok(found.cloned())
+        }
+
+        async fn check_column_names(
+            &self,
+            table_name: &TableName,
+            column_names: &[BoxStr],
+        ) -> Result<(Columns, Vec<BoxStr>), Self::Error> {
+            let all_columns = self.get_columns(table_name).await?;
 
-                let sql = format!("SELECT {selects} FROM {table_name} {sort} {limit}");
+            Ok(column_names.iter().fold(
+                (
+                    Vec::with_capacity(column_names.len()),
+                    Vec::with_capacity(column_names.len()),
+                ),
+                |(mut found, mut not_found), n| {
+                    match all_columns
+                        .iter()
+                        .find(|column| n.to_lowercase() == column.to_lowercase())
+                        .map(|n| ColumnName(n.clone()))
+                    {
+                        Some(col) => {
+                            found.push(col);
+                        }
+                        None => {
+                            not_found.push(n.clone());
+                        }
+                    };
+
+                    (found, not_found)
+                },
+            ))
+        }
+
+        async fn check_column_name(
+            &self,
+            table_name: &TableName,
+            column_name: &str,
+        ) -> Result<Option<ColumnName>, Self::Error> {
+            let column_name = column_name.to_lowercase();
+            for column in self.get_columns(table_name).await? {
+                if column.to_lowercase() == column_name {
+                    return Ok(Some(ColumnName(column)));
+                }
+            }
+
+            Ok(None)
+        }
+
+        async fn table_info(&self, table_name: &TableName) -> Result<Arc<TableInfo>, Self::Error> {
+            let cached = self
+                .schema_of(table_name)
+                .cache
+                .read()
+                .expect("schema cache lock poisoned")
+                .get(table_name)
+                .cloned();
+            if let Some(info) = cached {
+                return Ok(info);
+            }
+
+            let pool = self.pool.clone();
+            let TableName(table) = table_name.clone();
+            let schema: BoxStr = table_name.schema().into();
+            let name: BoxStr = table_name.split().1.into();
+            let pragma = table_name.pragma("table_info");
+            let search_indexes = self.search_indexes.clone();
+
+            let info =
+                tokio::task::spawn_blocking(move || -> Result<TableInfo, rusqlite::Error> {
+                    let conn = pool.get().expect("failed to get a connection from pool");
+
+                    let (strict, without_rowid) = conn
+                        .query_row(
+                            "SELECT strict, wr FROM pragma_table_list WHERE schema = ? AND name = ?",
+                            [&schema, &name],
+                            |r| Ok((r.get::<_, bool>(0)?, r.get::<_, bool>(1)?)),
+                        )
+                        .optional()?
+                        .unwrap_or_default();
+
+                    let mut stmt = conn.prepare(&pragma)?;
+                    let mut columns = stmt
+                        .query_map((), |r| {
+                            let declared_type = r.get::<_, BoxStr>(2)?;
+                            Ok(ColumnInfo {
+                                name: ColumnName(r.get(1)?),
+                                typ: str_to_sql_value_type(&declared_type),
+                                affinity: Affinity::from_declared_type(&declared_type),
+                                not_null: r.get(3)?,
+                                default: r.get(4)?,
+                                declared_type,
+                                primary_key: NonZeroU32::new(r.get(5)?),
+                                rowid_alias: false,
+                            })
+                        })?
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    // Only a primary key of a single `INTEGER` column aliases the rowid, composite
+                    // keys and keys of WITHOUT ROWID tables are ordinary columns.
+                    let key_len = columns.iter().filter(|c| c.primary_key.is_some()).count();
+                    for column in columns.iter_mut().filter(|c| c.primary_key.is_some()) {
+                        column.rowid_alias = key_len == 1
+                            && !without_rowid
+                            && column.declared_type.eq_ignore_ascii_case("INTEGER");
+                        // Other primary keys of STRICT and WITHOUT ROWID tables are implicitly
+                        // NOT NULL.
+                        column.not_null |= (strict || without_rowid) && !column.rowid_alias;
+                    }
+
+                    let search_index = match find_search_index(&conn, &table)? {
+                        Some(index) => Some(index),
+                        // Configured indexes are only created by `serve`, but the generated
+                        // clients can already know about them.
+                        None => search_indexes
+                            .iter()
+                            .find(|config| config.table.eq_ignore_ascii_case(&table))
+                            .map(|config| SearchIndex {
+                                name: TableName(config.index_name().into()),
+                                columns: config.columns.iter().cloned().map(ColumnName).collect(),
+                                content_rowid: Some(ColumnName("rowid".into())),
+                            }),
+                    };
+
+                    Ok(TableInfo {
+                        columns: columns.into(),
+                        strict,
+                        search_index,
+                    })
+                })
+                .await
+                .expect("failed to spawn a tokio task")?;
+
+            let info = Arc::new(info);
+            self.schema_of(table_name)
+                .cache
+                .write()
+                .expect("schema cache lock poisoned")
+                .insert(table_name.clone(), info.clone());
+
+            Ok(info)
+        }
+
+        async fn list_rows(
+            &self,
+            table_name: TableName,
+            column_names: Columns,
+            sort_info: Option<(ColumnName, SortOrder)>,
+            page: Option<Pagination>,
+        ) -> Result<BoxList<Row>, Self::Error> {
+            let types = self.get_value_types(&table_name).await?;
+            let sql = list_rows_sql(&table_name, column_names, sort_info, page);
+            let pool = self.pool.clone();
+            let blob_encoding = self.blob_encoding;
+            tokio::task::spawn_blocking(move || -> Result<BoxList<Row>, rusqlite::Error> {
+                let conn = pool.get().expect("failed to get a connection from pool");
                 let mut stmt = conn.prepare(&sql)?;
 
                 let column_names: BoxList<BoxStr> =
@@ -729,18 +3149,7 @@ mod db {
 
                 let rows = stmt
                     .query_map((), |r| {
-                        Ok(column_names
-                            .iter()
-                            .enumerate()
-                            .map(|(i, name)| {
-                                (
-                                    name.clone(),
-                                    rusqlite_to_json(
-                                        r.get_ref(i).expect("failed to get column value").into(),
-                                    ),
-                                )
-                            })
-                            .collect())
+                        Ok(read_row(r, &column_names, &types, blob_encoding))
                     })?
                     .collect::<Result<BoxList<_>, _>>()?;
 
@@ -750,14 +3159,88 @@ mod db {
             .expect("failed to spawn a tokio task")
         }
 
+        async fn stream_rows(
+            &self,
+            table_name: TableName,
+            column_names: Columns,
+            sort_info: Option<(ColumnName, SortOrder)>,
+            page: Option<Pagination>,
+            chunk_size: NonZeroUsize,
+        ) -> Result<BoxStream<'static, Result<BoxList<Row>, Self::Error>>, Self::Error> {
+            let types = self.get_value_types(&table_name).await?;
+            let sql = list_rows_sql(&table_name, column_names, sort_info, page);
+            let pool = self.pool.clone();
+            let blob_encoding = self.blob_encoding;
+            let runtime = tokio::runtime::Handle::current();
+
+            // A single statement reads every chunk, so they all come from one snapshot. It holds
+            // a connection until the last chunk is sent, so a client that stops taking them gets
+            // an error after `CHUNK_SEND_TIMEOUT`. One chunk is buffered and the other slot is
+            // kept for that error.
+            let (tx, rx) = mpsc::channel(2);
+            tokio::task::spawn_blocking(move || {
+                let Ok(last) = runtime.block_on(tx.clone().reserve_owned()) else {
+                    return;
+                };
+
+                let read_chunks = || -> Result<(), rusqlite::Error> {
+                    let conn = pool.get().expect("failed to get a connection from pool");
+                    let mut stmt = conn.prepare(&sql)?;
+
+                    let column_names: BoxList<BoxStr> =
+                        stmt.column_names().into_iter().map(Into::into).collect();
+
+                    let mut rows = stmt.query(())?;
+                    let mut chunk = Vec::with_capacity(chunk_size.get());
+                    loop {
+                        let row = rows.next()?;
+                        if let Some(r) = row {
+                            chunk.push(read_row(r, &column_names, &types, blob_encoding));
+                            if chunk.len() < chunk_size.get() {
+                                continue;
+                            }
+                        }
+                        if chunk.is_empty() {
+                            return Ok(());
+                        }
+
+                        let rows =
+                            std::mem::replace(&mut chunk, Vec::with_capacity(chunk_size.get()));
+                        let sent =
+                            runtime.block_on(tx.send_timeout(Ok(rows.into()), CHUNK_SEND_TIMEOUT));
+                        match sent {
+                            Ok(()) if row.is_some() => {}
+                            Ok(()) => return Ok(()),
+                            // Nobody is listening anymore.
+                            Err(SendTimeoutError::Closed(_)) => return Ok(()),
+                            Err(SendTimeoutError::Timeout(_)) => {
+                                return Err(rusqlite::Error::SqliteFailure(
+                                    rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_INTERRUPT),
+                                    Some("the client stopped reading the rows".into()),
+                                ))
+                            }
+                        }
+                    }
+                };
+
+                if let Err(err) = read_chunks() {
+                    last.send(Err(err));
+                }
+            });
+
+            Ok(ReceiverStream::new(rx).boxed())
+        }
+
         async fn get_row(
             &self,
             table_name: TableName,
-            key: JsonValue,
+            key: SqlValue,
             column_names: Columns,
         ) -> Result<Option<Row>, Self::Error> {
             let ColumnName(primary_key) = self.get_primary_key(&table_name).await?;
+            let types = self.get_value_types(&table_name).await?;
             let pool = self.pool.clone();
+            let blob_encoding = self.blob_encoding;
 
             let result = tokio::task::spawn_blocking(move || -> Result<Row, rusqlite::Error> {
                 let conn = pool.get().expect("failed to get a connection from pool");
@@ -779,20 +3262,8 @@ mod db {
                 let column_names: BoxList<BoxStr> =
                     stmt.column_names().into_iter().map(Into::into).collect();
 
-                let key = json_to_rusqlite(key);
                 let row = stmt.query_row([key], |r| {
-                    Ok(column_names
-                        .iter()
-                        .enumerate()
-                        .map(|(i, name)| {
-                            (
-                                name.clone(),
-                                rusqlite_to_json(
-                                    r.get_ref(i).expect("failed to get column value").into(),
-                                ),
-                            )
-                        })
-                        .collect())
+                    Ok(read_row(r, &column_names, &types, blob_encoding))
                 })?;
 
                 Ok(row)
@@ -807,171 +3278,1216 @@ mod db {
             }
         }
 
-        async fn insert_row(
+        async fn search(
             &self,
-            TableName(table_name): TableName,
-            data: HashMap<ColumnName, serde_json::Value>,
-        ) -> Result<usize, Self::Error> {
+            table_name: TableName,
+            column_names: Columns,
+            query: BoxStr,
+            SearchMarkup { highlight, snippet }: SearchMarkup,
+            page: Option<Pagination>,
+        ) -> Result<Result<BoxList<SearchHit>, BoxStr>, Self::Error> {
+            let info = self.table_info(&table_name).await?;
+            let Some(index) = info.search_index.clone() else {
+                return Ok(Err("the table has no search index".into()));
+            };
+            let types = self.get_value_types(&table_name).await?;
             let pool = self.pool.clone();
+            let blob_encoding = self.blob_encoding;
 
-            tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+            let index_name = index.name.as_str();
+            let (source, from) = match &index.content_rowid {
+                Some(ColumnName(rowid)) => (
+                    table_name.as_str(),
+                    format!("{index_name} JOIN {table_name} ON {table_name}.{rowid} = {index_name}.rowid"),
+                ),
+                None => (index_name, index_name.to_string()),
+            };
+
+            let selects = if column_names.is_empty() {
+                format!("{source}.*")
+            } else {
+                column_names
+                    .iter()
+                    .map(|ColumnName(c)| format!("{source}.{c}"))
+                    .collect::<BoxList<_>>()
+                    .join(",")
+            };
+
+            let mut markup = String::new();
+            let mut params = Vec::new();
+            let highlights = highlight.is_some();
+            if let Some(Highlight { open, close }) = highlight {
+                for i in 0..index.columns.len() {
+                    markup.push_str(&format!(", highlight({index_name}, {i}, ?, ?)"));
+                    params.extend([
+                        SqlValue::Text(open.clone().into()),
+                        SqlValue::Text(close.clone().into()),
+                    ]);
+                }
+            }
+            let snippets = snippet.is_some();
+            if let Some(Snippet {
+                open,
+                close,
+                ellipsis,
+                tokens,
+            }) = snippet
+            {
+                for i in 0..index.columns.len() {
+                    markup.push_str(&format!(", snippet({index_name}, {i}, ?, ?, ?, {tokens})"));
+                    params.extend([
+                        SqlValue::Text(open.clone().into()),
+                        SqlValue::Text(close.clone().into()),
+                        SqlValue::Text(ellipsis.clone().into()),
+                    ]);
+                }
+            }
+            params.push(SqlValue::Text(query.into()));
+
+            let limit = limit_sql(page);
+            let sql = format!(
+                "SELECT {selects}, {index_name}.rank{markup} FROM {from} WHERE {index_name} MATCH ? ORDER BY {index_name}.rank {limit}"
+            );
+
+            tokio::task::spawn_blocking(move || {
                 let conn = pool.get().expect("failed to get a connection from pool");
+                let mut stmt = conn.prepare(&sql)?;
 
-                let columns = data
-                    .keys()
-                    .cloned()
-                    .map(|ColumnName(col)| col)
-                    .collect::<Vec<_>>()
-                    .join(",");
+                let column_names: BoxList<BoxStr> =
+                    stmt.column_names().into_iter().map(Into::into).collect();
 
-                let values = data
-                    .into_values()
-                    .map(json_to_rusqlite)
-                    .map(rusqlite_to_str)
-                    .collect::<Vec<_>>()
-                    .join(",");
+                // The selected columns are followed by the rank, the highlights and the snippets.
+                let indexed = index.columns.len();
+                let rank = column_names.len()
+                    - 1
+                    - indexed * (usize::from(highlights) + usize::from(snippets));
+
+                let hits = stmt
+                    .query_map(params_from_iter(params), |r| {
+                        let read_markup = |start: usize| {
+                            index
+                                .columns
+                                .iter()
+                                .enumerate()
+                                .map(|(i, ColumnName(column))| {
+                                    Ok((column.clone(), r.get::<_, Option<BoxStr>>(start + i)?))
+                                })
+                                .collect::<Result<HashMap<_, _>, rusqlite::Error>>()
+                        };
+                        let snippet_start = rank + 1 + if highlights { indexed } else { 0 };
+
+                        Ok(SearchHit {
+                            row: read_row(r, &column_names[..rank], &types, blob_encoding),
+                            rank: r.get(rank)?,
+                            highlights: highlights.then(|| read_markup(rank + 1)).transpose()?,
+                            snippets: snippets.then(|| read_markup(snippet_start)).transpose()?,
+                        })
+                    })?
+                    .collect::<Result<BoxList<_>, _>>();
 
-                let sql = format!("INSERT INTO {table_name} ({columns}) VALUES ({values})");
-                conn.execute(&sql, ())
+                match hits {
+                    Ok(hits) => Ok(Ok(hits)),
+                    // The statement itself compiled, so errors while running it come from the query.
+                    Err(rusqlite::Error::SqliteFailure(err, message))
+                        if err.code == rusqlite::ErrorCode::Unknown =>
+                    {
+                        Ok(Err(message.unwrap_or_else(|| err.to_string()).into()))
+                    }
+                    Err(err) => Err(err),
+                }
             })
             .await
             .expect("failed to spawn a tokio task")
         }
 
-        async fn delete_row(
+        async fn read_blob(
             &self,
             table_name: TableName,
-            key: JsonValue,
-        ) -> Result<usize, Self::Error> {
+            key: SqlValue,
+            ColumnName(column): ColumnName,
+            offset: usize,
+            length: Option<usize>,
+        ) -> Result<Result<(Vec<u8>, usize), BlobError>, Self::Error> {
             let ColumnName(primary_key) = self.get_primary_key(&table_name).await?;
             let pool = self.pool.clone();
 
-            tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+            tokio::task::spawn_blocking(move || {
                 let conn = pool.get().expect("failed to get a connection from pool");
 
-                let TableName(table_name) = table_name;
-                let sql = format!("DELETE FROM {table_name} WHERE {primary_key} = ?");
+                let rowid =
+                    match blob_rowid(&conn, table_name.as_str(), &primary_key, &column, key)? {
+                        Ok(rowid) => rowid,
+                        Err(err) => return Ok(Err(err)),
+                    };
 
-                let key = json_to_rusqlite(key);
-                conn.execute(&sql, [key])
+                let (schema, table) = table_name.blob_table();
+                let blob = conn.blob_open(schema, table, &column, rowid, true)?;
+                let size = blob.len();
+                if offset > size {
+                    return Ok(Err(BlobError::OutOfBounds { size }));
+                }
+
+                let length = length.unwrap_or(size).min(MAX_BLOB_READ).min(size - offset);
+                let mut data = vec![0; length];
+                blob.read_at_exact(&mut data, offset)?;
+                Ok(Ok((data, size)))
             })
             .await
             .expect("failed to spawn a tokio task")
         }
 
-        async fn update_row(
+        async fn write_blob(
             &self,
             table_name: TableName,
-            key: JsonValue,
-            data: HashMap<ColumnName, serde_json::Value>,
-        ) -> Result<Option<usize>, Self::Error> {
+            key: SqlValue,
+            ColumnName(column): ColumnName,
+            offset: usize,
+            data: Vec<u8>,
+            size: Option<usize>,
+        ) -> Result<Result<usize, BlobError>, Self::Error> {
+            if size.is_some_and(|size| size > MAX_BLOB_SIZE) {
+                return Ok(Err(BlobError::OutOfBounds {
+                    size: MAX_BLOB_SIZE,
+                }));
+            }
+
             let ColumnName(primary_key) = self.get_primary_key(&table_name).await?;
             let pool = self.pool.clone();
 
-            let result = tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
-                let conn = pool.get().expect("failed to get a connection from pool");
+            tokio::task::spawn_blocking(move || {
+                let mut conn = pool.get().expect("failed to get a connection from pool");
+                // Dropping the transaction on an error also undoes the resize.
+                let tx = conn.transaction()?;
+
+                if let Some(size) = size {
+                    let sql = format!(
+                        "UPDATE {table_name} SET {column} = zeroblob(?) WHERE {primary_key} = ?"
+                    );
+                    tx.execute(
+                        &sql,
+                        params_from_iter([SqlValue::Integer(size as i64), key.clone()]),
+                    )?;
+                }
 
-                let updates = data
-                    .into_iter()
-                    .map(|(ColumnName(col), val)| (col, json_to_rusqlite(val)))
-                    .map(|(col, val)| (col, rusqlite_to_str(val)))
-                    .map(|(col, val)| format!("{col} = {val}"))
-                    .collect::<Vec<_>>()
-                    .join(",");
+                let rowid = match blob_rowid(&tx, table_name.as_str(), &primary_key, &column, key)?
+                {
+                    Ok(rowid) => rowid,
+                    Err(err) => return Ok(Err(err)),
+                };
 
-                let TableName(table_name) = table_name;
-                let sql = format!("UPDATE {table_name} SET {updates} WHERE {primary_key} = ?");
+                let (schema, table) = table_name.blob_table();
+                let mut blob = tx.blob_open(schema, table, &column, rowid, false)?;
+                let size = blob.len();
+                if offset.checked_add(data.len()).is_none_or(|end| end > size) {
+                    return Ok(Err(BlobError::OutOfBounds { size }));
+                }
 
-                let key = json_to_rusqlite(key);
-                conn.execute(&sql, [key])
+                blob.write_at(&data, offset)?;
+                blob.close()?;
+                tx.commit()?;
+                Ok(Ok(size))
             })
             .await
-            .expect("failed to spawn a tokio task");
+            .expect("failed to spawn a tokio task")
+        }
 
-            match result {
-                Ok(row) => Ok(Some(row)),
-                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                Err(err) => Err(err),
+        async fn describe_schema(
+            &self,
+        ) -> Result<(BoxList<TableDescription>, BoxList<ViewDescription>), Self::Error> {
+            let mut tables = Vec::new();
+            for table in self.get_user_tables().await? {
+                tables.push(self.describe_table(&table).await?);
+            }
+
+            let mut views = Vec::new();
+            for view in self.get_views().await? {
+                let info = self.table_info(&view).await?;
+                views.push(ViewDescription {
+                    name: view.0,
+                    columns: info.columns.iter().map(ColumnInfo::describe).collect(),
+                });
             }
+
+            Ok((tables.into(), views.into()))
         }
 
-        async fn batch_insert_row(
+        async fn describe_table(
             &self,
-            TableName(table_name): TableName,
-            data: Vec<HashMap<ColumnName, serde_json::Value>>,
-        ) -> Result<usize, Self::Error> {
+            table_name: &TableName,
+        ) -> Result<TableDescription, Self::Error> {
+            let info = self.table_info(table_name).await?;
             let pool = self.pool.clone();
+            let schema: BoxStr = table_name.schema().into();
+            let table: BoxStr = table_name.split().1.into();
 
-            tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+            let (indexes, foreign_keys) = tokio::task::spawn_blocking(move || {
                 let conn = pool.get().expect("failed to get a connection from pool");
 
-                let columns = data[0]
-                    .keys()
-                    .cloned()
-                    .map(|ColumnName(col)| col)
-                    .collect::<Vec<_>>()
-                    .join(",");
+                let indexes: Vec<(BoxStr, bool, BoxStr, bool)> = conn
+                    .prepare(
+                        r#"SELECT name, "unique", origin, partial FROM pragma_index_list(?, ?)"#,
+                    )?
+                    .query_map([&table, &schema], |r| {
+                        Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))
+                    })?
+                    .collect::<Result<_, _>>()?;
 
-                let values = data
+                let mut index_columns =
+                    conn.prepare("SELECT name FROM pragma_index_info(?, ?) ORDER BY seqno")?;
+                let indexes = indexes
                     .into_iter()
-                    .map(|d| {
-                        let v = d
-                            .into_values()
-                            .map(json_to_rusqlite)
-                            .map(rusqlite_to_str)
-                            .collect::<Vec<_>>()
-                            .join(",");
-                        format!("({v})")
-                    })
+                    .map(|(name, unique, origin, partial)| {
+                        let columns = index_columns
+                            .query_map([&name, &schema], |r| r.get(0))?
+                            .collect::<Result<_, _>>()?;
+                        let origin = match &*origin {
+                            "u" => IndexOrigin::Unique,
+                            "pk" => IndexOrigin::PrimaryKey,
+                            _ => IndexOrigin::CreateIndex,
+                        };
+
+                        Ok(IndexDescription {
+                            name,
+                            columns,
+                            unique,
+                            origin,
+                            partial,
+                        })
+                    })
+                    .collect::<Result<BoxList<_>, rusqlite::Error>>()?;
+
+                type ForeignKeyRow = (i64, BoxStr, BoxStr, Option<BoxStr>, BoxStr, BoxStr);
+                let rows: Vec<ForeignKeyRow> = conn
+                    .prepare(
+                        r#"SELECT id, "from", "table", "to", on_update, on_delete
+                           FROM pragma_foreign_key_list(?, ?) ORDER BY id, seq"#,
+                    )?
+                    .query_map([&table, &schema], |r| {
+                        Ok((
+                            r.get(0)?,
+                            r.get(1)?,
+                            r.get(2)?,
+                            r.get(3)?,
+                            r.get(4)?,
+                            r.get(5)?,
+                        ))
+                    })?
+                    .collect::<Result<_, _>>()?;
+
+                // Keys over several columns are a row per column, sharing an id.
+                let foreign_keys = rows
+                    .chunk_by(|a, b| a.0 == b.0)
+                    .map(|key| {
+                        let (_, _, table, _, on_update, on_delete) = &key[0];
+                        ForeignKeyDescription {
+                            columns: key.iter().map(|row| row.1.clone()).collect(),
+                            table: table.clone(),
+                            references: key.iter().map(|row| row.3.clone()).collect(),
+                            on_update: on_update.clone(),
+                            on_delete: on_delete.clone(),
+                        }
+                    })
+                    .collect();
+
+                Ok::<_, rusqlite::Error>((indexes, foreign_keys))
+            })
+            .await
+            .expect("failed to spawn a tokio task")?;
+
+            Ok(TableDescription {
+                name: table_name.0.clone(),
+                columns: info.columns.iter().map(ColumnInfo::describe).collect(),
+                indexes,
+                foreign_keys,
+                strict: info.strict,
+            })
+        }
+
+        async fn insert_row(
+            &self,
+            TableName(table_name): TableName,
+            data: HashMap<ColumnName, SqlValue>,
+        ) -> Result<usize, Self::Error> {
+            let pool = self.pool.clone();
+
+            tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+                let conn = pool.get().expect("failed to get a connection from pool");
+
+                let (columns, values): (Vec<_>, Vec<_>) = data
+                    .into_iter()
+                    .map(|(ColumnName(col), val)| (col, val))
+                    .unzip();
+
+                let placeholders = vec!["?"; values.len()].join(",");
+                let columns = columns.join(",");
+
+                let sql = format!("INSERT INTO {table_name} ({columns}) VALUES ({placeholders})");
+                conn.execute(&sql, params_from_iter(values))
+            })
+            .await
+            .expect("failed to spawn a tokio task")
+        }
+
+        async fn delete_row(
+            &self,
+            table_name: TableName,
+            key: SqlValue,
+        ) -> Result<usize, Self::Error> {
+            let ColumnName(primary_key) = self.get_primary_key(&table_name).await?;
+            let pool = self.pool.clone();
+
+            tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+                let conn = pool.get().expect("failed to get a connection from pool");
+
+                let TableName(table_name) = table_name;
+                let sql = format!("DELETE FROM {table_name} WHERE {primary_key} = ?");
+
+                conn.execute(&sql, [key])
+            })
+            .await
+            .expect("failed to spawn a tokio task")
+        }
+
+        async fn update_row(
+            &self,
+            table_name: TableName,
+            key: SqlValue,
+            data: HashMap<ColumnName, SqlValue>,
+        ) -> Result<Option<usize>, Self::Error> {
+            let ColumnName(primary_key) = self.get_primary_key(&table_name).await?;
+            let pool = self.pool.clone();
+
+            let result = tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+                let conn = pool.get().expect("failed to get a connection from pool");
+
+                let (updates, mut values): (Vec<_>, Vec<_>) = data
+                    .into_iter()
+                    .map(|(ColumnName(col), val)| (format!("{col} = ?"), val))
+                    .unzip();
+                let updates = updates.join(",");
+                values.push(key);
+
+                let TableName(table_name) = table_name;
+                let sql = format!("UPDATE {table_name} SET {updates} WHERE {primary_key} = ?");
+
+                conn.execute(&sql, params_from_iter(values))
+            })
+            .await
+            .expect("failed to spawn a tokio task");
+
+            match result {
+                Ok(row) => Ok(Some(row)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(err) => Err(err),
+            }
+        }
+
+        async fn batch_insert_row(
+            &self,
+            TableName(table_name): TableName,
+            data: Vec<HashMap<ColumnName, SqlValue>>,
+        ) -> Result<usize, Self::Error> {
+            let pool = self.pool.clone();
+
+            tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+                let conn = pool.get().expect("failed to get a connection from pool");
+
+                let columns = data[0].keys().cloned().collect::<Vec<_>>();
+                let placeholders = format!("({})", vec!["?"; columns.len()].join(","));
+                let placeholders = vec![placeholders; data.len()].join(",");
+
+                // Every row has the same columns, bind them in one order.
+                let values = data
+                    .into_iter()
+                    .flat_map(|mut row| {
+                        columns
+                            .iter()
+                            .map(|col| row.remove(col).unwrap_or(SqlValue::Null))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                let columns = columns
+                    .into_iter()
+                    .map(|ColumnName(col)| col)
                     .collect::<Vec<_>>()
                     .join(",");
 
-                let sql = format!("INSERT INTO {table_name} ({columns}) VALUES {values}");
-                conn.execute(&sql, ())
+                let sql = format!("INSERT INTO {table_name} ({columns}) VALUES {placeholders}");
+                conn.execute(&sql, params_from_iter(values))
             })
             .await
             .expect("failed to spawn a tokio task")
         }
+
+        async fn change_schema(
+            &self,
+            change: SchemaChange,
+        ) -> Result<Result<(), BoxStr>, Self::Error> {
+            let sql = match change.to_sql() {
+                Ok(sql) => sql,
+                Err(message) => return Ok(Err(message)),
+            };
+            let pool = self.pool.clone();
+
+            let result = tokio::task::spawn_blocking(move || -> Result<usize, rusqlite::Error> {
+                let conn = pool.get().expect("failed to get a connection from pool");
+                conn.execute(&sql, [])
+            })
+            .await
+            .expect("failed to spawn a tokio task");
+
+            match result {
+                Ok(_) => {}
+                // Mistakes in the definition, like a duplicate name or an existing row
+                // breaking a new unique index, rather than failures of the database.
+                Err(rusqlite::Error::SqliteFailure(err, message))
+                    if matches!(
+                        err.code,
+                        rusqlite::ErrorCode::Unknown | rusqlite::ErrorCode::ConstraintViolation
+                    ) =>
+                {
+                    return Ok(Err(message.unwrap_or_else(|| err.to_string()).into()));
+                }
+                Err(rusqlite::Error::SqlInputError { msg, .. }) => return Ok(Err(msg.into())),
+                Err(err) => return Err(err),
+            }
+
+            let schema = match &change {
+                SchemaChange::CreateTable { .. } => &self.schema,
+                SchemaChange::AddColumn { table, .. }
+                | SchemaChange::CreateIndex { table, .. }
+                | SchemaChange::DropTable { table } => self.schema_of(table),
+            };
+            schema.clear();
+            // Nobody listening is fine.
+            let _ = schema.changes.send(change.to_event());
+
+            Ok(Ok(()))
+        }
+    }
+
+    fn list_rows_sql(
+        TableName(table_name): &TableName,
+        column_names: Columns,
+        sort_info: Option<(ColumnName, SortOrder)>,
+        page: Option<Pagination>,
+    ) -> String {
+        let selects = if column_names.is_empty() {
+            "*".into()
+        } else {
+            column_names
+                .into_iter()
+                .map(|c| c.0)
+                .collect::<BoxList<_>>()
+                .join(",")
+        };
+
+        let sort = sort_info
+            .map(|(ColumnName(col), sort_order)| format!("ORDER BY {col} {sort_order}"))
+            .unwrap_or_default();
+
+        let limit = limit_sql(page);
+
+        format!("SELECT {selects} FROM {table_name} {sort} {limit}")
+    }
+
+    fn limit_sql(page: Option<Pagination>) -> String {
+        page.map(|Pagination { number, size }| {
+            let offset = (number - 1) * size;
+            format!("LIMIT {size} OFFSET {offset}")
+        })
+        .unwrap_or_default()
+    }
+
+    /// The rowid incremental blob I/O needs to open `column` in the row `key` identifies,
+    /// as long as the column holds a blob in that row.
+    fn blob_rowid(
+        conn: &rusqlite::Connection,
+        table: &str,
+        primary_key: &str,
+        column: &str,
+        key: SqlValue,
+    ) -> Result<Result<i64, BlobError>, rusqlite::Error> {
+        let sql = format!("SELECT rowid, typeof({column}) FROM {table} WHERE {primary_key} = ?");
+        let row = conn
+            .query_row(&sql, [key], |r| {
+                Ok((r.get::<_, i64>(0)?, r.get::<_, BoxStr>(1)?))
+            })
+            .optional()?;
+
+        Ok(match row {
+            Some((rowid, typ)) if &*typ == "blob" => Ok(rowid),
+            Some(_) => Err(BlobError::NotABlob),
+            None => Err(BlobError::RowNotFound),
+        })
+    }
+
+    /// The FTS5 table `table` is, or the one indexing its content.
+    fn find_search_index(
+        conn: &rusqlite::Connection,
+        table: &str,
+    ) -> Result<Option<SearchIndex>, rusqlite::Error> {
+        let indexes: Vec<(BoxStr, BoxStr)> = conn
+            .prepare(
+                "SELECT name, sql FROM sqlite_master
+                 WHERE type = 'table' AND sql LIKE 'CREATE VIRTUAL TABLE %USING fts5%'",
+            )?
+            .query_map((), |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+
+        for (name, sql) in indexes {
+            let options = fts5_options(&sql);
+            let content_rowid = if name.eq_ignore_ascii_case(table) {
+                None
+            } else if options
+                .get("content")
+                .is_some_and(|content| content.eq_ignore_ascii_case(table))
+            {
+                let rowid = options.get("content_rowid").map_or("rowid", |c| c);
+                Some(ColumnName(rowid.into()))
+            } else {
+                continue;
+            };
+
+            let columns = conn
+                .prepare(&format!("PRAGMA table_info({name})"))?
+                .query_map((), |r| r.get::<_, BoxStr>(1).map(ColumnName))?
+                .collect::<Result<_, _>>()?;
+
+            return Ok(Some(SearchIndex {
+                name: TableName(name),
+                columns,
+                content_rowid,
+            }));
+        }
+
+        Ok(None)
+    }
+
+    /// The `key=value` options in the arguments of a `CREATE VIRTUAL TABLE ... USING fts5(...)`.
+    fn fts5_options(sql: &str) -> HashMap<String, String> {
+        let arguments = sql
+            .split_once('(')
+            .and_then(|(_, rest)| rest.rsplit_once(')'))
+            .map_or("", |(arguments, _)| arguments);
+
+        arguments
+            .split(',')
+            .filter_map(|argument| argument.split_once('='))
+            .map(|(key, value)| {
+                let value = value.trim().trim_matches(['\'', '"', '`', '[', ']']);
+                (key.trim().to_lowercase(), value.to_owned())
+            })
+            .collect()
+    }
+
+    fn read_row(
+        r: &rusqlite::Row,
+        column_names: &[BoxStr],
+        types: &HashMap<BoxStr, SqlValueType>,
+        blob_encoding: BlobEncoding,
+    ) -> Row {
+        let mut blobs = Vec::new();
+        let values = column_names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let value = r.get_ref(i).expect("failed to get column value");
+                if value.data_type() == Type::Blob {
+                    blobs.push(name.clone());
+                }
+                let value =
+                    rusqlite_to_typed_json(types.get(name).copied(), value.into(), blob_encoding);
+                (name.clone(), value)
+            })
+            .collect();
+
+        Row {
+            values,
+            blobs: blobs.into(),
+        }
     }
 
-    pub fn rusqlite_to_json(v: SqlValue) -> JsonValue {
+    pub fn rusqlite_to_json(v: SqlValue, blob: BlobEncoding) -> JsonValue {
         match v {
             SqlValue::Null => JsonValue::Null,
             SqlValue::Integer(x) => serde_json::json!(x),
             SqlValue::Real(x) => serde_json::json!(x),
             SqlValue::Text(s) => JsonValue::String(s),
-            SqlValue::Blob(s) => serde_json::json!(s),
+            SqlValue::Blob(s) => blob.encode(&s),
         }
     }
 
-    pub fn rusqlite_to_str(v: SqlValue) -> BoxStr {
-        match v {
-            SqlValue::Null => Box::from("null"),
-            SqlValue::Integer(x) => x.to_string().into(),
-            SqlValue::Real(x) => x.to_string().into(),
-            SqlValue::Text(s) => format!("'{s}'").into(),
-            SqlValue::Blob(s) => format!("X'{}'", hex::encode(s)).into(),
+    /// Like [`rusqlite_to_json`], but returns booleans and json documents for columns of those types.
+    pub fn rusqlite_to_typed_json(
+        typ: Option<SqlValueType>,
+        v: SqlValue,
+        blob: BlobEncoding,
+    ) -> JsonValue {
+        match (typ, v) {
+            (Some(SqlValueType::Boolean), SqlValue::Integer(x)) => JsonValue::Bool(x != 0),
+            (Some(SqlValueType::Json), SqlValue::Text(s)) => {
+                serde_json::from_str(&s).unwrap_or(JsonValue::String(s))
+            }
+            (_, v) => rusqlite_to_json(v, blob),
         }
     }
 
-    pub fn json_to_rusqlite(v: JsonValue) -> SqlValue {
+    #[derive(Debug, Error)]
+    pub enum InvalidValue {
+        #[error("number {0} can not be stored in sqlite")]
+        UnrepresentableNumber(serde_json::Number),
+        #[error("arrays and objects can only be stored in json columns")]
+        NotAJsonColumn,
+        #[error("expected a {}", .0.description())]
+        InvalidBlob(BlobEncoding),
+    }
+
+    /// Converts a json value for a column of type `typ`. Booleans are stored as 0/1,
+    /// arrays or objects as json text, when the column is a json column, and blob
+    /// columns decode strings in the `blob` encoding or arrays of bytes.
+    pub fn json_to_rusqlite(
+        v: JsonValue,
+        typ: Option<SqlValueType>,
+        blob: BlobEncoding,
+    ) -> Result<SqlValue, InvalidValue> {
+        let is_encoded_blob = match &v {
+            JsonValue::String(_) => blob != BlobEncoding::Array,
+            JsonValue::Array(_) => true,
+            _ => false,
+        };
+        if typ == Some(SqlValueType::Blob) && is_encoded_blob {
+            return blob
+                .decode(&v)
+                .map(SqlValue::Blob)
+                .ok_or(InvalidValue::InvalidBlob(blob));
+        }
+
         match v {
-            JsonValue::Null => SqlValue::Null,
+            JsonValue::Null => Ok(SqlValue::Null),
+            JsonValue::Bool(b) => Ok(SqlValue::Integer(b.into())),
             JsonValue::Number(x) => {
                 if let Some(x) = x.as_i64() {
-                    return SqlValue::Integer(x);
+                    return Ok(SqlValue::Integer(x));
                 }
-                if let Some(x) = x.as_f64() {
-                    return SqlValue::Real(x);
+                match x.as_f64() {
+                    Some(f) if f.is_finite() => Ok(SqlValue::Real(f)),
+                    _ => Err(InvalidValue::UnrepresentableNumber(x)),
                 }
+            }
+            JsonValue::String(s) => Ok(SqlValue::Text(s)),
+            v @ (JsonValue::Array(_) | JsonValue::Object(_)) => match typ {
+                Some(SqlValueType::Json) => Ok(SqlValue::Text(v.to_string())),
+                _ => Err(InvalidValue::NotAJsonColumn),
+            },
+        }
+    }
 
-                panic!("json number value {x} can not be transformed into a sqlite number value");
+    #[cfg(test)]
+    mod tests {
+        use serde_json::json;
+
+        use super::*;
+
+        fn column(declared_type: &str, not_null: bool) -> ColumnInfo {
+            ColumnInfo {
+                name: ColumnName("column".into()),
+                declared_type: declared_type.into(),
+                typ: str_to_sql_value_type(declared_type),
+                affinity: Affinity::from_declared_type(declared_type),
+                not_null,
+                default: None,
+                primary_key: None,
+                rowid_alias: false,
             }
-            JsonValue::String(s) => SqlValue::Text(s),
-            _ => {
-                panic!("expected json key to be number or string");
+        }
+
+        #[test]
+        fn follows_sqlites_affinity_rules() {
+            for (declared_type, affinity) in [
+                ("INTEGER", Affinity::Integer),
+                ("bigint", Affinity::Integer),
+                ("UNSIGNED BIG INT", Affinity::Integer),
+                // `INT` wins over `POINT`'s other letters, as in SQLite.
+                ("FLOATING POINT", Affinity::Integer),
+                ("VARCHAR(255)", Affinity::Text),
+                ("nvarchar(40)", Affinity::Text),
+                ("CLOB", Affinity::Text),
+                ("", Affinity::Blob),
+                ("BLOB", Affinity::Blob),
+                ("REAL", Affinity::Real),
+                ("double precision", Affinity::Real),
+                ("FLOAT", Affinity::Real),
+                ("NUMERIC", Affinity::Numeric),
+                ("DECIMAL(10,5)", Affinity::Numeric),
+                ("BOOLEAN", Affinity::Numeric),
+                ("DATETIME", Affinity::Numeric),
+                ("STRING", Affinity::Numeric),
+            ] {
+                assert_eq!(
+                    Affinity::from_declared_type(declared_type),
+                    affinity,
+                    "{declared_type}"
+                );
+            }
+        }
+
+        #[test]
+        fn maps_declared_types_to_logical_types() {
+            for (declared_type, typ) in [
+                ("boolean", SqlValueType::Boolean),
+                ("BOOL", SqlValueType::Boolean),
+                ("DATE", SqlValueType::Date),
+                ("datetime", SqlValueType::DateTime),
+                ("TIMESTAMP", SqlValueType::DateTime),
+                ("DECIMAL", SqlValueType::Decimal),
+                ("decimal(10, 2)", SqlValueType::Decimal),
+                ("NUMERIC(10,2)", SqlValueType::Decimal),
+                ("NUMERIC", SqlValueType::Numeric),
+                ("JSON", SqlValueType::Json),
+                ("jsonb", SqlValueType::Json),
+                ("", SqlValueType::Text),
+                ("INTEGER", SqlValueType::Integer),
+                ("VARCHAR(40)", SqlValueType::Text),
+                ("BLOB", SqlValueType::Blob),
+                ("DOUBLE PRECISION", SqlValueType::Real),
+                ("ANY", SqlValueType::Numeric),
+            ] {
+                assert_eq!(str_to_sql_value_type(declared_type), typ, "{declared_type}");
+            }
+        }
+
+        #[test]
+        fn booleans_go_in_numeric_columns() {
+            for declared_type in ["INTEGER", "REAL", "NUMERIC", "DECIMAL(10,2)", "BOOLEAN"] {
+                let column = column(declared_type, false);
+                assert_eq!(column.check(&json!(true), false), Ok(()), "{declared_type}");
+                assert_eq!(column.check(&json!(false), true), Ok(()), "{declared_type}");
+            }
+
+            assert_eq!(
+                column("TEXT", false).check(&json!(true), false),
+                Err("expected a string")
+            );
+            assert_eq!(
+                column("BLOB", false).check(&json!(false), false),
+                Err("expected a blob")
+            );
+        }
+
+        #[test]
+        fn booleans_are_stored_as_integers() {
+            let value = json_to_rusqlite(
+                json!(true),
+                Some(SqlValueType::Integer),
+                BlobEncoding::Base64,
+            );
+            assert_eq!(value.unwrap(), SqlValue::Integer(1));
+            let value = json_to_rusqlite(json!(false), None, BlobEncoding::Base64);
+            assert_eq!(value.unwrap(), SqlValue::Integer(0));
+        }
+
+        #[test]
+        fn checks_nulls() {
+            assert_eq!(
+                column("TEXT", true).check(&json!(null), false),
+                Err("must not be null")
+            );
+            assert_eq!(column("TEXT", false).check(&json!(null), false), Ok(()));
+
+            let mut rowid = column("INTEGER", true);
+            rowid.primary_key = NonZeroU32::new(1);
+            rowid.rowid_alias = true;
+            assert_eq!(rowid.check(&json!(null), true), Ok(()));
+        }
+
+        #[test]
+        fn checks_boolean_columns() {
+            let column = column("BOOLEAN", false);
+            assert_eq!(column.check(&json!(1), false), Ok(()));
+            assert_eq!(column.check(&json!(2), false), Err("expected a boolean"));
+            assert_eq!(
+                column.check(&json!("yes"), false),
+                Err("expected a boolean")
+            );
+        }
+
+        #[test]
+        fn checks_numbers_by_affinity() {
+            assert_eq!(column("INTEGER", false).check(&json!("12"), false), Ok(()));
+            assert_eq!(
+                column("INTEGER", false).check(&json!("twelve"), false),
+                Err("expected a number")
+            );
+            assert_eq!(column("INTEGER", false).check(&json!(1.5), false), Ok(()));
+            assert_eq!(
+                column("INTEGER", false).check(&json!(1.5), true),
+                Err("expected an integer")
+            );
+            assert_eq!(column("INTEGER", false).check(&json!(2.0), true), Ok(()));
+            assert_eq!(
+                column("INT", false).check(&json!("1.5"), true),
+                Err("expected an integer")
+            );
+            assert_eq!(column("REAL", false).check(&json!(" 1e3 "), false), Ok(()));
+            assert_eq!(column("TEXT", false).check(&json!(12), false), Ok(()));
+        }
+
+        #[test]
+        fn checks_everything_else() {
+            assert_eq!(
+                column("TEXT", false).check(&json!([1]), false),
+                Err("expected a scalar value")
+            );
+            assert_eq!(column("JSON", false).check(&json!({"a": 1}), false), Ok(()));
+            assert_eq!(column("BLOB", false).check(&json!([1, 2]), false), Ok(()));
+            assert_eq!(column("BLOB", false).check(&json!(1), false), Ok(()));
+            assert_eq!(
+                column("BLOB", false).check(&json!(1), true),
+                Err("expected a blob")
+            );
+            assert_eq!(column("ANY", false).check(&json!("x"), true), Ok(()));
+            assert_eq!(
+                column("DATE", false).check(&json!("2024-01-02"), true),
+                Ok(())
+            );
+        }
+
+        #[test]
+        fn parses_pragmas() {
+            let pragma: Pragma = "mmap_size = 268435456".parse().unwrap();
+            assert_eq!(pragma.schema, None);
+            assert_eq!(&*pragma.name, "mmap_size");
+            assert_eq!(pragma.value, SqlValue::Integer(268435456));
+
+            let pragma: Pragma = "archive.cache_size=-2000".parse().unwrap();
+            assert_eq!(pragma.schema.as_deref(), Some("archive"));
+            assert_eq!(&*pragma.name, "cache_size");
+            assert_eq!(pragma.value, SqlValue::Integer(-2000));
+
+            let pragma: Pragma = "temp_store=memory".parse().unwrap();
+            assert_eq!(pragma.value, SqlValue::Text("memory".into()));
+
+            assert!("mmap_size".parse::<Pragma>().is_err());
+            assert!("mmap size=1".parse::<Pragma>().is_err());
+            assert!("main;drop.x=1".parse::<Pragma>().is_err());
+            assert!("=1".parse::<Pragma>().is_err());
+        }
+
+        #[test]
+        fn parses_journal_modes() {
+            for mode in JournalMode::value_variants() {
+                let name = mode.as_str();
+                assert_eq!(JournalMode::from_str(name, true), Ok(*mode));
+                assert_eq!(
+                    JournalMode::from_str(&name.to_lowercase(), false),
+                    Ok(*mode)
+                );
+            }
+            assert!(JournalMode::from_str("wal2", true).is_err());
+        }
+
+        #[test]
+        fn parses_database_sources() {
+            let source: DatabaseSource = "archive = /data/archive.db".parse().unwrap();
+            assert_eq!(source.name.as_deref(), Some("archive"));
+            assert_eq!(&*source.path, "/data/archive.db");
+
+            let source: DatabaseSource = " ./sample.sqlite3 ".parse().unwrap();
+            assert_eq!(source.name, None);
+            assert_eq!(&*source.path, "./sample.sqlite3");
+
+            assert!("archive=".parse::<DatabaseSource>().is_err());
+            assert!("".parse::<DatabaseSource>().is_err());
+            assert!("my archive=a.db".parse::<DatabaseSource>().is_err());
+        }
+
+        #[test]
+        fn names_databases_after_their_file() {
+            assert_eq!(&*database_name(Path::new("/data/sample.sqlite3")), "sample");
+            assert_eq!(&*database_name(Path::new("my-shop.v2.db")), "my_shop_v2");
+            assert_eq!(&*database_name(Path::new("2024.db")), "_2024");
+        }
+    }
+}
+
+mod migrations {
+    use std::{collections::HashMap, path::Path};
+
+    use color_eyre::eyre::{self, Context};
+    use sha2::{Digest, Sha256};
+
+    use crate::{db::SqliteDatabase, BoxStr};
+
+    /// A `<version>_<name>.sql` file from the migrations directory.
+    #[derive(Debug)]
+    pub struct Migration {
+        pub version: i64,
+        /// The file name without its extension.
+        pub name: BoxStr,
+        pub sql: String,
+        /// Hex encoded SHA-256 of `sql`.
+        pub checksum: BoxStr,
+    }
+
+    /// A row of the bookkeeping table.
+    #[derive(Debug)]
+    pub struct AppliedMigration {
+        pub version: i64,
+        pub name: BoxStr,
+        pub checksum: BoxStr,
+        pub applied_at: BoxStr,
+    }
+
+    /// Reads the migrations in `dir`, ordered by version. Files that don't end in `.sql`
+    /// are ignored.
+    pub fn read_migrations(dir: &Path) -> eyre::Result<Vec<Migration>> {
+        let mut migrations = Vec::new();
+
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("failed to read migrations directory {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "sql") {
+                continue;
+            }
+
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default();
+            let version = stem.split_once('_').map_or(stem, |(version, _)| version);
+            let version = version.parse().map_err(|_| {
+                eyre::eyre!(
+                    "migration file names must start with a version number: {}",
+                    path.display()
+                )
+            })?;
+
+            let sql = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read migration {}", path.display()))?;
+            let checksum = hex::encode(Sha256::digest(&sql)).into();
+
+            migrations.push(Migration {
+                version,
+                name: stem.into(),
+                sql,
+                checksum,
+            });
+        }
+
+        migrations.sort_by_key(|migration| migration.version);
+        if let Some(pair) = migrations
+            .windows(2)
+            .find(|pair| pair[0].version == pair[1].version)
+        {
+            eyre::bail!(
+                "migrations {} and {} have the same version",
+                pair[0].name,
+                pair[1].name
+            );
+        }
+
+        Ok(migrations)
+    }
+
+    /// Applies the migrations that haven't been applied yet, in a single transaction.
+    /// A dry run applies them and rolls back, so broken migrations are still reported.
+    pub async fn migrate(db: &SqliteDatabase, dir: &Path, dry_run: bool) -> eyre::Result<()> {
+        let migrations = read_migrations(dir)?;
+        let applied = db
+            .applied_migrations()
+            .await?
+            .into_iter()
+            .map(|applied| (applied.version, applied))
+            .collect::<HashMap<_, _>>();
+
+        let mut pending = Vec::new();
+        for migration in migrations {
+            match applied.get(&migration.version) {
+                Some(applied) if applied.checksum != migration.checksum => eyre::bail!(
+                    "migration {} was changed after it was applied",
+                    migration.name
+                ),
+                Some(_) => {}
+                None => pending.push(migration),
+            }
+        }
+
+        if pending.is_empty() {
+            tracing::info!("no pending migrations");
+            return Ok(());
+        }
+
+        let count = pending.len();
+        for migration in pending.iter() {
+            tracing::info!("applying migration {}", migration.name);
+        }
+        db.apply_migrations(pending, dry_run).await?;
+
+        if dry_run {
+            tracing::info!("dry run, rolled back {count} migrations");
+        } else {
+            tracing::info!("applied {count} migrations");
+        }
+        Ok(())
+    }
+
+    /// Prints every migration, in the directory or the database, and whether it was applied.
+    pub async fn status(db: &SqliteDatabase, dir: &Path) -> eyre::Result<()> {
+        let migrations = read_migrations(dir)?;
+        let mut applied = db
+            .applied_migrations()
+            .await?
+            .into_iter()
+            .map(|applied| (applied.version, applied))
+            .collect::<HashMap<_, _>>();
+
+        let mut rows = Vec::with_capacity(migrations.len());
+        for migration in migrations {
+            let status = match applied.remove(&migration.version) {
+                Some(applied) if applied.checksum != migration.checksum => {
+                    format!("modified, applied at {}", applied.applied_at)
+                }
+                Some(applied) => format!("applied at {}", applied.applied_at),
+                None => "pending".into(),
+            };
+            rows.push((migration.version, migration.name, status));
+        }
+        // Applied migrations whose files were since removed.
+        for (version, applied) in applied {
+            let status = format!("missing, applied at {}", applied.applied_at);
+            rows.push((version, applied.name, status));
+        }
+        rows.sort_by_key(|(version, _, _)| *version);
+
+        if rows.is_empty() {
+            println!("no migrations in {}", dir.display());
+        }
+        for (_, name, status) in rows {
+            println!("{name}: {status}");
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::db::BlobEncoding;
+
+        /// An empty directory of its own for every test, removed when dropped.
+        struct TempDir(PathBuf);
+
+        impl TempDir {
+            fn new(name: &str) -> Self {
+                let dir = std::env::temp_dir()
+                    .join(format!("jabroni-migrations-{name}-{}", std::process::id()));
+                std::fs::remove_dir_all(&dir).ok();
+                std::fs::create_dir_all(dir.join("migrations")).unwrap();
+                Self(dir)
+            }
+
+            fn migrations(&self) -> PathBuf {
+                self.0.join("migrations")
+            }
+
+            fn write(&self, file: &str, sql: &str) {
+                std::fs::write(self.migrations().join(file), sql).unwrap();
+            }
+
+            async fn open(&self) -> SqliteDatabase {
+                let path = self.0.join("db.sqlite3").to_string_lossy().into();
+                SqliteDatabase::new(
+                    path,
+                    Default::default(),
+                    Default::default(),
+                    BlobEncoding::default(),
+                    Vec::new(),
+                )
+                .await
+                .unwrap()
+            }
+        }
+
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                std::fs::remove_dir_all(&self.0).ok();
             }
         }
+
+        #[test]
+        fn reads_migrations_in_version_order() {
+            let dir = TempDir::new("order");
+            dir.write("10_add_index.sql", "CREATE INDEX a_b ON a(b);");
+            dir.write("2_create.sql", "CREATE TABLE a(b);");
+            dir.write("0001_init.sql", "SELECT 1;");
+            dir.write("README.md", "not a migration");
+
+            let migrations = read_migrations(&dir.migrations()).unwrap();
+            let names: Vec<_> = migrations.iter().map(|m| &*m.name).collect();
+            assert_eq!(names, ["0001_init", "2_create", "10_add_index"]);
+            assert_eq!(migrations[0].version, 1);
+            assert_eq!(
+                &*migrations[0].checksum,
+                hex::encode(Sha256::digest("SELECT 1;"))
+            );
+        }
+
+        #[test]
+        fn rejects_bad_migration_names() {
+            let dir = TempDir::new("duplicate");
+            dir.write("1_a.sql", "");
+            dir.write("01_b.sql", "");
+            let err = read_migrations(&dir.migrations()).unwrap_err();
+            assert!(err.to_string().contains("same version"), "{err}");
+
+            let dir = TempDir::new("unnumbered");
+            dir.write("init.sql", "");
+            let err = read_migrations(&dir.migrations()).unwrap_err();
+            assert!(err.to_string().contains("version number"), "{err}");
+        }
+
+        #[tokio::test]
+        async fn applies_pending_migrations_once() {
+            let dir = TempDir::new("apply");
+            dir.write("1_create.sql", "CREATE TABLE a(b);");
+            let db = dir.open().await;
+
+            migrate(&db, &dir.migrations(), true).await.unwrap();
+            assert!(db.applied_migrations().await.unwrap().is_empty());
+
+            migrate(&db, &dir.migrations(), false).await.unwrap();
+            dir.write("2_insert.sql", "INSERT INTO a VALUES (1);");
+            migrate(&db, &dir.migrations(), false).await.unwrap();
+            // Nothing is pending, the table would otherwise be created twice.
+            migrate(&db, &dir.migrations(), false).await.unwrap();
+
+            let applied = db.applied_migrations().await.unwrap();
+            let versions: Vec<_> = applied.iter().map(|m| m.version).collect();
+            assert_eq!(versions, [1, 2]);
+            assert_eq!(
+                &*applied[1].checksum,
+                hex::encode(Sha256::digest("INSERT INTO a VALUES (1);"))
+            );
+        }
+
+        #[tokio::test]
+        async fn refuses_migrations_changed_after_they_were_applied() {
+            let dir = TempDir::new("changed");
+            dir.write("1_create.sql", "CREATE TABLE a(b);");
+            let db = dir.open().await;
+            migrate(&db, &dir.migrations(), false).await.unwrap();
+
+            dir.write("1_create.sql", "CREATE TABLE a(b, c);");
+            let err = migrate(&db, &dir.migrations(), false).await.unwrap_err();
+            assert!(
+                err.to_string().contains("changed after it was applied"),
+                "{err}"
+            );
+        }
     }
 }
 
@@ -981,23 +4497,35 @@ mod app {
         task::{Context, Poll},
     };
 
-    use futures::future;
+    use futures::{
+        future,
+        stream::{self, BoxStream},
+        StreamExt,
+    };
     use serde::Serialize;
     use thiserror::Error;
     use tower::Service;
 
+    use serde_json::Value as JsonValue;
+
     use crate::{
-        db::Database,
-        requests::ApiRequest,
+        db::{
+            json_to_rusqlite, BlobEncoding, BlobError, ColumnName, Database, InvalidValue,
+            SchemaChange, SearchMarkup, SqlValue, SqlValueType, TableInfo,
+        },
+        requests::{ApiRequest, RoutedRequest},
         responses::{
-            ApiResponse, DeleteRowResponse, GetRowResponse, InsertRowResponse, ListRowsResponse,
-            UpdateRowResponse,
+            ApiResponse, DeleteRowResponse, DescribeSchemaResponse, DescribeTableResponse,
+            GetRowResponse, InsertRowResponse, ListRowsEndResponse, ListRowsResponse,
+            ReadBlobResponse, Row, SchemaChangeResponse, SearchResponse, UpdateRowResponse,
+            WriteBlobResponse,
         },
         BoxList, BoxStr,
     };
 
     pub struct App<DB: Database> {
         db: DB,
+        admin: bool,
     }
 
     #[derive(Debug, Error, Serialize)]
@@ -1023,11 +4551,188 @@ mod app {
         BatchInsertWithNoData,
         #[error("batch insert must all have the same columns")]
         BatchInsertWithIrregularColumns,
+        #[error("validation failed: {errors:?}")]
+        ValidationFailed { errors: Vec<InvalidColumn> },
+        #[error("invalid value: {message}")]
+        InvalidValue {
+            /// Missing when the value is a row key.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            column: Option<BoxStr>,
+            message: BoxStr,
+        },
+        #[error("table has no search index: {table}")]
+        TableNotSearchable { table: BoxStr },
+        #[error("invalid search: {message}")]
+        InvalidSearch { message: BoxStr },
+        #[error("column does not hold a blob: {column}")]
+        NotABlob { column: BoxStr },
+        #[error("blob range is out of bounds, the blob is {size} bytes long")]
+        BlobOutOfBounds { size: usize },
+        #[error("only admins can change the schema")]
+        Forbidden,
+        #[error("invalid schema change: {message}")]
+        InvalidSchemaChange { message: BoxStr },
+        #[error("database not found: {database}")]
+        DatabaseNotFound { database: BoxStr },
     }
 
-    impl<DB: Database> App<DB> {
-        pub const fn new(db: DB) -> Self {
-            Self { db }
+    #[derive(Debug, Serialize)]
+    pub struct InvalidColumn {
+        pub column: BoxStr,
+        /// Index of the offending row in a batch insert.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub row: Option<usize>,
+        pub message: BoxStr,
+    }
+
+    /// Checks `data` against the table's columns. Inserts also have to provide
+    /// every `NOT NULL` column that has no default.
+    fn validate_row(
+        info: &TableInfo,
+        data: &HashMap<ColumnName, JsonValue>,
+        row: Option<usize>,
+        is_insert: bool,
+        errors: &mut Vec<InvalidColumn>,
+    ) {
+        for column in info.columns.iter() {
+            let message = match data.get(&column.name) {
+                Some(value) => column.check(value, info.strict).err(),
+                None if is_insert && column.is_required() => Some("missing required value"),
+                None => None,
+            };
+
+            if let Some(message) = message {
+                errors.push(InvalidColumn {
+                    column: column.name.as_str().into(),
+                    row,
+                    message: message.into(),
+                });
+            }
+        }
+    }
+
+    fn to_sql_row<E: std::error::Error>(
+        info: &TableInfo,
+        row: HashMap<ColumnName, JsonValue>,
+        blob: BlobEncoding,
+    ) -> Result<HashMap<ColumnName, SqlValue>, AppError<E>> {
+        row.into_iter()
+            .map(|(name, value)| {
+                let typ = info
+                    .columns
+                    .iter()
+                    .find(|column| column.name == name)
+                    .map(|column| column.typ);
+
+                match json_to_rusqlite(value, typ, blob) {
+                    Ok(value) => Ok((name, value)),
+                    Err(err) => Err(AppError::InvalidValue {
+                        column: Some(name.as_str().into()),
+                        message: err.to_string().into(),
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    fn to_sql_key<E: std::error::Error>(
+        info: &TableInfo,
+        key: JsonValue,
+        blob: BlobEncoding,
+    ) -> Result<SqlValue, AppError<E>> {
+        let typ = info
+            .columns
+            .iter()
+            .find(|column| column.primary_key.is_some())
+            .map(|column| column.typ);
+
+        json_to_rusqlite(key, typ, blob).map_err(|err| AppError::InvalidValue {
+            column: None,
+            message: err.to_string().into(),
+        })
+    }
+
+    fn blob_columns(info: &TableInfo) -> BoxList<BoxStr> {
+        info.columns
+            .iter()
+            .filter(|column| column.typ == SqlValueType::Blob)
+            .map(|column| column.name.as_str().into())
+            .collect()
+    }
+
+    /// Incremental blob I/O is only offered on blob columns, so the bytes keep their encoding.
+    fn check_blob_column<E: std::error::Error>(
+        info: &TableInfo,
+        column: &ColumnName,
+    ) -> Result<(), AppError<E>> {
+        match info.columns.iter().find(|c| c.name == *column) {
+            Some(c) if c.typ == SqlValueType::Blob => Ok(()),
+            _ => Err(AppError::NotABlob {
+                column: column.as_str().into(),
+            }),
+        }
+    }
+
+    fn from_blob_error<E: std::error::Error>(err: BlobError, column: BoxStr) -> AppError<E> {
+        match err {
+            BlobError::RowNotFound => AppError::RowNotFound,
+            BlobError::NotABlob => AppError::NotABlob { column },
+            BlobError::OutOfBounds { size } => AppError::BlobOutOfBounds { size },
+        }
+    }
+
+    /// Turns chunks of rows into `ListRowsChunk` responses followed by a `ListRowsEnd`,
+    /// stopping at the first error.
+    fn stream_chunks<E>(
+        chunks: BoxStream<'static, Result<BoxList<Row>, E>>,
+        table: BoxStr,
+        request_id: BoxStr,
+        blob_columns: BoxList<BoxStr>,
+    ) -> BoxStream<'static, Result<ApiResponse, AppError<E>>>
+    where
+        E: std::error::Error + Send + 'static,
+    {
+        stream::unfold(Some((chunks, 0)), move |state| {
+            let table = table.clone();
+            let request_id = request_id.clone();
+            let blob_columns = blob_columns.clone();
+
+            async move {
+                let (mut chunks, total_rows) = state?;
+                let response = match chunks.next().await {
+                    Some(Ok(rows)) => {
+                        let total_rows = total_rows + rows.len();
+                        let response = ApiResponse::ListRowsChunk(ListRowsResponse {
+                            table,
+                            rows,
+                            request_id,
+                            blob_columns,
+                        });
+                        return Some((Ok(response), Some((chunks, total_rows))));
+                    }
+                    Some(Err(err)) => Err(AppError::from(err)),
+                    None => Ok(ApiResponse::ListRowsEnd(ListRowsEndResponse {
+                        table,
+                        total_rows,
+                        request_id,
+                    })),
+                };
+
+                Some((response, None))
+            }
+        })
+        .boxed()
+    }
+
+    impl<DB: Database> App<DB> {
+        pub const fn new(db: DB) -> Self {
+            Self { db, admin: false }
+        }
+
+        /// Allows requests that change the schema.
+        pub const fn with_admin(mut self, admin: bool) -> Self {
+            self.admin = admin;
+            self
         }
     }
 
@@ -1036,7 +4741,7 @@ mod app {
         DB: Database,
         AppError<DB::Error>: From<DB::Error>,
     {
-        type Response = ApiResponse;
+        type Response = BoxStream<'static, Result<ApiResponse, Self::Error>>;
         type Error = AppError<DB::Error>;
         type Future = future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -1046,6 +4751,7 @@ mod app {
 
         fn call(&mut self, request: ApiRequest) -> Self::Future {
             let db = self.db.clone();
+            let admin = self.admin;
 
             Box::pin(async move {
                 let response = match request {
@@ -1081,6 +4787,25 @@ mod app {
                             return Err(Self::Error::PageNumberCanNotBeZero);
                         }
 
+                        let info = db.table_info(&table_name).await?;
+                        if let Some(chunk_size) = req.chunk_size {
+                            let chunks = db
+                                .stream_rows(
+                                    table_name,
+                                    found_columns,
+                                    sort_info,
+                                    req.page,
+                                    chunk_size,
+                                )
+                                .await?;
+                            return Ok(stream_chunks(
+                                chunks,
+                                req.table,
+                                req.request_id,
+                                blob_columns(&info),
+                            ));
+                        }
+
                         let rows = db
                             .list_rows(table_name, found_columns, sort_info, req.page)
                             .await?;
@@ -1088,6 +4813,7 @@ mod app {
                             table: req.table,
                             rows,
                             request_id: req.request_id,
+                            blob_columns: blob_columns(&info),
                         })
                     }
                     ApiRequest::GetRow(req) => {
@@ -1106,14 +4832,17 @@ mod app {
                             });
                         }
 
+                        let info = db.table_info(&table_name).await?;
+                        let key = to_sql_key(&info, req.key, db.blob_encoding())?;
                         let row = db
-                            .get_row(table_name, req.key, found_columns)
+                            .get_row(table_name, key, found_columns)
                             .await?
                             .ok_or(Self::Error::RowNotFound)?;
                         ApiResponse::GetRow(GetRowResponse {
                             table: req.table,
                             row,
                             request_id: req.request_id,
+                            blob_columns: blob_columns(&info),
                         })
                     }
                     ApiRequest::InsertRow(req) => {
@@ -1145,6 +4874,14 @@ mod app {
                             })
                             .collect();
 
+                        let info = db.table_info(&table_name).await?;
+                        let mut errors = Vec::new();
+                        validate_row(&info, &row, None, true, &mut errors);
+                        if !errors.is_empty() {
+                            return Err(Self::Error::ValidationFailed { errors });
+                        }
+
+                        let row = to_sql_row(&info, row, db.blob_encoding())?;
                         let inserted_rows = db.insert_row(table_name, row).await?;
                         ApiResponse::InsertRow(InsertRowResponse {
                             table: req.table,
@@ -1159,7 +4896,9 @@ mod app {
                             },
                         )?;
 
-                        let deleted_rows = db.delete_row(table_name, req.key).await?;
+                        let info = db.table_info(&table_name).await?;
+                        let key = to_sql_key(&info, req.key, db.blob_encoding())?;
+                        let deleted_rows = db.delete_row(table_name, key).await?;
                         ApiResponse::DeleteRow(DeleteRowResponse {
                             table: req.table,
                             deleted_rows,
@@ -1195,8 +4934,17 @@ mod app {
                             })
                             .collect();
 
+                        let info = db.table_info(&table_name).await?;
+                        let mut errors = Vec::new();
+                        validate_row(&info, &row, None, false, &mut errors);
+                        if !errors.is_empty() {
+                            return Err(Self::Error::ValidationFailed { errors });
+                        }
+
+                        let row = to_sql_row(&info, row, db.blob_encoding())?;
+                        let key = to_sql_key(&info, req.key, db.blob_encoding())?;
                         let updated_rows = db
-                            .update_row(table_name, req.key, row)
+                            .update_row(table_name, key, row)
                             .await?
                             .ok_or(Self::Error::RowNotFound)?;
 
@@ -1253,6 +5001,19 @@ mod app {
                             return Err(Self::Error::BatchInsertWithIrregularColumns);
                         }
 
+                        let info = db.table_info(&table_name).await?;
+                        let mut errors = Vec::new();
+                        for (i, row) in rows.iter().enumerate() {
+                            validate_row(&info, row, Some(i), true, &mut errors);
+                        }
+                        if !errors.is_empty() {
+                            return Err(Self::Error::ValidationFailed { errors });
+                        }
+
+                        let rows = rows
+                            .into_iter()
+                            .map(|row| to_sql_row(&info, row, db.blob_encoding()))
+                            .collect::<Result<_, _>>()?;
                         let inserted_rows = db.batch_insert_row(table_name, rows).await?;
                         ApiResponse::BatchInsertRow(InsertRowResponse {
                             table: req.table,
@@ -1260,44 +5021,481 @@ mod app {
                             request_id: req.request_id,
                         })
                     }
+                    ApiRequest::Search(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let info = db.table_info(&table_name).await?;
+                        if info.search_index.is_none() {
+                            return Err(Self::Error::TableNotSearchable { table: req.table });
+                        }
+
+                        let (found_columns, not_found_columns) =
+                            db.check_column_names(&table_name, &req.select).await?;
+
+                        if !not_found_columns.is_empty() {
+                            return Err(Self::Error::ColumnsNotFound {
+                                columns: not_found_columns,
+                            });
+                        }
+
+                        if let Some(0) = req.page.as_ref().map(|p| p.number) {
+                            return Err(Self::Error::PageNumberCanNotBeZero);
+                        }
+
+                        if let Some(snippet) = &req.snippet {
+                            if !(1..=64).contains(&snippet.tokens) {
+                                return Err(Self::Error::InvalidSearch {
+                                    message: "snippets must be 1 to 64 tokens long".into(),
+                                });
+                            }
+                        }
+
+                        let markup = SearchMarkup {
+                            highlight: req.highlight,
+                            snippet: req.snippet,
+                        };
+                        let hits = db
+                            .search(table_name, found_columns, req.query, markup, req.page)
+                            .await?
+                            .map_err(|message| Self::Error::InvalidSearch { message })?;
+                        ApiResponse::Search(SearchResponse {
+                            table: req.table,
+                            hits,
+                            request_id: req.request_id,
+                            blob_columns: blob_columns(&info),
+                        })
+                    }
+                    ApiRequest::ReadBlob(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let column = db
+                            .check_column_name(&table_name, &req.column)
+                            .await?
+                            .ok_or(Self::Error::ColumnsNotFound {
+                                columns: vec![req.column.clone()],
+                            })?;
+
+                        let info = db.table_info(&table_name).await?;
+                        check_blob_column(&info, &column)?;
+
+                        let key = to_sql_key(&info, req.key, db.blob_encoding())?;
+                        let offset = req.offset.unwrap_or_default();
+                        let (data, size) = db
+                            .read_blob(table_name, key, column, offset, req.length)
+                            .await?
+                            .map_err(|err| from_blob_error(err, req.column.clone()))?;
+                        ApiResponse::ReadBlob(ReadBlobResponse {
+                            table: req.table,
+                            column: req.column,
+                            offset,
+                            data: db.blob_encoding().encode(&data),
+                            size,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::WriteBlob(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let column = db
+                            .check_column_name(&table_name, &req.column)
+                            .await?
+                            .ok_or(Self::Error::ColumnsNotFound {
+                                columns: vec![req.column.clone()],
+                            })?;
+
+                        let info = db.table_info(&table_name).await?;
+                        check_blob_column(&info, &column)?;
+
+                        let blob = db.blob_encoding();
+                        let data = blob.decode(&req.data).ok_or(Self::Error::InvalidValue {
+                            column: Some(req.column.clone()),
+                            message: InvalidValue::InvalidBlob(blob).to_string().into(),
+                        })?;
+                        let written_bytes = data.len();
+
+                        let key = to_sql_key(&info, req.key, blob)?;
+                        let size = db
+                            .write_blob(
+                                table_name,
+                                key,
+                                column,
+                                req.offset.unwrap_or_default(),
+                                data,
+                                req.size,
+                            )
+                            .await?
+                            .map_err(|err| from_blob_error(err, req.column.clone()))?;
+                        ApiResponse::WriteBlob(WriteBlobResponse {
+                            table: req.table,
+                            column: req.column,
+                            written_bytes,
+                            size,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::DescribeSchema(req) => {
+                        let (tables, views) = db.describe_schema().await?;
+                        ApiResponse::DescribeSchema(DescribeSchemaResponse {
+                            tables,
+                            views,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::DescribeTable(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let table = db.describe_table(&table_name).await?;
+                        ApiResponse::DescribeTable(DescribeTableResponse {
+                            table,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::CreateTable(_)
+                    | ApiRequest::AddColumn(_)
+                    | ApiRequest::CreateIndex(_)
+                    | ApiRequest::DropTable(_)
+                        if !admin =>
+                    {
+                        return Err(Self::Error::Forbidden);
+                    }
+                    ApiRequest::CreateTable(req) => {
+                        let change = SchemaChange::CreateTable {
+                            table: req.table.clone(),
+                            columns: req.columns,
+                            strict: req.strict,
+                        };
+                        db.change_schema(change)
+                            .await?
+                            .map_err(|message| Self::Error::InvalidSchemaChange { message })?;
+
+                        ApiResponse::CreateTable(SchemaChangeResponse {
+                            table: req.table,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::AddColumn(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let change = SchemaChange::AddColumn {
+                            table: table_name,
+                            column: req.column,
+                        };
+                        db.change_schema(change)
+                            .await?
+                            .map_err(|message| Self::Error::InvalidSchemaChange { message })?;
+
+                        ApiResponse::AddColumn(SchemaChangeResponse {
+                            table: req.table,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::CreateIndex(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let (columns, not_found_columns) =
+                            db.check_column_names(&table_name, &req.columns).await?;
+
+                        if !not_found_columns.is_empty() {
+                            return Err(Self::Error::ColumnsNotFound {
+                                columns: not_found_columns,
+                            });
+                        }
+
+                        let change = SchemaChange::CreateIndex {
+                            table: table_name,
+                            name: req.name,
+                            columns,
+                            unique: req.unique,
+                        };
+                        db.change_schema(change)
+                            .await?
+                            .map_err(|message| Self::Error::InvalidSchemaChange { message })?;
+
+                        ApiResponse::CreateIndex(SchemaChangeResponse {
+                            table: req.table,
+                            request_id: req.request_id,
+                        })
+                    }
+                    ApiRequest::DropTable(req) => {
+                        let table_name = db.check_table_name(&req.table).await?.ok_or(
+                            Self::Error::TableNotFound {
+                                table: req.table.clone(),
+                            },
+                        )?;
+
+                        let change = SchemaChange::DropTable { table: table_name };
+                        db.change_schema(change)
+                            .await?
+                            .map_err(|message| Self::Error::InvalidSchemaChange { message })?;
+
+                        ApiResponse::DropTable(SchemaChangeResponse {
+                            table: req.table,
+                            request_id: req.request_id,
+                        })
+                    }
                 };
 
-                Ok(response)
+                Ok(stream::once(future::ready(Ok(response))).boxed())
             })
         }
     }
+
+    /// Sends each request to the [`App`] of the database it names, and requests naming
+    /// none to the connection's database.
+    pub struct Router<DB: Database> {
+        apps: BoxList<(BoxStr, App<DB>)>,
+        default: BoxStr,
+    }
+
+    impl<DB: Database> Router<DB> {
+        pub fn new(apps: impl IntoIterator<Item = (BoxStr, App<DB>)>, default: BoxStr) -> Self {
+            Self {
+                apps: apps.into_iter().collect(),
+                default,
+            }
+        }
+
+        /// Stops routing to `database`, requests naming it fail as if it didn't exist.
+        pub fn without(self, database: &str) -> Self {
+            let apps = self.apps.into_vec().into_iter();
+            Self {
+                apps: apps.filter(|(name, _)| **name != *database).collect(),
+                default: self.default,
+            }
+        }
+    }
+
+    impl<DB> Service<RoutedRequest> for Router<DB>
+    where
+        DB: Database,
+        AppError<DB::Error>: From<DB::Error>,
+    {
+        type Response = <App<DB> as Service<ApiRequest>>::Response;
+        type Error = AppError<DB::Error>;
+        type Future = future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            // Apps are always ready.
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, routed: RoutedRequest) -> Self::Future {
+            let database = routed.database.unwrap_or_else(|| self.default.clone());
+            match self.apps.iter_mut().find(|(name, _)| *name == database) {
+                Some((_, app)) => app.call(routed.request),
+                None => Box::pin(future::err(AppError::DatabaseNotFound { database })),
+            }
+        }
+    }
 }
 
 mod websocket {
     use std::{
         error::Error,
+        ops::Range,
         task::{Context, Poll},
     };
 
-    use futures::{future, FutureExt};
-    use serde::Serialize;
+    use futures::{
+        future,
+        stream::{self, BoxStream},
+        FutureExt, StreamExt,
+    };
+    use serde::{Deserialize, Serialize};
+    use thiserror::Error;
     use tower::{Layer, Service};
 
-    use crate::{requests::ApiRequest, responses::ErrorResponse, WsMessage};
+    use rmpv::Value as MsgPackValue;
+    use serde_json::{Map, Value as JsonValue};
+    use tokio_tungstenite::tungstenite::http::{header, Request as HttpRequest};
+
+    use crate::{
+        db::BlobEncoding,
+        requests::{ApiRequest, HelloRequest, RoutedRequest},
+        responses::{
+            ApiResponse, BlobCells, ErrorEnvelope, ErrorResponse, HelloResponse, WithRequestId,
+        },
+        BoxList, BoxStr, WsMessage,
+    };
+
+    /// Spoken until the client says `Hello`, errors are sent as they are.
+    pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+    /// Errors are wrapped in an `Error` message.
+    pub const PROTOCOL_VERSION: u32 = 2;
+
+    /// What this server supports, offered to clients in the `Hello` response.
+    pub const CAPABILITIES: &[&str] = &[
+        "streaming",
+        "binary_frames",
+        "search",
+        "blobs",
+        "describe",
+        "schema_changes",
+        "message_pack",
+    ];
+
+    /// [`CAPABILITIES`], and `admin` on connections that may change the schema.
+    pub fn capabilities(admin: bool) -> BoxList<BoxStr> {
+        let admin = admin.then_some("admin");
+        CAPABILITIES
+            .iter()
+            .copied()
+            .chain(admin)
+            .map(Into::into)
+            .collect()
+    }
+
+    /// How a connection's messages are encoded, negotiated with the WebSocket subprotocol.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Encoding {
+        Json,
+        /// Binary frames are MessagePack, and so is every message the server sends.
+        /// Blobs are MessagePack `bin` values.
+        MessagePack,
+    }
+
+    impl Encoding {
+        pub const MESSAGE_PACK_PROTOCOL: &'static str = "jabroni.msgpack";
+
+        /// MessagePack if the client offered it among its subprotocols.
+        pub fn negotiate<B>(request: &HttpRequest<B>) -> Self {
+            let message_pack = request
+                .headers()
+                .get_all(header::SEC_WEBSOCKET_PROTOCOL)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .any(|protocol| protocol.trim() == Self::MESSAGE_PACK_PROTOCOL);
+
+            if message_pack {
+                Self::MessagePack
+            } else {
+                Self::Json
+            }
+        }
+
+        /// The subprotocol the server accepts the connection with.
+        pub const fn protocol(self) -> Option<&'static str> {
+            match self {
+                Self::Json => None,
+                Self::MessagePack => Some(Self::MESSAGE_PACK_PROTOCOL),
+            }
+        }
+
+        /// Messages that carry no blobs, like errors and notifications.
+        pub fn message<T: Serialize>(self, value: &T) -> WsMessage {
+            match self {
+                Self::Json => WsMessage::text(
+                    serde_json::to_string(value).expect("failed to serialize message to json"),
+                ),
+                Self::MessagePack => {
+                    let value = serde_json::to_value(value).expect("failed to serialize message");
+                    WsMessage::binary(write_message_pack(to_message_pack(value, &[])))
+                }
+            }
+        }
+    }
+
+    /// How the responses to a request are sent.
+    #[derive(Debug, Clone, Copy)]
+    enum Reply {
+        Text,
+        /// The binary frames described at [`decode_frame`].
+        Frame(BlobEncoding),
+        MessagePack(BlobEncoding),
+    }
+
+    impl Reply {
+        const fn encoding(self) -> Encoding {
+            match self {
+                Self::Text | Self::Frame(_) => Encoding::Json,
+                Self::MessagePack(_) => Encoding::MessagePack,
+            }
+        }
+    }
 
     pub struct WebSocketAdapter<S> {
         inner: S,
+        blob_encoding: BlobEncoding,
+        encoding: Encoding,
+        /// Everything the server offers until the client's `Hello` narrows it down.
+        capabilities: BoxList<BoxStr>,
+        protocol_version: u32,
     }
 
     impl<S> WebSocketAdapter<S> {
-        pub const fn new(inner: S) -> Self {
-            Self { inner }
+        pub const fn new(
+            inner: S,
+            blob_encoding: BlobEncoding,
+            encoding: Encoding,
+            capabilities: BoxList<BoxStr>,
+        ) -> Self {
+            Self {
+                inner,
+                blob_encoding,
+                encoding,
+                capabilities,
+                protocol_version: LEGACY_PROTOCOL_VERSION,
+            }
+        }
+
+        /// Settles on the newest version both sides speak, and the capabilities both support.
+        /// Clients that don't list any capabilities are offered all of them.
+        fn hello(&mut self, hello: HelloRequest) -> Result<ApiResponse, ErrorResponse> {
+            if hello.protocol_version < LEGACY_PROTOCOL_VERSION {
+                return Err(ErrorResponse::UnsupportedProtocolVersion {
+                    supported: (LEGACY_PROTOCOL_VERSION..=PROTOCOL_VERSION).collect(),
+                });
+            }
+            self.protocol_version = hello.protocol_version.min(PROTOCOL_VERSION);
+
+            self.capabilities = self
+                .capabilities
+                .iter()
+                .filter(|c| hello.capabilities.is_empty() || hello.capabilities.contains(c))
+                .cloned()
+                .collect();
+
+            Ok(ApiResponse::Hello(HelloResponse {
+                protocol_version: self.protocol_version,
+                capabilities: self.capabilities.clone(),
+                request_id: hello.request_id,
+            }))
         }
     }
 
-    impl<S> Service<String> for WebSocketAdapter<S>
+    impl<S, R, E> Service<WsMessage> for WebSocketAdapter<S>
     where
-        S: Service<ApiRequest>,
-        S::Response: Serialize,
+        S: Service<RoutedRequest, Response = BoxStream<'static, Result<R, E>>, Error = E>,
+        R: Serialize + BlobCells + 'static,
         S::Future: Send + 'static,
-        S::Error: Error + Serialize + Send + 'static,
+        E: Error + Serialize + Send + 'static,
     {
-        type Response = WsMessage;
+        type Response = BoxStream<'static, WsMessage>;
         type Error = S::Error;
         type Future = future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
@@ -1305,546 +5503,7498 @@ mod websocket {
             self.inner.poll_ready(ctx)
         }
 
-        fn call(&mut self, request: String) -> Self::Future {
-            let req = serde_json::from_str::<ApiRequest>(&request);
-
-            match req {
-                Ok(req) => self
-                    .inner
-                    .call(req)
-                    .map(|body| Ok(to_message(body)))
-                    .boxed(),
-                Err(err) => {
-                    tracing::error!("failed to decode json request body: {err}");
-                    let err = ErrorResponse::bad_request("failed to decode request".into());
-                    let err = serde_json::to_string(&err)
-                        .expect("failed to serialize error response to json");
-                    future::ok(WsMessage::text(err)).boxed()
+        fn call(&mut self, message: WsMessage) -> Self::Future {
+            let blob_encoding = self.blob_encoding;
+            let encoding = self.encoding;
+            let protocol_version = self.protocol_version;
+            // Requests sent in binary frames are answered with binary frames.
+            let (request, binary) = match message {
+                WsMessage::Text(text) => {
+                    (serde_json::from_str(&text).map_err(FrameError::from), false)
                 }
-            }
-        }
-    }
+                WsMessage::Binary(frame) => match encoding {
+                    Encoding::Json => (decode_frame(&frame, blob_encoding), true),
+                    Encoding::MessagePack => (decode_message_pack(&frame, blob_encoding), true),
+                },
+                _ => {
+                    let err = ErrorResponse::NonTextMessage;
+                    let err = error_message(err, None, protocol_version, encoding);
+                    return future::ok(single(err)).boxed();
+                }
+            };
 
-    fn to_message<R, E>(body: Result<R, E>) -> WsMessage
-    where
-        R: Serialize,
-        E: Serialize + Error,
+            let request_id = request.as_ref().ok().and_then(find_request_id);
+
+            let is_hello = request.as_ref().is_ok_and(|request| {
+                request.get("type").and_then(JsonValue::as_str) == Some("Hello")
+            });
+            if is_hello {
+                let hello = request.and_then(|request| Ok(HelloRequest::deserialize(request)?));
+                let message = match hello.map(|hello| self.hello(hello)) {
+                    Ok(Ok(resp)) => encoding.message(&resp),
+                    Ok(Err(err)) => error_message(err, request_id, protocol_version, encoding),
+                    Err(err) => {
+                        tracing::error!("failed to decode hello: {err}");
+                        let err = ErrorResponse::bad_request("failed to decode hello".into());
+                        error_message(err, request_id, protocol_version, encoding)
+                    }
+                };
+                return future::ok(single(message)).boxed();
+            }
+
+            let database = request.as_ref().ok().and_then(find_database);
+            let req = request.and_then(|request| Ok(ApiRequest::deserialize(request)?));
+
+            let missing = req.as_ref().ok().and_then(|req| {
+                required_capability(req, binary && encoding == Encoding::Json)
+                    .filter(|capability| !self.capabilities.iter().any(|c| **c == **capability))
+            });
+            if let Some(capability) = missing {
+                let message = format!("the {capability} capability was not negotiated");
+                let err = ErrorResponse::bad_request(message.into());
+                let err = error_message(err, request_id, protocol_version, encoding);
+                return future::ok(single(err)).boxed();
+            }
+
+            match req {
+                Ok(req) => {
+                    let request_id = req.request_id().clone();
+                    let req = RoutedRequest {
+                        database,
+                        request: req,
+                    };
+                    let reply = match encoding {
+                        Encoding::MessagePack => Reply::MessagePack(blob_encoding),
+                        Encoding::Json if binary => Reply::Frame(blob_encoding),
+                        Encoding::Json => Reply::Text,
+                    };
+                    self.inner
+                        .call(req)
+                        .map(move |body| {
+                            let messages = match body {
+                                Ok(responses) => responses
+                                    .map(move |body| {
+                                        to_message(
+                                            body,
+                                            request_id.clone(),
+                                            reply,
+                                            protocol_version,
+                                        )
+                                    })
+                                    .boxed(),
+                                Err(err) => single(to_message::<R, _>(
+                                    Err(err),
+                                    request_id,
+                                    reply,
+                                    protocol_version,
+                                )),
+                            };
+                            Ok(messages)
+                        })
+                        .boxed()
+                }
+                Err(err) => {
+                    tracing::error!("failed to decode request: {err}");
+                    let err = ErrorResponse::bad_request("failed to decode request".into());
+                    let err = error_message(err, request_id, protocol_version, encoding);
+                    future::ok(single(err)).boxed()
+                }
+            }
+        }
+    }
+
+    /// The capability a request relies on, refused when the client's `Hello` left it out.
+    fn required_capability(request: &ApiRequest, binary_frame: bool) -> Option<&'static str> {
+        match request {
+            ApiRequest::ListRows(req) if req.chunk_size.is_some() => Some("streaming"),
+            _ if binary_frame => Some("binary_frames"),
+            ApiRequest::Search(_) => Some("search"),
+            ApiRequest::ReadBlob(_) | ApiRequest::WriteBlob(_) => Some("blobs"),
+            ApiRequest::DescribeSchema(_) | ApiRequest::DescribeTable(_) => Some("describe"),
+            ApiRequest::CreateTable(_)
+            | ApiRequest::AddColumn(_)
+            | ApiRequest::CreateIndex(_)
+            | ApiRequest::DropTable(_) => Some("schema_changes"),
+            _ => None,
+        }
+    }
+
+    fn single(message: WsMessage) -> BoxStream<'static, WsMessage> {
+        stream::once(future::ready(message)).boxed()
+    }
+
+    fn error_message<E: Serialize>(
+        error: E,
+        request_id: Option<BoxStr>,
+        protocol_version: u32,
+        encoding: Encoding,
+    ) -> WsMessage {
+        if protocol_version >= 2 {
+            encoding.message(&ErrorEnvelope { error, request_id })
+        } else {
+            encoding.message(&WithRequestId::new(error, request_id))
+        }
+    }
+
+    /// Recovers the `request_id` of a request that failed to decode, so the error can still be correlated.
+    fn find_request_id(request: &JsonValue) -> Option<BoxStr> {
+        request.get("request_id")?.as_str().map(Into::into)
+    }
+
+    /// Requests name the database they are for in a `database` field next to their `type`.
+    fn find_database(request: &JsonValue) -> Option<BoxStr> {
+        request.get("database")?.as_str().map(Into::into)
+    }
+
+    #[derive(Debug, Error)]
+    enum FrameError {
+        #[error("binary frame is shorter than its header")]
+        TooShort,
+        #[error("binary frame refers to bytes past its end")]
+        AttachmentOutOfBounds,
+        #[error("invalid json: {0}")]
+        InvalidJson(#[from] serde_json::Error),
+        #[error("invalid message pack: {0}")]
+        InvalidMessagePack(#[from] rmpv::decode::Error),
+        #[error("message pack {0} have no json equivalent")]
+        UnsupportedMessagePack(&'static str),
+    }
+
+    /// Binary frames are a big endian `u32` length, that many bytes of json and the raw bytes
+    /// of blobs, which the json refers to as `{"$binary": [offset, length]}`.
+    fn decode_frame(frame: &[u8], blob_encoding: BlobEncoding) -> Result<JsonValue, FrameError> {
+        let (length, rest) = frame.split_first_chunk::<4>().ok_or(FrameError::TooShort)?;
+        let length = u32::from_be_bytes(*length) as usize;
+        if rest.len() < length {
+            return Err(FrameError::TooShort);
+        }
+
+        let (json, attachments) = rest.split_at(length);
+        let mut request = serde_json::from_slice(json)?;
+        resolve_attachments(&mut request, attachments, blob_encoding)?;
+        Ok(request)
+    }
+
+    /// Replaces references to attached bytes with the blob in the json encoding.
+    fn resolve_attachments(
+        value: &mut JsonValue,
+        attachments: &[u8],
+        blob_encoding: BlobEncoding,
+    ) -> Result<(), FrameError> {
+        match value {
+            JsonValue::Object(map) => match attachment_range(map) {
+                Some(range) => {
+                    let bytes = attachments
+                        .get(range)
+                        .ok_or(FrameError::AttachmentOutOfBounds)?;
+                    *value = blob_encoding.encode(bytes);
+                    Ok(())
+                }
+                None => map
+                    .values_mut()
+                    .try_for_each(|v| resolve_attachments(v, attachments, blob_encoding)),
+            },
+            JsonValue::Array(items) => items
+                .iter_mut()
+                .try_for_each(|v| resolve_attachments(v, attachments, blob_encoding)),
+            _ => Ok(()),
+        }
+    }
+
+    fn attachment_range(map: &Map<String, JsonValue>) -> Option<Range<usize>> {
+        if map.len() != 1 {
+            return None;
+        }
+
+        let [offset, length] = map.get("$binary")?.as_array()?.as_slice() else {
+            return None;
+        };
+        let offset = usize::try_from(offset.as_u64()?).ok()?;
+        let length = usize::try_from(length.as_u64()?).ok()?;
+        Some(offset..offset.checked_add(length)?)
+    }
+
+    /// Replaces the values of blob columns with references to the returned raw bytes.
+    fn detach_blobs(resp: &mut impl BlobCells, blob_encoding: BlobEncoding) -> Vec<u8> {
+        let mut attachments = Vec::new();
+        for cell in resp.blob_cells() {
+            if let Some(bytes) = blob_encoding.decode(cell) {
+                *cell = serde_json::json!({ "$binary": [attachments.len(), bytes.len()] });
+                attachments.extend(bytes);
+            }
+        }
+        attachments
+    }
+
+    /// Moves the values of blob columns out of the json and into the frame as raw bytes.
+    fn encode_frame<R>(mut resp: R, blob_encoding: BlobEncoding) -> Vec<u8>
+    where
+        R: Serialize + BlobCells,
     {
-        match body {
-            Ok(resp) => {
-                let resp =
-                    serde_json::to_string(&resp).expect("failed to serialize response to json");
-                WsMessage::text(resp)
+        let attachments = detach_blobs(&mut resp, blob_encoding);
+
+        let json = serde_json::to_vec(&resp).expect("failed to serialize response to json");
+        let length = u32::try_from(json.len()).expect("response json is larger than 4GiB");
+
+        let mut frame = Vec::with_capacity(4 + json.len() + attachments.len());
+        frame.extend(length.to_be_bytes());
+        frame.extend(json);
+        frame.extend(attachments);
+        frame
+    }
+
+    fn encode_message_pack<R>(mut resp: R, blob_encoding: BlobEncoding) -> Vec<u8>
+    where
+        R: Serialize + BlobCells,
+    {
+        let attachments = detach_blobs(&mut resp, blob_encoding);
+        let value = serde_json::to_value(&resp).expect("failed to serialize response");
+        write_message_pack(to_message_pack(value, &attachments))
+    }
+
+    fn write_message_pack(value: MsgPackValue) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, &value).expect("writing to a vec can't fail");
+        bytes
+    }
+
+    /// Converts json to MessagePack, turning references to attached bytes into `bin` values.
+    fn to_message_pack(value: JsonValue, attachments: &[u8]) -> MsgPackValue {
+        match value {
+            JsonValue::Null => MsgPackValue::Nil,
+            JsonValue::Bool(b) => MsgPackValue::Boolean(b),
+            JsonValue::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => MsgPackValue::from(i),
+                (None, Some(u)) => MsgPackValue::from(u),
+                (None, None) => MsgPackValue::F64(n.as_f64().unwrap_or_default()),
+            },
+            JsonValue::String(s) => MsgPackValue::from(s),
+            JsonValue::Array(items) => MsgPackValue::Array(
+                items
+                    .into_iter()
+                    .map(|v| to_message_pack(v, attachments))
+                    .collect(),
+            ),
+            JsonValue::Object(map) => {
+                let bytes = attachment_range(&map).and_then(|range| attachments.get(range));
+                match bytes {
+                    Some(bytes) => MsgPackValue::Binary(bytes.to_vec()),
+                    None => MsgPackValue::Map(
+                        map.into_iter()
+                            .map(|(k, v)| (k.into(), to_message_pack(v, attachments)))
+                            .collect(),
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Decodes a MessagePack request, `bin` values become blobs in the json encoding.
+    fn decode_message_pack(
+        frame: &[u8],
+        blob_encoding: BlobEncoding,
+    ) -> Result<JsonValue, FrameError> {
+        let value = rmpv::decode::read_value(&mut &*frame)?;
+        from_message_pack(value, blob_encoding)
+    }
+
+    fn from_message_pack(
+        value: MsgPackValue,
+        blob_encoding: BlobEncoding,
+    ) -> Result<JsonValue, FrameError> {
+        let value = match value {
+            MsgPackValue::Nil => JsonValue::Null,
+            MsgPackValue::Boolean(b) => JsonValue::Bool(b),
+            MsgPackValue::Integer(i) => match i.as_i64() {
+                Some(i) => JsonValue::from(i),
+                None => JsonValue::from(i.as_u64()),
+            },
+            MsgPackValue::F32(f) => JsonValue::from(f),
+            MsgPackValue::F64(f) => JsonValue::from(f),
+            MsgPackValue::String(s) => s
+                .into_str()
+                .map(JsonValue::String)
+                .ok_or(FrameError::UnsupportedMessagePack("non utf-8 strings"))?,
+            MsgPackValue::Binary(bytes) => blob_encoding.encode(&bytes),
+            MsgPackValue::Array(items) => items
+                .into_iter()
+                .map(|v| from_message_pack(v, blob_encoding))
+                .collect::<Result<_, _>>()?,
+            MsgPackValue::Map(entries) => entries
+                .into_iter()
+                .map(|(k, v)| match k {
+                    MsgPackValue::String(k) if k.is_str() => Ok((
+                        k.into_str().unwrap_or_default(),
+                        from_message_pack(v, blob_encoding)?,
+                    )),
+                    _ => Err(FrameError::UnsupportedMessagePack("non string keys")),
+                })
+                .collect::<Result<Map<_, _>, _>>()?
+                .into(),
+            MsgPackValue::Ext(..) => {
+                return Err(FrameError::UnsupportedMessagePack("extension types"))
             }
+        };
+        Ok(value)
+    }
+
+    fn to_message<R, E>(
+        body: Result<R, E>,
+        request_id: BoxStr,
+        reply: Reply,
+        protocol_version: u32,
+    ) -> WsMessage
+    where
+        R: Serialize + BlobCells,
+        E: Serialize + Error,
+    {
+        match body {
+            Ok(resp) => match reply {
+                Reply::Text => {
+                    let resp =
+                        serde_json::to_string(&resp).expect("failed to serialize response to json");
+                    WsMessage::text(resp)
+                }
+                Reply::Frame(blob_encoding) => WsMessage::binary(encode_frame(resp, blob_encoding)),
+                Reply::MessagePack(blob_encoding) => {
+                    WsMessage::binary(encode_message_pack(resp, blob_encoding))
+                }
+            },
 
             Err(err) => {
                 tracing::error!("error occured while processing request: {err}");
-                let err = serde_json::to_string(&err)
-                    .expect("failed to serialize error response to json");
-                WsMessage::text(err)
+                error_message(err, Some(request_id), protocol_version, reply.encoding())
             }
         }
     }
 
-    pub struct WebSocketAdapterLayer;
+    pub struct WebSocketAdapterLayer {
+        blob_encoding: BlobEncoding,
+        encoding: Encoding,
+        capabilities: BoxList<BoxStr>,
+    }
+
+    impl WebSocketAdapterLayer {
+        pub const fn new(
+            blob_encoding: BlobEncoding,
+            encoding: Encoding,
+            capabilities: BoxList<BoxStr>,
+        ) -> Self {
+            Self {
+                blob_encoding,
+                encoding,
+                capabilities,
+            }
+        }
+    }
 
     impl<S> Layer<S> for WebSocketAdapterLayer {
         type Service = WebSocketAdapter<S>;
 
-        fn layer(&self, inner: S) -> Self::Service {
-            WebSocketAdapter::new(inner)
-        }
-    }
-}
+        fn layer(&self, inner: S) -> Self::Service {
+            WebSocketAdapter::new(
+                inner,
+                self.blob_encoding,
+                self.encoding,
+                self.capabilities.clone(),
+            )
+        }
+    }
+}
+
+mod deflate {
+    use std::{
+        io,
+        ops::Range,
+        pin::Pin,
+        task::{ready, Context, Poll},
+    };
+
+    use flate2::{Compress, Decompress, FlushCompress, FlushDecompress, Status};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use tokio_tungstenite::tungstenite::http::{header, HeaderValue, Request as HttpRequest};
+
+    /// Same as tungstenite's defaults, so frames and inflated messages can't grow past what
+    /// it accepts.
+    const MAX_FRAME_SIZE: usize = 16 << 20;
+    const MAX_MESSAGE_SIZE: usize = 64 << 20;
+    /// Ends every deflated message, and is left out of the frames.
+    const SYNC_MARKER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+    const FIN: u8 = 0x80;
+    const RSV1: u8 = 0x40;
+    const MASK: u8 = 0x80;
+    const CONTINUATION: u8 = 0x0;
+    const TEXT: u8 = 0x1;
+    const BINARY: u8 = 0x2;
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct Compression {
+        pub level: u32,
+        pub threshold: usize,
+    }
+
+    /// The permessage-deflate extension (RFC 7692) as negotiated with one client.
+    pub struct PerMessageDeflate {
+        compression: Compression,
+        server_no_context_takeover: bool,
+    }
+
+    impl PerMessageDeflate {
+        /// Accepts the first offer this server can honor. The server always compresses with
+        /// a 15 bit window, so offers limiting it are declined.
+        pub fn negotiate<B>(request: &HttpRequest<B>, compression: Compression) -> Option<Self> {
+            request
+                .headers()
+                .get_all(header::SEC_WEBSOCKET_EXTENSIONS)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .find_map(|offer| {
+                    let mut params = offer.split(';').map(str::trim);
+                    if params.next()? != "permessage-deflate" {
+                        return None;
+                    }
+
+                    let mut server_no_context_takeover = false;
+                    for param in params {
+                        let (name, value) = match param.split_once('=') {
+                            Some((name, value)) => {
+                                (name.trim(), Some(value.trim().trim_matches('"')))
+                            }
+                            None => (param, None),
+                        };
+                        match (name, value) {
+                            ("server_no_context_takeover", None) => {
+                                server_no_context_takeover = true
+                            }
+                            ("client_no_context_takeover", None)
+                            | ("client_max_window_bits", _)
+                            | ("server_max_window_bits", Some("15")) => {}
+                            _ => return None,
+                        }
+                    }
+
+                    Some(Self {
+                        compression,
+                        server_no_context_takeover,
+                    })
+                })
+        }
+
+        pub fn response_header(&self) -> HeaderValue {
+            HeaderValue::from_static(if self.server_no_context_takeover {
+                "permessage-deflate; server_no_context_takeover"
+            } else {
+                "permessage-deflate"
+            })
+        }
+    }
+
+    /// Sits between the socket and tungstenite, which doesn't support extensions. Frames
+    /// coming in with RSV1 set are inflated, and text and binary messages going out in a
+    /// single frame are deflated once they reach the threshold. Passes everything through
+    /// until [`DeflateStream::enable`] is called after the handshake.
+    pub struct DeflateStream<S> {
+        inner: S,
+        state: Option<(Inflater, Deflater)>,
+    }
+
+    impl<S> DeflateStream<S> {
+        pub const fn new(inner: S) -> Self {
+            Self { inner, state: None }
+        }
+
+        pub fn enable(&mut self, deflate: PerMessageDeflate) {
+            self.state = Some((Inflater::new(), Deflater::new(deflate)));
+        }
+    }
+
+    struct FrameHeader {
+        fin: bool,
+        rsv1: bool,
+        opcode: u8,
+        mask: Option<[u8; 4]>,
+        header_len: usize,
+        payload_len: usize,
+    }
+
+    impl FrameHeader {
+        /// `None` until `buf` holds the whole header.
+        fn parse(buf: &[u8]) -> Option<Self> {
+            let [first, second, ..] = *buf else {
+                return None;
+            };
+
+            let (payload_len, mut header_len) = match second & 0x7f {
+                126 => (
+                    u16::from_be_bytes(buf.get(2..4)?.try_into().ok()?) as usize,
+                    4,
+                ),
+                // Lengths that don't fit are over any limit anyway.
+                127 => (
+                    usize::try_from(u64::from_be_bytes(buf.get(2..10)?.try_into().ok()?))
+                        .unwrap_or(usize::MAX),
+                    10,
+                ),
+                len => (len as usize, 2),
+            };
+            let mask = if second & MASK != 0 {
+                let mask = buf.get(header_len..header_len + 4)?.try_into().ok()?;
+                header_len += 4;
+                Some(mask)
+            } else {
+                None
+            };
+
+            Some(Self {
+                fin: first & FIN != 0,
+                rsv1: first & RSV1 != 0,
+                opcode: first & 0x0f,
+                mask,
+                header_len,
+                payload_len,
+            })
+        }
+
+        fn write(out: &mut Vec<u8>, opcode: u8, rsv1: bool, mask: Option<[u8; 4]>, len: usize) {
+            out.push(FIN | if rsv1 { RSV1 } else { 0 } | opcode);
+            let mask_bit = if mask.is_some() { MASK } else { 0 };
+            match len {
+                0..=125 => out.push(mask_bit | len as u8),
+                126..=0xffff => {
+                    out.push(mask_bit | 126);
+                    out.extend((len as u16).to_be_bytes());
+                }
+                _ => {
+                    out.push(mask_bit | 127);
+                    out.extend((len as u64).to_be_bytes());
+                }
+            }
+            if let Some(mask) = mask {
+                out.extend(mask);
+            }
+        }
+    }
+
+    fn too_large() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, "inflated message is too large")
+    }
+
+    fn frame_too_large() -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, "frame is too large")
+    }
+
+    /// Turns the client's compressed frames into plain ones. The client may compress with
+    /// context takeover, so one inflater lives as long as the connection.
+    struct Inflater {
+        decompress: Decompress,
+        raw: Vec<u8>,
+        decoded: Vec<u8>,
+        position: usize,
+        /// The opcode and payload of a compressed message arriving in several frames.
+        fragments: Option<(u8, Vec<u8>)>,
+        /// What's left of an uncompressed frame's payload, passed on as it arrives.
+        passthrough: usize,
+    }
+
+    impl Inflater {
+        fn new() -> Self {
+            Self {
+                decompress: Decompress::new(false),
+                raw: Vec::new(),
+                decoded: Vec::new(),
+                position: 0,
+                fragments: None,
+                passthrough: 0,
+            }
+        }
+
+        /// Moves the next frame, or the next part of an uncompressed one, in `raw` to
+        /// `decoded`. False when more has to be read first.
+        fn decode_frame(&mut self) -> io::Result<bool> {
+            if self.passthrough > 0 {
+                if self.raw.is_empty() {
+                    return Ok(false);
+                }
+                let n = self.passthrough.min(self.raw.len());
+                self.decoded.extend(self.raw.drain(..n));
+                self.passthrough -= n;
+                return Ok(true);
+            }
+
+            let Some(header) = FrameHeader::parse(&self.raw) else {
+                return Ok(false);
+            };
+            // Checked before buffering anything, the header is all a client has to send.
+            if header.payload_len > MAX_FRAME_SIZE {
+                return Err(frame_too_large());
+            }
+
+            let compressed = header.rsv1 && matches!(header.opcode, TEXT | BINARY);
+            let continued = header.opcode == CONTINUATION && self.fragments.is_some();
+            if !compressed && !continued {
+                self.decoded.extend(self.raw.drain(..header.header_len));
+                self.passthrough = header.payload_len;
+                return Ok(true);
+            }
+
+            let len = header
+                .header_len
+                .checked_add(header.payload_len)
+                .ok_or_else(frame_too_large)?;
+            if self.raw.len() < len {
+                return Ok(false);
+            }
+
+            let mut payload: Vec<u8> = self.raw.drain(..len).skip(header.header_len).collect();
+            if let Some(mask) = header.mask {
+                for (i, byte) in payload.iter_mut().enumerate() {
+                    *byte ^= mask[i % 4];
+                }
+            }
+
+            let (opcode, mut message) = match self.fragments.take() {
+                Some(fragments) => fragments,
+                None => (header.opcode, Vec::new()),
+            };
+            message.extend(payload);
+            if message.len() > MAX_MESSAGE_SIZE {
+                return Err(too_large());
+            }
+            if !header.fin {
+                self.fragments = Some((opcode, message));
+                return Ok(true);
+            }
+
+            let message = self.inflate(message)?;
+            // Masked with zeros, tungstenite refuses unmasked frames from clients.
+            FrameHeader::write(
+                &mut self.decoded,
+                opcode,
+                false,
+                Some([0; 4]),
+                message.len(),
+            );
+            self.decoded.extend(message);
+            Ok(true)
+        }
+
+        /// Never holds more than one byte past [`MAX_MESSAGE_SIZE`], however well the
+        /// message compresses.
+        fn inflate(&mut self, mut message: Vec<u8>) -> io::Result<Vec<u8>> {
+            message.extend(SYNC_MARKER);
+            let limit = MAX_MESSAGE_SIZE + 1;
+            let mut out = Vec::with_capacity(message.len().saturating_mul(4).min(limit));
+            let mut consumed = 0;
+            loop {
+                let (before, written) = (self.decompress.total_in(), out.len());
+                let status = self
+                    .decompress
+                    .decompress_vec(&message[consumed..], &mut out, FlushDecompress::Sync)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                let read = (self.decompress.total_in() - before) as usize;
+                consumed += read;
+
+                if out.len() > MAX_MESSAGE_SIZE {
+                    return Err(too_large());
+                }
+                // A message may end the deflate stream with a final block, leaving the sync
+                // marker unread. The next message starts a new stream.
+                if status == Status::StreamEnd {
+                    self.decompress.reset(false);
+                    return Ok(out);
+                }
+                if consumed == message.len() && out.len() < out.capacity() {
+                    return Ok(out);
+                }
+                if read == 0 && out.len() == written && out.len() < out.capacity() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "compressed message is incomplete",
+                    ));
+                }
+                out.reserve_exact(out.capacity().min(limit - out.len()));
+            }
+        }
+    }
+
+    /// Compresses the server's messages, keeping the context between them unless the client
+    /// asked for `server_no_context_takeover`.
+    struct Deflater {
+        compress: Compress,
+        deflate: PerMessageDeflate,
+        raw: Vec<u8>,
+        encoded: Vec<u8>,
+        position: usize,
+    }
+
+    impl Deflater {
+        fn new(deflate: PerMessageDeflate) -> Self {
+            let level = flate2::Compression::new(deflate.compression.level);
+            Self {
+                compress: Compress::new(level, false),
+                deflate,
+                raw: Vec::new(),
+                encoded: Vec::new(),
+                position: 0,
+            }
+        }
+
+        /// Moves the complete frames in `raw` to `encoded`, compressing the ones that qualify.
+        fn encode_frames(&mut self) {
+            while let Some(header) = FrameHeader::parse(&self.raw) {
+                let len = header.header_len + header.payload_len;
+                if self.raw.len() < len {
+                    return;
+                }
+
+                let compress = header.fin
+                    && !header.rsv1
+                    && matches!(header.opcode, TEXT | BINARY)
+                    && header.payload_len >= self.deflate.compression.threshold;
+                if compress {
+                    let message = self.deflate(header.header_len..len);
+                    FrameHeader::write(&mut self.encoded, header.opcode, true, None, message.len());
+                    self.encoded.extend(message);
+                } else {
+                    self.encoded.extend(&self.raw[..len]);
+                }
+                self.raw.drain(..len);
+            }
+        }
+
+        fn deflate(&mut self, payload: Range<usize>) -> Vec<u8> {
+            let payload = &self.raw[payload];
+            let mut out = Vec::with_capacity(payload.len() / 2 + 64);
+            let mut consumed = 0;
+            loop {
+                let before = self.compress.total_in();
+                self.compress
+                    .compress_vec(&payload[consumed..], &mut out, FlushCompress::Sync)
+                    .expect("deflating into a vec can't fail");
+                consumed += (self.compress.total_in() - before) as usize;
+
+                if consumed == payload.len() && out.len() < out.capacity() {
+                    break;
+                }
+                out.reserve(out.capacity());
+            }
+
+            if self.deflate.server_no_context_takeover {
+                self.compress.reset();
+            }
+            if out.ends_with(&SYNC_MARKER) {
+                out.truncate(out.len() - SYNC_MARKER.len());
+            }
+            out
+        }
+
+        fn poll_drain<W>(&mut self, inner: &mut W, cx: &mut Context<'_>) -> Poll<io::Result<()>>
+        where
+            W: AsyncWrite + Unpin,
+        {
+            while self.position < self.encoded.len() {
+                let encoded = &self.encoded[self.position..];
+                let n = ready!(Pin::new(&mut *inner).poll_write(cx, encoded))?;
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                }
+                self.position += n;
+            }
+            self.encoded.clear();
+            self.position = 0;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<S: AsyncRead + Unpin> AsyncRead for DeflateStream<S> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let Self { inner, state } = self.get_mut();
+            let Some((inflater, _)) = state else {
+                return Pin::new(inner).poll_read(cx, buf);
+            };
+
+            loop {
+                if inflater.position < inflater.decoded.len() {
+                    let decoded = &inflater.decoded[inflater.position..];
+                    let n = decoded.len().min(buf.remaining());
+                    buf.put_slice(&decoded[..n]);
+                    inflater.position += n;
+                    return Poll::Ready(Ok(()));
+                }
+                inflater.decoded.clear();
+                inflater.position = 0;
+
+                if inflater.decode_frame()? {
+                    continue;
+                }
+
+                let mut chunk = [0; 8192];
+                let mut chunk = ReadBuf::new(&mut chunk);
+                ready!(Pin::new(&mut *inner).poll_read(cx, &mut chunk))?;
+                if chunk.filled().is_empty() {
+                    return Poll::Ready(Ok(()));
+                }
+                inflater.raw.extend_from_slice(chunk.filled());
+            }
+        }
+    }
+
+    impl<S: AsyncWrite + Unpin> AsyncWrite for DeflateStream<S> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let Self { inner, state } = self.get_mut();
+            let Some((_, deflater)) = state else {
+                return Pin::new(inner).poll_write(cx, buf);
+            };
+
+            ready!(deflater.poll_drain(inner, cx))?;
+            deflater.raw.extend_from_slice(buf);
+            deflater.encode_frames();
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            let Self { inner, state } = self.get_mut();
+            if let Some((_, deflater)) = state {
+                ready!(deflater.poll_drain(inner, cx))?;
+            }
+            Pin::new(inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            let Self { inner, state } = self.get_mut();
+            if let Some((_, deflater)) = state {
+                ready!(deflater.poll_drain(inner, cx))?;
+            }
+            Pin::new(inner).poll_shutdown(cx)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const MASK_KEY: [u8; 4] = [1, 2, 3, 4];
+
+        fn frame(opcode: u8, fin: bool, rsv1: bool, payload: &[u8]) -> Vec<u8> {
+            let mut out = Vec::new();
+            FrameHeader::write(&mut out, opcode, rsv1, Some(MASK_KEY), payload.len());
+            if !fin {
+                out[0] &= !FIN;
+            }
+            out.extend(payload.iter().enumerate().map(|(i, b)| b ^ MASK_KEY[i % 4]));
+            out
+        }
+
+        fn deflate_raw(data: &[u8]) -> Vec<u8> {
+            let mut compress = Compress::new(flate2::Compression::fast(), false);
+            let mut out = Vec::with_capacity(data.len() / 100 + 64);
+            let mut consumed = 0;
+            loop {
+                let before = compress.total_in();
+                compress
+                    .compress_vec(&data[consumed..], &mut out, FlushCompress::Sync)
+                    .unwrap();
+                consumed += (compress.total_in() - before) as usize;
+                if consumed == data.len() && out.len() < out.capacity() {
+                    break;
+                }
+                out.reserve(out.capacity());
+            }
+            out.truncate(out.len() - SYNC_MARKER.len());
+            out
+        }
+
+        /// Runs `input` through an inflater, the way `poll_read` feeds it.
+        fn decode(inflater: &mut Inflater, input: &[u8]) -> io::Result<Vec<u8>> {
+            inflater.raw.extend_from_slice(input);
+            while inflater.decode_frame()? {}
+            Ok(std::mem::take(&mut inflater.decoded))
+        }
+
+        fn unmask(frame: &[u8]) -> (FrameHeader, Vec<u8>) {
+            let header = FrameHeader::parse(frame).unwrap();
+            let mask = header.mask.unwrap_or_default();
+            let payload = frame[header.header_len..]
+                .iter()
+                .enumerate()
+                .map(|(i, b)| b ^ mask[i % 4])
+                .collect();
+            (header, payload)
+        }
+
+        #[test]
+        fn parses_every_length_encoding() {
+            for len in [0, 125, 126, 0xffff, 0x10000] {
+                let mut out = Vec::new();
+                FrameHeader::write(&mut out, BINARY, true, Some(MASK_KEY), len);
+                let header = FrameHeader::parse(&out).unwrap();
+                assert_eq!(header.payload_len, len);
+                assert_eq!(header.header_len, out.len());
+                assert_eq!(header.mask, Some(MASK_KEY));
+                assert!(header.fin && header.rsv1);
+                assert_eq!(header.opcode, BINARY);
+            }
+        }
+
+        #[test]
+        fn waits_for_the_whole_header() {
+            let mut out = Vec::new();
+            FrameHeader::write(&mut out, TEXT, false, Some(MASK_KEY), 0x10000);
+            for end in 0..out.len() {
+                assert!(FrameHeader::parse(&out[..end]).is_none());
+            }
+        }
+
+        #[test]
+        fn rejects_a_length_that_overflows() {
+            let mut input = vec![FIN | BINARY, MASK | 127];
+            input.extend([0xff; 8]);
+            input.extend(MASK_KEY);
+
+            let header = FrameHeader::parse(&input).unwrap();
+            assert_eq!(header.payload_len, usize::MAX);
+            assert!(decode(&mut Inflater::new(), &input).is_err());
+        }
+
+        #[test]
+        fn rejects_oversized_frames_from_the_header() {
+            let mut input = Vec::new();
+            FrameHeader::write(&mut input, TEXT, true, Some(MASK_KEY), MAX_FRAME_SIZE + 1);
+            assert!(decode(&mut Inflater::new(), &input).is_err());
+
+            let mut input = Vec::new();
+            FrameHeader::write(&mut input, TEXT, false, Some(MASK_KEY), MAX_FRAME_SIZE + 1);
+            assert!(decode(&mut Inflater::new(), &input).is_err());
+        }
+
+        #[test]
+        fn passes_uncompressed_frames_through_as_they_arrive() {
+            let input = frame(TEXT, true, false, b"hello world");
+            let mut inflater = Inflater::new();
+
+            let (head, tail) = input.split_at(9);
+            let mut output = decode(&mut inflater, head).unwrap();
+            assert_eq!(output, head);
+            assert!(inflater.raw.is_empty());
+
+            output.extend(decode(&mut inflater, tail).unwrap());
+            assert_eq!(output, input);
+        }
+
+        #[test]
+        fn inflates_compressed_frames() {
+            let message = b"hello hello hello hello";
+            let input = frame(TEXT, true, true, &deflate_raw(message));
+
+            let output = decode(&mut Inflater::new(), &input).unwrap();
+            let (header, payload) = unmask(&output);
+            assert!(!header.rsv1);
+            assert_eq!(header.opcode, TEXT);
+            assert_eq!(payload, message);
+        }
+
+        #[test]
+        fn inflates_messages_that_end_the_deflate_stream() {
+            let mut inflater = Inflater::new();
+            for message in [&b"the final block"[..], b"and a new stream"] {
+                let mut compress = Compress::new(flate2::Compression::fast(), false);
+                let mut compressed = Vec::with_capacity(message.len() + 64);
+                let status = compress
+                    .compress_vec(message, &mut compressed, FlushCompress::Finish)
+                    .unwrap();
+                assert_eq!(status, Status::StreamEnd);
+
+                let input = frame(TEXT, true, true, &compressed);
+                let output = decode(&mut inflater, &input).unwrap();
+                assert_eq!(unmask(&output).1, message);
+            }
+        }
+
+        #[test]
+        fn inflates_fragmented_messages() {
+            let compressed = deflate_raw(b"fragmented message");
+            let (first, second) = compressed.split_at(compressed.len() / 2);
+            let mut input = frame(BINARY, false, true, first);
+            input.extend(frame(CONTINUATION, true, false, second));
+
+            let output = decode(&mut Inflater::new(), &input).unwrap();
+            let (header, payload) = unmask(&output);
+            assert_eq!(header.opcode, BINARY);
+            assert_eq!(payload, b"fragmented message");
+        }
+
+        #[test]
+        fn stops_inflating_past_the_message_limit() {
+            let bomb = deflate_raw(&vec![0; MAX_MESSAGE_SIZE + 1]);
+            assert!(bomb.len() <= MAX_FRAME_SIZE);
+            let input = frame(BINARY, true, true, &bomb);
+
+            let err = decode(&mut Inflater::new(), &input).unwrap_err();
+            assert_eq!(err.to_string(), too_large().to_string());
+        }
+
+        #[test]
+        fn deflates_what_reaches_the_threshold() {
+            let deflate = PerMessageDeflate {
+                compression: Compression {
+                    level: 6,
+                    threshold: 16,
+                },
+                server_no_context_takeover: false,
+            };
+            let mut deflater = Deflater::new(deflate);
+            let mut input = Vec::new();
+            FrameHeader::write(&mut input, TEXT, false, None, 5);
+            input.extend(b"short");
+            let message = b"long enough to be compressed, long enough to be compressed";
+            FrameHeader::write(&mut input, TEXT, false, None, message.len());
+            input.extend(message);
+
+            deflater.raw.extend(&input);
+            deflater.encode_frames();
+            let short = FrameHeader::parse(&deflater.encoded).unwrap();
+            assert!(!short.rsv1);
+            let long = &deflater.encoded[short.header_len + short.payload_len..];
+            assert!(FrameHeader::parse(long).unwrap().rsv1);
+
+            let output = decode(&mut Inflater::new(), long).unwrap();
+            assert_eq!(unmask(&output).1, message);
+        }
+    }
+}
+
+mod rest {
+    use std::{
+        collections::HashMap,
+        error::Error,
+        fmt::Write,
+        task::{Context, Poll},
+    };
+
+    use futures::{future, stream::BoxStream, FutureExt, StreamExt};
+    use serde::Serialize;
+    use serde_json::Value as JsonValue;
+    use thiserror::Error;
+    use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+    use tokio_tungstenite::tungstenite::http::{self, header, Method, StatusCode, Version};
+    use tower::{Layer, Service};
+
+    use crate::{
+        app::AppError,
+        requests::{
+            ApiRequest, BatchInsertRowRequest, DeleteRowRequest, GetRowRequest, InsertRowRequest,
+            ListRowsRequest, Pagination, RoutedRequest, SortInfo, SortOrder, UpdateRowRequest,
+        },
+        responses::{ErrorResponse, WithRequestId},
+        BoxList, BoxStr, HttpRequest, HttpResponse,
+    };
+
+    const MAX_HEAD_SIZE: usize = 16 * 1024;
+    const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+    const DEFAULT_PAGE_SIZE: u32 = 25;
+
+    #[derive(Debug, Error)]
+    pub enum HttpError {
+        #[error("io error: {0}")]
+        Io(#[from] std::io::Error),
+        #[error("malformed request: {0}")]
+        Malformed(#[from] httparse::Error),
+        #[error("invalid request: {0}")]
+        Invalid(#[from] http::Error),
+        #[error("incomplete request head")]
+        Incomplete,
+        #[error("request head is larger than {MAX_HEAD_SIZE} bytes")]
+        HeadTooLarge,
+        #[error("request body is larger than {MAX_BODY_SIZE} bytes")]
+        BodyTooLarge,
+        #[error("invalid content length")]
+        InvalidContentLength,
+        #[error("chunked request bodies are not supported")]
+        ChunkedBody,
+    }
+
+    impl HttpError {
+        pub fn status(&self) -> StatusCode {
+            match self {
+                HttpError::HeadTooLarge => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                HttpError::BodyTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+                HttpError::ChunkedBody => StatusCode::LENGTH_REQUIRED,
+                _ => StatusCode::BAD_REQUEST,
+            }
+        }
+    }
+
+    /// Reads everything up to and including the blank line that ends the request head.
+    /// An empty head means the peer closed the connection without sending anything.
+    pub async fn read_head<R>(reader: &mut R) -> Result<Vec<u8>, HttpError>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let mut head = Vec::new();
+
+        loop {
+            let limit = (MAX_HEAD_SIZE - head.len()) as u64;
+            let read = (&mut *reader)
+                .take(limit)
+                .read_until(b'\n', &mut head)
+                .await?;
+
+            if head.ends_with(b"\r\n\r\n") || head.ends_with(b"\n\n") {
+                return Ok(head);
+            }
+
+            if read == 0 {
+                return if head.is_empty() {
+                    Ok(head)
+                } else {
+                    Err(HttpError::Incomplete)
+                };
+            }
+
+            if head.len() >= MAX_HEAD_SIZE {
+                return Err(HttpError::HeadTooLarge);
+            }
+        }
+    }
+
+    pub fn parse_head(head: &[u8]) -> Result<HttpRequest<()>, HttpError> {
+        let mut headers = [httparse::EMPTY_HEADER; 64];
+        let mut parsed = httparse::Request::new(&mut headers);
+
+        if parsed.parse(head)?.is_partial() {
+            return Err(HttpError::Incomplete);
+        }
+
+        let version = match parsed.version {
+            Some(0) => Version::HTTP_10,
+            _ => Version::HTTP_11,
+        };
+
+        let mut builder = HttpRequest::builder()
+            .method(parsed.method.unwrap_or_default())
+            .uri(parsed.path.unwrap_or_default())
+            .version(version);
+
+        for h in parsed.headers.iter() {
+            builder = builder.header(h.name, h.value);
+        }
+
+        Ok(builder.body(())?)
+    }
+
+    pub fn is_websocket_upgrade<B>(request: &HttpRequest<B>) -> bool {
+        request
+            .headers()
+            .get_all(header::UPGRADE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .any(|v| v.eq_ignore_ascii_case("websocket"))
+    }
+
+    /// Whether the request carries `token` in an `Authorization: Bearer` header.
+    pub fn has_bearer_token<B>(request: &HttpRequest<B>, token: &str) -> bool {
+        request
+            .headers()
+            .get_all(header::AUTHORIZATION)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(|v| v.strip_prefix("Bearer "))
+            .any(|v| constant_time_eq(v.trim().as_bytes(), token.as_bytes()))
+    }
+
+    /// Like [`has_bearer_token`], but WebSocket upgrades may also pass `token` in an
+    /// `access_token` query parameter, browsers can't set headers on them. Other requests
+    /// need the header, so the token doesn't end up in the logs of proxies along the way.
+    pub fn has_access_token<B>(request: &HttpRequest<B>, token: &str) -> bool {
+        has_bearer_token(request, token)
+            || is_websocket_upgrade(request)
+                && request
+                    .uri()
+                    .query()
+                    .map(parse_query)
+                    .and_then(|query| query.get("access_token").cloned())
+                    .is_some_and(|v| constant_time_eq(v.as_bytes(), token.as_bytes()))
+    }
+
+    /// Compares every byte, so the time it takes doesn't tell how much of a token was right.
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+
+    /// The schema fingerprint a generated client was made for, sent in an `X-Jabroni-Schema`
+    /// header or, again for browsers, a `schema` query parameter.
+    pub fn schema_fingerprint<B>(request: &HttpRequest<B>) -> Option<BoxStr> {
+        request
+            .headers()
+            .get("x-jabroni-schema")
+            .and_then(|v| v.to_str().ok())
+            .map(Into::into)
+            .or_else(|| {
+                request
+                    .uri()
+                    .query()
+                    .map(parse_query)
+                    .and_then(|mut query| query.remove("schema"))
+            })
+    }
+
+    pub fn is_keep_alive<B>(request: &HttpRequest<B>) -> bool {
+        let has_token = |token: &str| {
+            request
+                .headers()
+                .get_all(header::CONNECTION)
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .any(|v| v.trim().eq_ignore_ascii_case(token))
+        };
+
+        match request.version() {
+            Version::HTTP_10 => has_token("keep-alive"),
+            _ => !has_token("close"),
+        }
+    }
+
+    pub async fn read_body<R>(
+        reader: &mut R,
+        request: &HttpRequest<()>,
+    ) -> Result<Vec<u8>, HttpError>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        if request.headers().contains_key(header::TRANSFER_ENCODING) {
+            return Err(HttpError::ChunkedBody);
+        }
+
+        let length = match request.headers().get(header::CONTENT_LENGTH) {
+            Some(value) => value
+                .to_str()
+                .ok()
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .ok_or(HttpError::InvalidContentLength)?,
+            None => 0,
+        };
+
+        if length > MAX_BODY_SIZE {
+            return Err(HttpError::BodyTooLarge);
+        }
+
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await?;
+
+        Ok(body)
+    }
+
+    pub async fn write_response<W>(
+        writer: &mut W,
+        response: HttpResponse<String>,
+        keep_alive: bool,
+    ) -> std::io::Result<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let (parts, body) = response.into_parts();
+
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\n",
+            parts.status.as_str(),
+            parts.status.canonical_reason().unwrap_or_default()
+        );
+        for (name, value) in parts.headers.iter() {
+            let value = value.to_str().unwrap_or_default();
+            write!(head, "{name}: {value}\r\n").expect("failed to write to string");
+        }
+
+        let connection = if keep_alive { "keep-alive" } else { "close" };
+        write!(
+            head,
+            "content-length: {}\r\nconnection: {connection}\r\n\r\n",
+            body.len()
+        )
+        .expect("failed to write to string");
+
+        writer.write_all(head.as_bytes()).await?;
+        writer.write_all(body.as_bytes()).await?;
+        writer.flush().await
+    }
+
+    pub fn error_response(status: StatusCode, error: ErrorResponse) -> HttpResponse<String> {
+        let body =
+            serde_json::to_string(&error).expect("failed to serialize error response to json");
+        json_response(status, body)
+    }
+
+    fn json_response(status: StatusCode, body: String) -> HttpResponse<String> {
+        HttpResponse::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body)
+            .expect("failed to build http response")
+    }
+
+    pub trait HttpStatus {
+        fn status(&self) -> StatusCode;
+    }
+
+    impl<E: Error> HttpStatus for AppError<E> {
+        fn status(&self) -> StatusCode {
+            match self {
+                AppError::DatabaseError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+                AppError::TableNotFound { .. }
+                | AppError::RowNotFound
+                | AppError::DatabaseNotFound { .. } => StatusCode::NOT_FOUND,
+                AppError::ColumnsNotFound { .. }
+                | AppError::SortColumnNotFound { .. }
+                | AppError::PageNumberCanNotBeZero
+                | AppError::BatchInsertWithNoData
+                | AppError::BatchInsertWithIrregularColumns
+                | AppError::ValidationFailed { .. }
+                | AppError::InvalidValue { .. }
+                | AppError::TableNotSearchable { .. }
+                | AppError::InvalidSearch { .. }
+                | AppError::NotABlob { .. }
+                | AppError::BlobOutOfBounds { .. }
+                | AppError::InvalidSchemaChange { .. } => StatusCode::BAD_REQUEST,
+                AppError::Forbidden => StatusCode::FORBIDDEN,
+            }
+        }
+    }
+
+    pub struct RestAdapter<S> {
+        inner: S,
+    }
+
+    impl<S> RestAdapter<S> {
+        pub const fn new(inner: S) -> Self {
+            Self { inner }
+        }
+    }
+
+    impl<S, R, E> Service<HttpRequest<Vec<u8>>> for RestAdapter<S>
+    where
+        S: Service<RoutedRequest, Response = BoxStream<'static, Result<R, E>>, Error = E>,
+        R: Serialize + 'static,
+        S::Future: Send + 'static,
+        E: Error + Serialize + HttpStatus + Send + 'static,
+    {
+        type Response = HttpResponse<String>;
+        type Error = S::Error;
+        type Future = future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(ctx)
+        }
+
+        fn call(&mut self, request: HttpRequest<Vec<u8>>) -> Self::Future {
+            match to_api_request(request) {
+                Ok(req) => {
+                    let request_id = req.request.request_id().clone();
+                    // HTTP requests never ask for streamed rows, so there is a single response.
+                    self.inner
+                        .call(req)
+                        .then(|body| async move {
+                            match body {
+                                Ok(mut responses) => responses.next().await,
+                                Err(err) => Some(Err(err)),
+                            }
+                        })
+                        .map(move |body| match body {
+                            Some(body) => Ok(to_response(body, request_id)),
+                            None => {
+                                tracing::error!("request {request_id} got no response");
+                                Ok(error_response(
+                                    StatusCode::INTERNAL_SERVER_ERROR,
+                                    ErrorResponse::InternalError,
+                                ))
+                            }
+                        })
+                        .boxed()
+                }
+                Err((status, err)) => {
+                    tracing::error!("failed to route http request: {err:?}");
+                    future::ok(error_response(status, err)).boxed()
+                }
+            }
+        }
+    }
+
+    fn to_response<R, E>(body: Result<R, E>, request_id: BoxStr) -> HttpResponse<String>
+    where
+        R: Serialize,
+        E: Serialize + Error + HttpStatus,
+    {
+        match body {
+            Ok(resp) => {
+                let resp =
+                    serde_json::to_string(&resp).expect("failed to serialize response to json");
+                json_response(StatusCode::OK, resp)
+            }
+            Err(err) => {
+                tracing::error!("error occured while processing request: {err}");
+                let status = err.status();
+                let body = serde_json::to_string(&WithRequestId::new(err, Some(request_id)))
+                    .expect("failed to serialize error response to json");
+                json_response(status, body)
+            }
+        }
+    }
+
+    type RouteError = (StatusCode, ErrorResponse);
+
+    fn bad_request(message: impl Into<BoxStr>) -> RouteError {
+        (
+            StatusCode::BAD_REQUEST,
+            ErrorResponse::bad_request(message.into()),
+        )
+    }
+
+    /// Maps a plain HTTP request onto the same [`ApiRequest`] the WebSocket clients send.
+    ///
+    /// - `GET /tables/{table}?select=a,b&sort=a&order=desc&page=1&size=25` lists rows
+    /// - `GET /tables/{table}/{key}?select=a,b` gets a single row
+    /// - `POST /tables/{table}` inserts a row, or a batch of rows when the body is an array
+    /// - `PATCH /tables/{table}/{key}` updates a row
+    /// - `DELETE /tables/{table}/{key}` deletes a row
+    ///
+    /// Paths starting with `/{database}/tables` are for that database instead of the default.
+    fn to_api_request(request: HttpRequest<Vec<u8>>) -> Result<RoutedRequest, RouteError> {
+        let request_id: BoxStr = request
+            .headers()
+            .get("x-request-id")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .into();
+
+        let segments = request
+            .uri()
+            .path()
+            .trim_matches('/')
+            .split('/')
+            .map(percent_decode)
+            .collect::<Vec<_>>();
+        let segments = segments.iter().map(AsRef::as_ref).collect::<Vec<&str>>();
+        let (database, segments) = match segments.as_slice() {
+            [database, "tables", rest @ ..] if *database != "tables" => (
+                Some((*database).into()),
+                ["tables"].iter().chain(rest).copied().collect(),
+            ),
+            _ => (None, segments),
+        };
+
+        let query = parse_query(request.uri().query().unwrap_or_default());
+
+        let req = match (request.method(), segments.as_slice()) {
+            (&Method::GET, ["tables", table]) => ApiRequest::ListRows(ListRowsRequest {
+                table: (*table).into(),
+                select: parse_select(&query),
+                sort: parse_sort(&query)?,
+                page: parse_page(&query)?,
+                chunk_size: None,
+                request_id,
+            }),
+            (&Method::GET, ["tables", table, key]) => ApiRequest::GetRow(GetRowRequest {
+                table: (*table).into(),
+                key: parse_key(key),
+                select: parse_select(&query),
+                request_id,
+            }),
+            (&Method::POST, ["tables", table]) => match parse_json(request.body())? {
+                JsonValue::Array(rows) => {
+                    let data = rows
+                        .into_iter()
+                        .map(serde_json::from_value)
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| bad_request("expected an array of json objects"))?;
+
+                    ApiRequest::BatchInsertRow(BatchInsertRowRequest {
+                        table: (*table).into(),
+                        data,
+                        request_id,
+                    })
+                }
+                row => ApiRequest::InsertRow(InsertRowRequest {
+                    table: (*table).into(),
+                    data: serde_json::from_value(row)
+                        .map_err(|_| bad_request("expected a json object"))?,
+                    request_id,
+                }),
+            },
+            (&Method::PATCH, ["tables", table, key]) => ApiRequest::UpdateRow(UpdateRowRequest {
+                table: (*table).into(),
+                key: parse_key(key),
+                data: serde_json::from_value(parse_json(request.body())?)
+                    .map_err(|_| bad_request("expected a json object"))?,
+                request_id,
+            }),
+            (&Method::DELETE, ["tables", table, key]) => ApiRequest::DeleteRow(DeleteRowRequest {
+                table: (*table).into(),
+                key: parse_key(key),
+                request_id,
+            }),
+            (method, ["tables", _] | ["tables", _, _]) => {
+                return Err((
+                    StatusCode::METHOD_NOT_ALLOWED,
+                    ErrorResponse::MethodNotAllowed {
+                        method: method.as_str().into(),
+                    },
+                ))
+            }
+            _ => {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    ErrorResponse::RouteNotFound {
+                        path: request.uri().path().into(),
+                    },
+                ))
+            }
+        };
+
+        Ok(RoutedRequest {
+            database,
+            request: req,
+        })
+    }
+
+    pub fn percent_decode(s: &str) -> BoxStr {
+        percent_encoding::percent_decode_str(s)
+            .decode_utf8_lossy()
+            .into()
+    }
+
+    fn parse_query(query: &str) -> HashMap<BoxStr, BoxStr> {
+        query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (
+                    percent_decode(&key.replace('+', " ")),
+                    percent_decode(&value.replace('+', " ")),
+                )
+            })
+            .collect()
+    }
+
+    fn parse_select(query: &HashMap<BoxStr, BoxStr>) -> BoxList<BoxStr> {
+        query
+            .get("select")
+            .map(|select| {
+                select
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(Into::into)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parse_sort(query: &HashMap<BoxStr, BoxStr>) -> Result<Option<SortInfo>, RouteError> {
+        let Some(column) = query.get("sort") else {
+            return Ok(None);
+        };
+
+        let order = match query.get("order").map(|o| o.to_lowercase()).as_deref() {
+            None | Some("asc") => SortOrder::Asc,
+            Some("desc") => SortOrder::Desc,
+            Some(order) => return Err(bad_request(format!("invalid sort order: {order}"))),
+        };
+
+        Ok(Some(SortInfo {
+            column: column.clone(),
+            order,
+        }))
+    }
+
+    fn parse_page(query: &HashMap<BoxStr, BoxStr>) -> Result<Option<Pagination>, RouteError> {
+        let Some(number) = query.get("page") else {
+            return Ok(None);
+        };
+
+        let number = number
+            .parse()
+            .map_err(|_| bad_request(format!("invalid page number: {number}")))?;
+
+        let size = match query.get("size") {
+            Some(size) => size
+                .parse()
+                .map_err(|_| bad_request(format!("invalid page size: {size}")))?,
+            None => DEFAULT_PAGE_SIZE,
+        };
+
+        Ok(Some(Pagination { number, size }))
+    }
+
+    /// Keys that look like numbers are sent as numbers, everything else as a string.
+    fn parse_key(key: &str) -> JsonValue {
+        match serde_json::from_str::<JsonValue>(key) {
+            Ok(JsonValue::Number(n)) => JsonValue::Number(n),
+            _ => JsonValue::String(key.into()),
+        }
+    }
+
+    fn parse_json(body: &[u8]) -> Result<JsonValue, RouteError> {
+        serde_json::from_slice(body).map_err(|err| {
+            tracing::error!("failed to decode json request body: {err}");
+            bad_request("failed to decode request")
+        })
+    }
+
+    pub struct RestAdapterLayer;
+
+    impl<S> Layer<S> for RestAdapterLayer {
+        type Service = RestAdapter<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            RestAdapter::new(inner)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn request(uri: &str, headers: &[(&str, &str)]) -> HttpRequest<()> {
+            let mut builder = HttpRequest::builder().uri(uri);
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            builder.body(()).unwrap()
+        }
+
+        #[test]
+        fn decodes_percent_escapes() {
+            assert_eq!(&*percent_decode("caf%C3%A9%20au%20lait"), "café au lait");
+            assert_eq!(&*percent_decode("a+b"), "a+b");
+            assert_eq!(&*percent_decode("100%"), "100%");
+            assert_eq!(&*percent_decode("%FF"), "\u{FFFD}");
+        }
+
+        #[test]
+        fn parses_queries() {
+            let query = parse_query("select=Name,%20Composer&q=AC+DC&&flag&page=2");
+            assert_eq!(query.len(), 4);
+            assert_eq!(query["select"].as_ref(), "Name, Composer");
+            assert_eq!(query["q"].as_ref(), "AC DC");
+            assert_eq!(query["flag"].as_ref(), "");
+            assert_eq!(query["page"].as_ref(), "2");
+            assert!(parse_query("").is_empty());
+            assert_eq!(parse_query("a%2Bb=c%3Dd")["a+b"].as_ref(), "c=d");
+        }
+
+        #[test]
+        fn parses_keys() {
+            assert_eq!(parse_key("42"), serde_json::json!(42));
+            assert_eq!(parse_key("-1.5"), serde_json::json!(-1.5));
+            assert_eq!(parse_key("0042"), serde_json::json!("0042"));
+            assert_eq!(parse_key("abc"), serde_json::json!("abc"));
+            assert_eq!(parse_key("true"), serde_json::json!("true"));
+            assert_eq!(parse_key("\"x\""), serde_json::json!("\"x\""));
+        }
+
+        #[test]
+        fn checks_the_bearer_token() {
+            let bearer = [("authorization", "Bearer secret")];
+            assert!(has_bearer_token(&request("/", &bearer), "secret"));
+            assert!(!has_bearer_token(&request("/", &bearer), "secret2"));
+            assert!(!has_bearer_token(&request("/", &bearer), "secre"));
+            assert!(!has_bearer_token(&request("/", &[]), "secret"));
+            let basic = [("authorization", "Basic secret")];
+            assert!(!has_bearer_token(&request("/", &basic), "secret"));
+        }
+
+        #[test]
+        fn takes_the_access_token_only_on_websocket_upgrades() {
+            let upgrade = [("upgrade", "websocket")];
+            let uri = "/tables?access_token=secret";
+            assert!(has_access_token(&request(uri, &upgrade), "secret"));
+            assert!(!has_access_token(&request(uri, &upgrade), "other"));
+            assert!(!has_access_token(&request(uri, &[]), "secret"));
+
+            let bearer = [("authorization", "Bearer secret")];
+            assert!(has_access_token(&request("/tables", &bearer), "secret"));
+        }
+
+        /// Answers every request with an empty stream of responses.
+        struct Silent;
+
+        impl Service<RoutedRequest> for Silent {
+            type Response = BoxStream<'static, Result<JsonValue, AppError<rusqlite::Error>>>;
+            type Error = AppError<rusqlite::Error>;
+            type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+            fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, _: RoutedRequest) -> Self::Future {
+                future::ok(futures::stream::empty().boxed())
+            }
+        }
+
+        #[tokio::test]
+        async fn answers_requests_without_a_response_with_an_error() {
+            let request = HttpRequest::builder()
+                .uri("/tables/tracks/1")
+                .body(Vec::new())
+                .unwrap();
+            let response = RestAdapter::new(Silent).call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(response.body(), r#"{"type":"InternalError"}"#);
+        }
+    }
+}
+
+mod typescript_client {
+    use std::{
+        fmt::{Display, Write},
+        ops::Deref,
+    };
+
+    use color_eyre::eyre::Context;
+
+    use crate::{
+        db::{BlobEncoding, ColumnName, Database, SqlValueType, SqliteDatabase, TableName},
+        BoxList, BoxStr,
+    };
+
+    /// Tables with a search index, and the columns it covers.
+    type Searchable<'a> = [(ClientTable<'a>, BoxList<ColumnName>)];
+    /// Tables with blob columns, which `ReadBlob` and `WriteBlob` requests can target.
+    type BlobColumns<'a> = [(ClientTable<'a>, BoxList<ColumnName>)];
+
+    /// A table the way the client's types and helpers are named after it, `archive.albums`
+    /// in an attached database displays as `archive_albums`. Requests still send its name.
+    #[derive(Clone, Copy, PartialEq)]
+    struct ClientTable<'a>(&'a TableName);
+
+    impl Display for ClientTable<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0.identifier())
+        }
+    }
+
+    impl Deref for ClientTable<'_> {
+        type Target = TableName;
+
+        fn deref(&self) -> &TableName {
+            self.0
+        }
+    }
+
+    fn client_tables(
+        tables: &[(TableName, BoxList<ColumnName>)],
+    ) -> Vec<(ClientTable<'_>, BoxList<ColumnName>)> {
+        tables
+            .iter()
+            .map(|(table, columns)| (ClientTable(table), columns.clone()))
+            .collect()
+    }
+
+    /// Without runtime validation the client is plain TypeScript types and type guards,
+    /// so it doesn't depend on `zod` and `nanoid`.
+    pub async fn generate_typescript_client(
+        db: SqliteDatabase,
+        out_path: BoxStr,
+        database: Option<&str>,
+        runtime_validation: bool,
+    ) -> color_eyre::Result<()> {
+        tracing::info!("generating client library");
+
+        let tables = db
+            .get_user_tables()
+            .await
+            .context("failed to fetch tables")?;
+
+        let searchable = db.get_searchable_tables(&tables).await?;
+        let blob_columns = db.get_blob_columns(&tables).await?;
+
+        let (searchable, blob_columns) = (client_tables(&searchable), client_tables(&blob_columns));
+        let tables = tables.iter().map(ClientTable).collect::<Vec<_>>();
+
+        let mut client = if runtime_validation {
+            zod_schemas(&db, &tables, &searchable, &blob_columns).await?
+        } else {
+            plain_types(&db, &tables, &searchable, &blob_columns).await?
+        };
+        let fingerprint = db.schema_fingerprint().await?;
+        writeln!(
+            client,
+            "\n/** Identifies the schema this client was generated for. */\nexport const SCHEMA_FINGERPRINT = \"{fingerprint}\";"
+        )?;
+        let database = database.map_or("null".into(), |name| format!("\"{name}\""));
+        writeln!(
+            client,
+            "/** The database this client connects to, the server's default when `null`. */\nexport const DATABASE: string | null = {database};"
+        )?;
+        client.push_str(RUNTIME);
+        client.push_str(&table_helpers(
+            &tables,
+            &searchable,
+            &blob_columns,
+            runtime_validation,
+        )?);
+
+        std::fs::write(out_path.as_ref(), client)?;
+        tracing::info!("client library generated at {out_path}");
+        Ok(())
+    }
+
+    async fn zod_schemas(
+        db: &SqliteDatabase,
+        tables: &[ClientTable<'_>],
+        searchable: &Searchable<'_>,
+        blob_columns: &BlobColumns<'_>,
+    ) -> color_eyre::Result<String> {
+        let mut schema = r#"
+import { z } from "zod";
+import { nanoid } from "nanoid";
+
+export const Pagination = z
+  .object({
+    number: z.number(),
+    size: z.number(),
+  });
+
+export const Highlight = z.object({
+  open: z.string().optional(),
+  close: z.string().optional(),
+});
+
+export const Snippet = z.object({
+  open: z.string().optional(),
+  close: z.string().optional(),
+  ellipsis: z.string().optional(),
+  tokens: z.number().int().min(1).max(64).optional(),
+});
+
+export const ColumnDescription = z.object({
+  name: z.string(),
+  declared_type: z.string(),
+  affinity: z.enum(["INTEGER", "TEXT", "BLOB", "REAL", "NUMERIC"]),
+  nullable: z.boolean(),
+  default: z.string().nullable(),
+  primary_key: z.number().nullable(),
+});
+
+export const IndexDescription = z.object({
+  name: z.string(),
+  columns: z.array(z.string().nullable()),
+  unique: z.boolean(),
+  origin: z.enum(["create_index", "unique", "primary_key"]),
+  partial: z.boolean(),
+});
+
+export const ForeignKeyDescription = z.object({
+  columns: z.array(z.string()),
+  table: z.string(),
+  references: z.array(z.string().nullable()),
+  on_update: z.string(),
+  on_delete: z.string(),
+});
+
+export const TableDescription = z.object({
+  name: z.string(),
+  columns: z.array(ColumnDescription),
+  indexes: z.array(IndexDescription),
+  foreign_keys: z.array(ForeignKeyDescription),
+  strict: z.boolean(),
+});
+
+export const ViewDescription = z.object({
+  name: z.string(),
+  columns: z.array(ColumnDescription),
+});
+
+export const DescribeSchemaRequest = z.object({
+  type: z.literal("DescribeSchema"),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DescribeTableRequest = z.object({
+  type: z.literal("DescribeTable"),
+  table: z.string(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DescribeSchemaResponse = z.object({
+  type: z.literal("DescribeSchema"),
+  tables: z.array(TableDescription),
+  views: z.array(ViewDescription),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DescribeTableResponse = z.object({
+  type: z.literal("DescribeTable"),
+  table: TableDescription,
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const ColumnDefinition = z.object({
+  name: z.string(),
+  declared_type: z.string().optional(),
+  not_null: z.boolean().optional(),
+  primary_key: z.boolean().optional(),
+  unique: z.boolean().optional(),
+  default: z.union([z.string(), z.number(), z.boolean(), z.null()]).optional(),
+  references: z
+    .object({
+      table: z.string(),
+      column: z.string().optional(),
+    })
+    .optional(),
+});
+
+export const CreateTableRequest = z.object({
+  type: z.literal("CreateTable"),
+  table: z.string(),
+  columns: z.array(ColumnDefinition).min(1),
+  strict: z.boolean().optional(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const AddColumnRequest = z.object({
+  type: z.literal("AddColumn"),
+  table: z.string(),
+  column: ColumnDefinition,
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const CreateIndexRequest = z.object({
+  type: z.literal("CreateIndex"),
+  table: z.string(),
+  name: z.string(),
+  columns: z.array(z.string()).min(1),
+  unique: z.boolean().optional(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DropTableRequest = z.object({
+  type: z.literal("DropTable"),
+  table: z.string(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const CreateTableResponse = z.object({
+  type: z.literal("CreateTable"),
+  table: z.string(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const AddColumnResponse = CreateTableResponse.extend({
+  type: z.literal("AddColumn"),
+});
+
+export const CreateIndexResponse = CreateTableResponse.extend({
+  type: z.literal("CreateIndex"),
+});
+
+export const DropTableResponse = CreateTableResponse.extend({
+  type: z.literal("DropTable"),
+});
+
+export const SchemaChanged = z.object({
+  type: z.literal("SchemaChanged"),
+  table: z.string(),
+  change: z.enum(["CreateTable", "AddColumn", "CreateIndex", "DropTable"]),
+});
+export type SchemaChanged = z.infer<typeof SchemaChanged>;
+
+"#
+        .to_string();
+
+        let blob = db.blob_encoding();
+
+        for table in tables.iter() {
+            let primary_key_type = db.get_primary_key_type(table).await?;
+            let primary_key_type_schema = format!(
+                "export const {table}_primary_key = {};",
+                zod_type(&primary_key_type, blob)
+            );
+
+            writeln!(schema, "{primary_key_type_schema}")?;
+
+            let columns = db.get_column_types(table).await?;
+
+            let mut table_schema = format!("export const {table}_schema = z.object({{");
+            for (col, typ) in columns.iter() {
+                let typ = match typ {
+                    (typ, true) => format!("{},", zod_type(typ, blob)),
+                    (typ, false) => format!("{}.nullable().optional(),", zod_type(typ, blob)),
+                };
+                writeln!(table_schema, "  {col}: {typ}")?;
+            }
+            writeln!(table_schema, "}});")?;
+            writeln!(schema, "{table_schema}")?;
+
+            let mut table_schema = format!("export const {table}_schema_optional = z.object({{");
+            for (col, typ) in columns {
+                let typ = format!("{}.nullable().optional(),", zod_type(&typ.0, blob));
+                writeln!(table_schema, "  {col}: {typ}")?;
+            }
+            writeln!(table_schema, "}});")?;
+            writeln!(schema, "{table_schema}")?;
+        }
+
+        for table in tables.iter() {
+            let name = table.as_str();
+            let columns = db.get_columns(table).await?;
+
+            let columns_schema = columns
+                .iter()
+                .map(|col| format!("z.literal('{col}')"))
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(
+                schema,
+                "const {table}_columns = z.union([{columns_schema}]);"
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_sort_options = z
+  .object({{
+    column: {table}_columns,
+    order: z.enum(["Asc", "Desc"]),
+  }});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_list_rows_request = z.object({{
+  type: z.literal("ListRows"),
+  table: z.literal('{name}'),
+  select: z.array({table}_columns).default([]),
+  sort: {table}_sort_options.optional(),
+  page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_get_row_request = z.object({{
+  type: z.literal("GetRow"),
+  table: z.literal('{name}'),
+  key: {table}_primary_key,
+  select: z.array({table}_columns).default([]),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_insert_row_request = z.object({{
+  type: z.literal("InsertRow"),
+  table: z.literal('{name}'),
+  data: {table}_schema,
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_batch_insert_row_request = z.object({{
+  type: z.literal("BatchInsertRow"),
+  table: z.literal('{name}'),
+  data: z.array({table}_schema),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_delete_row_request = z.object({{
+  type: z.literal("DeleteRow"),
+  table: z.literal('{name}'),
+  key: {table}_primary_key,
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_update_row_request = z.object({{
+  type: z.literal("UpdateRow"),
+  table: z.literal('{name}'),
+  key: {table}_primary_key,
+  data: {table}_schema_optional,
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+        }
+
+        let list_rows_request = tables
+            .iter()
+            .map(|table| format!("{table}_list_rows_request"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            schema,
+            "export const ListRowsRequest = z.discriminatedUnion('table', [{list_rows_request}]);"
+        )?;
+
+        let get_row_request = tables
+            .iter()
+            .map(|table| format!("{table}_get_row_request"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            schema,
+            "export const GetRowRequest = z.discriminatedUnion('table', [{get_row_request}]);"
+        )?;
+
+        let insert_row_request = tables
+            .iter()
+            .map(|table| format!("{table}_insert_row_request"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const InsertRowRequest = z.discriminatedUnion('table', [{insert_row_request}]);"
+    )?;
+
+        let batch_insert_row_request = tables
+            .iter()
+            .map(|table| format!("{table}_batch_insert_row_request"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const BatchInsertRowRequest = z.discriminatedUnion('table', [{batch_insert_row_request}]);"
+    )?;
+
+        let delete_row_request = tables
+            .iter()
+            .map(|table| format!("{table}_delete_row_request"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const DeleteRowRequest = z.discriminatedUnion('table', [{delete_row_request}]);"
+    )?;
+
+        let update_row_request = tables
+            .iter()
+            .map(|table| format!("{table}_update_row_request"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const UpdateRowRequest = z.discriminatedUnion('table', [{update_row_request}]);"
+    )?;
+
+        let mut requests = vec![
+            "ListRowsRequest",
+            "GetRowRequest",
+            "InsertRowRequest",
+            "BatchInsertRowRequest",
+            "DeleteRowRequest",
+            "UpdateRowRequest",
+            "DescribeSchemaRequest",
+            "DescribeTableRequest",
+            "CreateTableRequest",
+            "AddColumnRequest",
+            "CreateIndexRequest",
+            "DropTableRequest",
+        ];
+        if !searchable.is_empty() {
+            for (table, _) in searchable {
+                let name = table.as_str();
+                writeln!(
+                    schema,
+                    r#"
+export const {table}_search_request = z.object({{
+  type: z.literal("Search"),
+  table: z.literal('{name}'),
+  query: z.string(),
+  select: z.array({table}_columns).default([]),
+  highlight: Highlight.optional(),
+  snippet: Snippet.optional(),
+  page: Pagination.optional(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+                )?;
+            }
+
+            let search_request = searchable
+                .iter()
+                .map(|(table, _)| format!("{table}_search_request"))
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(
+                schema,
+                "export const SearchRequest = z.discriminatedUnion('table', [{search_request}]);"
+            )?;
+            requests.push("SearchRequest");
+        }
+        if !blob_columns.is_empty() {
+            let blob_schema = zod_type(&SqlValueType::Blob, blob);
+            for (table, columns) in blob_columns {
+                let name = table.as_str();
+                // A `z.enum` of the names, unlike a `z.union` of literals it takes a single one.
+                let columns_schema = columns
+                    .iter()
+                    .map(|col| format!("'{col}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(
+                    schema,
+                    r#"
+const {table}_blob_columns = z.enum([{columns_schema}]);
+
+export const {table}_read_blob_request = z.object({{
+  type: z.literal("ReadBlob"),
+  table: z.literal('{name}'),
+  key: {table}_primary_key,
+  column: {table}_blob_columns,
+  offset: z.number().int().nonnegative().optional(),
+  length: z.number().int().nonnegative().optional(),
+  request_id: z.string().default(() => nanoid()),
+}});
+
+export const {table}_write_blob_request = z.object({{
+  type: z.literal("WriteBlob"),
+  table: z.literal('{name}'),
+  key: {table}_primary_key,
+  column: {table}_blob_columns,
+  offset: z.number().int().nonnegative().optional(),
+  data: {blob_schema},
+  size: z.number().int().nonnegative().optional(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+                )?;
+            }
+
+            for (name, suffix) in [("ReadBlob", "read_blob"), ("WriteBlob", "write_blob")] {
+                let variants = blob_columns
+                    .iter()
+                    .map(|(table, _)| format!("{table}_{suffix}_request"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(
+                    schema,
+                    "export const {name}Request = z.discriminatedUnion('table', [{variants}]);"
+                )?;
+            }
+            requests.extend(["ReadBlobRequest", "WriteBlobRequest"]);
+        }
+
+        writeln!(
+            schema,
+            "export const ApiRequest = z.union([{}]);",
+            requests.join(", ")
+        )?;
+
+        for table in tables.iter() {
+            let name = table.as_str();
+            writeln!(
+                schema,
+                r#"
+export const {table}_list_rows_response = z.object({{
+  type: z.literal('ListRows'),
+  table: z.literal('{name}'),
+  rows: z.array({table}_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_list_rows_chunk_response = z.object({{
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('{name}'),
+  rows: z.array({table}_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_list_rows_end_response = z.object({{
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('{name}'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_get_row_response = z.object({{
+  type: z.literal('GetRow'),
+  table: z.literal('{name}'),
+  row: {table}_schema_optional,
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_insert_row_response = z.object({{
+  type: z.literal('InsertRow'),
+  table: z.literal('{name}'),
+  inserted_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_batch_insert_row_response = z.object({{
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('{name}'),
+  inserted_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_delete_row_response = z.object({{
+  type: z.literal('DeleteRow'),
+  table: z.literal('{name}'),
+  deleted_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+
+            writeln!(
+                schema,
+                r#"
+export const {table}_update_row_response = z.object({{
+  type: z.literal('UpdateRow'),
+  table: z.literal('{name}'),
+  updated_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+            )?;
+        }
+
+        let list_rows_response = tables
+            .iter()
+            .map(|table| format!("{table}_list_rows_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const ListRowsResponse = z.discriminatedUnion('table', [{list_rows_response}]);"
+    )?;
+
+        let list_rows_chunk_response = tables
+            .iter()
+            .map(|table| format!("{table}_list_rows_chunk_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            schema,
+            "export const ListRowsChunkResponse = z.discriminatedUnion('table', [{list_rows_chunk_response}]);"
+        )?;
+
+        let list_rows_end_response = tables
+            .iter()
+            .map(|table| format!("{table}_list_rows_end_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            schema,
+            "export const ListRowsEndResponse = z.discriminatedUnion('table', [{list_rows_end_response}]);"
+        )?;
+
+        let get_row_response = tables
+            .iter()
+            .map(|table| format!("{table}_get_row_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+            schema,
+            "export const GetRowResponse = z.discriminatedUnion('table', [{get_row_response}]);"
+        )?;
+
+        let insert_row_response = tables
+            .iter()
+            .map(|table| format!("{table}_insert_row_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const InsertRowResponse = z.discriminatedUnion('table', [{insert_row_response}]);"
+    )?;
+
+        let batch_insert_row_response = tables
+            .iter()
+            .map(|table| format!("{table}_batch_insert_row_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const BatchInsertRowResponse = z.discriminatedUnion('table', [{batch_insert_row_response}]);"
+    )?;
+
+        let delete_row_response = tables
+            .iter()
+            .map(|table| format!("{table}_delete_row_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const DeleteRowResponse = z.discriminatedUnion('table', [{delete_row_response}]);"
+    )?;
+
+        let update_row_response = tables
+            .iter()
+            .map(|table| format!("{table}_update_row_response"))
+            .collect::<Vec<_>>()
+            .join(",");
+        writeln!(
+        schema,
+        "export const UpdateRowResponse = z.discriminatedUnion('table', [{update_row_response}]);"
+    )?;
+
+        let mut responses = vec![
+            "ListRowsResponse",
+            "ListRowsChunkResponse",
+            "ListRowsEndResponse",
+            "GetRowResponse",
+            "InsertRowResponse",
+            "BatchInsertRowResponse",
+            "DeleteRowResponse",
+            "UpdateRowResponse",
+            "DescribeSchemaResponse",
+            "DescribeTableResponse",
+            "CreateTableResponse",
+            "AddColumnResponse",
+            "CreateIndexResponse",
+            "DropTableResponse",
+        ];
+        if !searchable.is_empty() {
+            for (table, columns) in searchable {
+                let name = table.as_str();
+                let mut markup = format!("export const {table}_search_markup = z.object({{");
+                for column in columns.iter() {
+                    writeln!(markup, "  {column}: z.string().nullable(),")?;
+                }
+                writeln!(markup, "}});")?;
+
+                writeln!(
+                    schema,
+                    r#"
+{markup}
+export const {table}_search_response = z.object({{
+  type: z.literal('Search'),
+  table: z.literal('{name}'),
+  hits: z.array(z.object({{
+    row: {table}_schema_optional,
+    rank: z.number(),
+    highlights: {table}_search_markup.optional(),
+    snippets: {table}_search_markup.optional(),
+  }})),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+                )?;
+            }
+
+            let search_response = searchable
+                .iter()
+                .map(|(table, _)| format!("{table}_search_response"))
+                .collect::<Vec<_>>()
+                .join(",");
+            writeln!(
+                schema,
+                "export const SearchResponse = z.discriminatedUnion('table', [{search_response}]);"
+            )?;
+            responses.push("SearchResponse");
+        }
+        if !blob_columns.is_empty() {
+            let blob_schema = zod_type(&SqlValueType::Blob, blob);
+            for (table, _) in blob_columns {
+                let name = table.as_str();
+                writeln!(
+                    schema,
+                    r#"
+export const {table}_read_blob_response = z.object({{
+  type: z.literal('ReadBlob'),
+  table: z.literal('{name}'),
+  column: {table}_blob_columns,
+  offset: z.number(),
+  data: {blob_schema},
+  size: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+
+export const {table}_write_blob_response = z.object({{
+  type: z.literal('WriteBlob'),
+  table: z.literal('{name}'),
+  column: {table}_blob_columns,
+  written_bytes: z.number(),
+  size: z.number(),
+  request_id: z.string().default(() => nanoid()),
+}});
+"#
+                )?;
+            }
+
+            for (name, suffix) in [("ReadBlob", "read_blob"), ("WriteBlob", "write_blob")] {
+                let variants = blob_columns
+                    .iter()
+                    .map(|(table, _)| format!("{table}_{suffix}_response"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                writeln!(
+                    schema,
+                    "export const {name}Response = z.discriminatedUnion('table', [{variants}]);"
+                )?;
+            }
+            responses.extend(["ReadBlobResponse", "WriteBlobResponse"]);
+        }
+
+        writeln!(
+            schema,
+            "export const ApiResponse = z.union([{}]);",
+            responses.join(", ")
+        )?;
+
+        writeln!(
+            schema,
+            r#"
+export const BadRequest = z.object({{
+  message: z.string(),
+}});
+
+export const TableNotFound = z.object({{
+  table: z.string(),
+}});
+
+export const ColumnsNotFound = z.object({{
+  columns: z.array(z.string()),
+}});
+
+export const SortColumnNotFound = z.object({{
+  column: z.string(),
+}});
+
+export const InvalidColumn = z.object({{
+  column: z.string(),
+  row: z.number().optional(),
+  message: z.string(),
+}});
+
+export const ErrorResponse = z.discriminatedUnion("type", [
+  z.object({{ type: z.literal("BadRequest"), ...BadRequest.shape }}),
+  z.object({{ type: z.literal("NonTextMessage") }}),
+  z.object({{ type: z.literal("TableNotFound"), ...TableNotFound.shape }}),
+  z.object({{ type: z.literal("ColumnsNotFound"), ...ColumnsNotFound.shape }}),
+  z.object({{ type: z.literal("SortColumnNotFound"), ...SortColumnNotFound.shape }}),
+  z.object({{ type: z.literal("PageNumberCanNotBeZero") }}),
+  z.object({{ type: z.literal("RowNotFound") }}),
+  z.object({{ type: z.literal("DatabaseError") }}),
+  z.object({{ type: z.literal("ValidationFailed"), errors: z.array(InvalidColumn) }}),
+  z.object({{
+    type: z.literal("InvalidValue"),
+    column: z.string().optional(),
+    message: z.string(),
+  }}),
+  z.object({{ type: z.literal("TableNotSearchable"), table: z.string() }}),
+  z.object({{ type: z.literal("InvalidSearch"), message: z.string() }}),
+  z.object({{ type: z.literal("NotABlob"), column: z.string() }}),
+  z.object({{ type: z.literal("BlobOutOfBounds"), size: z.number() }}),
+  z.object({{ type: z.literal("Forbidden") }}),
+  z.object({{ type: z.literal("InvalidSchemaChange"), message: z.string() }}),
+  z.object({{ type: z.literal("SchemaMismatch"), server: z.string(), client: z.string() }}),
+  z.object({{ type: z.literal("UnsupportedProtocolVersion"), supported: z.array(z.number()) }}),
+  z.object({{ type: z.literal("DatabaseNotFound"), database: z.string() }}),
+]);
+export type Request = z.infer<typeof ApiRequest>;
+export type ApiError = z.infer<typeof ErrorResponse>;
+export type Response =
+  | {{ data: z.infer<typeof ApiResponse> }}
+  | {{ error: ApiError }};
+
+const newRequestId = () => nanoid();
+
+function narrow<T extends z.ZodTypeAny>(
+  resp: Response,
+  schema: T,
+): Result<z.infer<T>> {{
+  return "data" in resp ? {{ data: schema.parse(resp.data) }} : resp;
+}}
+
+function parseMessage(message: unknown): Response {{
+  const error = ErrorResponse.safeParse(message);
+  if (error.data) {{
+    return {{ error: error.data }};
+  }}
+
+  return {{ data: ApiResponse.parse(message) }};
+}}
+"#
+        )?;
+
+        Ok(schema)
+    }
+
+    async fn plain_types(
+        db: &SqliteDatabase,
+        tables: &[ClientTable<'_>],
+        searchable: &Searchable<'_>,
+        blob_columns: &BlobColumns<'_>,
+    ) -> color_eyre::Result<String> {
+        let mut types = r#"
+export type Pagination = {
+  number: number;
+  size: number;
+};
+
+export type Highlight = {
+  open?: string;
+  close?: string;
+};
+
+export type Snippet = {
+  open?: string;
+  close?: string;
+  ellipsis?: string;
+  /** between 1 and 64 */
+  tokens?: number;
+};
+
+export type SortOrder = "Asc" | "Desc";
+
+export type ColumnDescription = {
+  name: string;
+  declared_type: string;
+  affinity: "INTEGER" | "TEXT" | "BLOB" | "REAL" | "NUMERIC";
+  nullable: boolean;
+  default: string | null;
+  /** position in the primary key, starting at 1 */
+  primary_key: number | null;
+};
+
+export type IndexDescription = {
+  name: string;
+  /** `null` for expressions */
+  columns: (string | null)[];
+  unique: boolean;
+  origin: "create_index" | "unique" | "primary_key";
+  partial: boolean;
+};
+
+export type ForeignKeyDescription = {
+  columns: string[];
+  table: string;
+  /** `null` when referencing the primary key implicitly */
+  references: (string | null)[];
+  on_update: string;
+  on_delete: string;
+};
+
+export type TableDescription = {
+  name: string;
+  columns: ColumnDescription[];
+  indexes: IndexDescription[];
+  foreign_keys: ForeignKeyDescription[];
+  strict: boolean;
+};
+
+export type ViewDescription = {
+  name: string;
+  columns: ColumnDescription[];
+};
+
+export type DescribeSchemaRequest = {
+  type: "DescribeSchema";
+  request_id?: string;
+};
+
+export type DescribeTableRequest = {
+  type: "DescribeTable";
+  table: string;
+  request_id?: string;
+};
+
+export type DescribeSchemaResponse = {
+  type: "DescribeSchema";
+  tables: TableDescription[];
+  views: ViewDescription[];
+  request_id: string;
+};
+
+export type DescribeTableResponse = {
+  type: "DescribeTable";
+  table: TableDescription;
+  request_id: string;
+};
+
+export type ColumnDefinition = {
+  name: string;
+  declared_type?: string;
+  not_null?: boolean;
+  /** every column marked here becomes part of the primary key */
+  primary_key?: boolean;
+  unique?: boolean;
+  default?: string | number | boolean | null;
+  /** the referenced table's primary key when `column` is missing */
+  references?: { table: string; column?: string };
+};
+
+export type CreateTableRequest = {
+  type: "CreateTable";
+  table: string;
+  columns: ColumnDefinition[];
+  strict?: boolean;
+  request_id?: string;
+};
+
+export type AddColumnRequest = {
+  type: "AddColumn";
+  table: string;
+  column: ColumnDefinition;
+  request_id?: string;
+};
+
+export type CreateIndexRequest = {
+  type: "CreateIndex";
+  table: string;
+  name: string;
+  columns: string[];
+  unique?: boolean;
+  request_id?: string;
+};
+
+export type DropTableRequest = {
+  type: "DropTable";
+  table: string;
+  request_id?: string;
+};
+
+type SchemaChangeResponse<T> = {
+  type: T;
+  table: string;
+  request_id: string;
+};
+
+export type CreateTableResponse = SchemaChangeResponse<"CreateTable">;
+export type AddColumnResponse = SchemaChangeResponse<"AddColumn">;
+export type CreateIndexResponse = SchemaChangeResponse<"CreateIndex">;
+export type DropTableResponse = SchemaChangeResponse<"DropTable">;
+
+export type SchemaChanged = {
+  type: "SchemaChanged";
+  table: string;
+  change: "CreateTable" | "AddColumn" | "CreateIndex" | "DropTable";
+};
+
+"#
+        .to_string();
+
+        let blob = db.blob_encoding();
+
+        for table in tables.iter() {
+            let name = table.as_str();
+            let primary_key_type = db.get_primary_key_type(table).await?;
+            writeln!(
+                types,
+                "export type {table}_primary_key = {};",
+                value_type(&primary_key_type, blob)
+            )?;
+
+            let columns = db.get_column_types(table).await?;
+
+            writeln!(types, "export type {table}_schema = {{")?;
+            for (col, (typ, required)) in columns.iter() {
+                if *typ == SqlValueType::Blob {
+                    writeln!(types, "  /** {} */", blob.description())?;
+                }
+                let typ = value_type(typ, blob);
+                match (typ, required) {
+                    (typ, true) => writeln!(types, "  {col}: {typ};")?,
+                    ("null", false) => writeln!(types, "  {col}?: null;")?,
+                    (typ, false) => writeln!(types, "  {col}?: {typ} | null;")?,
+                }
+            }
+            writeln!(types, "}};")?;
+            writeln!(types)?;
+
+            writeln!(types, "export type {table}_schema_optional = {{")?;
+            for (col, (typ, _)) in columns.iter() {
+                if *typ == SqlValueType::Blob {
+                    writeln!(types, "  /** {} */", blob.description())?;
+                }
+                match value_type(typ, blob) {
+                    "null" => writeln!(types, "  {col}?: null;")?,
+                    typ => writeln!(types, "  {col}?: {typ} | null;")?,
+                }
+            }
+            writeln!(types, "}};")?;
+            writeln!(types)?;
+
+            let columns_type = columns
+                .iter()
+                .map(|(col, _)| format!("'{col}'"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            writeln!(types, "export type {table}_columns = {columns_type};")?;
+
+            writeln!(
+                types,
+                r#"
+export type {table}_sort_options = {{
+  column: {table}_columns;
+  order: SortOrder;
+}};
+
+export type {table}_list_rows_request = {{
+  type: "ListRows";
+  table: '{name}';
+  select: {table}_columns[];
+  sort?: {table}_sort_options;
+  page?: Pagination;
+  chunk_size?: number;
+  request_id?: string;
+}};
+
+export type {table}_get_row_request = {{
+  type: "GetRow";
+  table: '{name}';
+  key: {table}_primary_key;
+  select: {table}_columns[];
+  request_id?: string;
+}};
+
+export type {table}_insert_row_request = {{
+  type: "InsertRow";
+  table: '{name}';
+  data: {table}_schema;
+  request_id?: string;
+}};
+
+export type {table}_batch_insert_row_request = {{
+  type: "BatchInsertRow";
+  table: '{name}';
+  data: {table}_schema[];
+  request_id?: string;
+}};
+
+export type {table}_delete_row_request = {{
+  type: "DeleteRow";
+  table: '{name}';
+  key: {table}_primary_key;
+  request_id?: string;
+}};
+
+export type {table}_update_row_request = {{
+  type: "UpdateRow";
+  table: '{name}';
+  key: {table}_primary_key;
+  data: {table}_schema_optional;
+  request_id?: string;
+}};
+
+export type {table}_list_rows_response = {{
+  type: 'ListRows';
+  table: '{name}';
+  rows: {table}_schema_optional[];
+  request_id: string;
+}};
+
+export type {table}_list_rows_chunk_response = {{
+  type: 'ListRowsChunk';
+  table: '{name}';
+  rows: {table}_schema_optional[];
+  request_id: string;
+}};
+
+export type {table}_list_rows_end_response = {{
+  type: 'ListRowsEnd';
+  table: '{name}';
+  total_rows: number;
+  request_id: string;
+}};
+
+export type {table}_get_row_response = {{
+  type: 'GetRow';
+  table: '{name}';
+  row: {table}_schema_optional;
+  request_id: string;
+}};
+
+export type {table}_insert_row_response = {{
+  type: 'InsertRow';
+  table: '{name}';
+  inserted_rows: number;
+  request_id: string;
+}};
+
+export type {table}_batch_insert_row_response = {{
+  type: 'BatchInsertRow';
+  table: '{name}';
+  inserted_rows: number;
+  request_id: string;
+}};
+
+export type {table}_delete_row_response = {{
+  type: 'DeleteRow';
+  table: '{name}';
+  deleted_rows: number;
+  request_id: string;
+}};
+
+export type {table}_update_row_response = {{
+  type: 'UpdateRow';
+  table: '{name}';
+  updated_rows: number;
+  request_id: string;
+}};
+"#
+            )?;
+        }
+
+        for (table, columns) in searchable {
+            let name = table.as_str();
+            writeln!(
+                types,
+                r#"export type {table}_search_request = {{
+  type: "Search";
+  table: '{name}';
+  query: string;
+  select: {table}_columns[];
+  highlight?: Highlight;
+  snippet?: Snippet;
+  page?: Pagination;
+  request_id?: string;
+}};
+"#
+            )?;
+
+            writeln!(types, "export type {table}_search_markup = {{")?;
+            for column in columns.iter() {
+                writeln!(types, "  {column}: string | null;")?;
+            }
+            writeln!(types, "}};")?;
+
+            writeln!(
+                types,
+                r#"
+export type {table}_search_response = {{
+  type: 'Search';
+  table: '{name}';
+  hits: {{
+    row: {table}_schema_optional;
+    rank: number;
+    highlights?: {table}_search_markup;
+    snippets?: {table}_search_markup;
+  }}[];
+  request_id: string;
+}};
+"#
+            )?;
+        }
+
+        for (table, columns) in blob_columns {
+            let name = table.as_str();
+            let columns_type = columns
+                .iter()
+                .map(|col| format!("'{col}'"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            let blob_type = value_type(&SqlValueType::Blob, blob);
+            let description = blob.description();
+            writeln!(
+                types,
+                r#"export type {table}_blob_columns = {columns_type};
+
+export type {table}_read_blob_request = {{
+  type: "ReadBlob";
+  table: '{name}';
+  key: {table}_primary_key;
+  column: {table}_blob_columns;
+  offset?: number;
+  /** up to the end of the blob when missing */
+  length?: number;
+  request_id?: string;
+}};
+
+export type {table}_write_blob_request = {{
+  type: "WriteBlob";
+  table: '{name}';
+  key: {table}_primary_key;
+  column: {table}_blob_columns;
+  offset?: number;
+  /** {description} */
+  data: {blob_type};
+  /** replaces the blob with this many zero bytes before writing */
+  size?: number;
+  request_id?: string;
+}};
+
+export type {table}_read_blob_response = {{
+  type: 'ReadBlob';
+  table: '{name}';
+  column: {table}_blob_columns;
+  offset: number;
+  /** {description} */
+  data: {blob_type};
+  size: number;
+  request_id: string;
+}};
+
+export type {table}_write_blob_response = {{
+  type: 'WriteBlob';
+  table: '{name}';
+  column: {table}_blob_columns;
+  written_bytes: number;
+  size: number;
+  request_id: string;
+}};
+"#
+            )?;
+        }
+
+        for kind in ["request", "response"] {
+            let streamed: &[_] = match kind {
+                "response" => &[
+                    ("ListRowsChunk", "list_rows_chunk"),
+                    ("ListRowsEnd", "list_rows_end"),
+                ],
+                _ => &[],
+            };
+
+            let mut unions = Vec::new();
+            for (name, suffix) in [
+                ("ListRows", "list_rows"),
+                ("GetRow", "get_row"),
+                ("InsertRow", "insert_row"),
+                ("BatchInsertRow", "batch_insert_row"),
+                ("DeleteRow", "delete_row"),
+                ("UpdateRow", "update_row"),
+            ]
+            .iter()
+            .chain(streamed)
+            {
+                let union = format!("{name}{}", capitalize(kind));
+                let variants = tables
+                    .iter()
+                    .map(|table| format!("{table}_{suffix}_{kind}"))
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                writeln!(types, "export type {union} = {variants};")?;
+                unions.push(union);
+            }
+            if !searchable.is_empty() {
+                let union = format!("Search{}", capitalize(kind));
+                let variants = searchable
+                    .iter()
+                    .map(|(table, _)| format!("{table}_search_{kind}"))
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                writeln!(types, "export type {union} = {variants};")?;
+                unions.push(union);
+            }
+            if !blob_columns.is_empty() {
+                for (name, suffix) in [("ReadBlob", "read_blob"), ("WriteBlob", "write_blob")] {
+                    let union = format!("{name}{}", capitalize(kind));
+                    let variants = blob_columns
+                        .iter()
+                        .map(|(table, _)| format!("{table}_{suffix}_{kind}"))
+                        .collect::<Vec<_>>()
+                        .join(" | ");
+                    writeln!(types, "export type {union} = {variants};")?;
+                    unions.push(union);
+                }
+            }
+            for name in [
+                "DescribeSchema",
+                "DescribeTable",
+                "CreateTable",
+                "AddColumn",
+                "CreateIndex",
+                "DropTable",
+            ] {
+                unions.push(format!("{name}{}", capitalize(kind)));
+            }
+            writeln!(
+                types,
+                "export type Api{} = {};",
+                capitalize(kind),
+                unions.join(" | ")
+            )?;
+            writeln!(types)?;
+        }
+
+        let table_names = tables
+            .iter()
+            .map(|table| format!("'{}'", table.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(
+            types,
+            r#"export type InvalidColumn = {{
+  column: string;
+  row?: number;
+  message: string;
+}};
+
+export type ErrorResponse =
+  | {{ type: "BadRequest"; message: string }}
+  | {{ type: "NonTextMessage" }}
+  | {{ type: "TableNotFound"; table: string }}
+  | {{ type: "ColumnsNotFound"; columns: string[] }}
+  | {{ type: "SortColumnNotFound"; column: string }}
+  | {{ type: "PageNumberCanNotBeZero" }}
+  | {{ type: "RowNotFound" }}
+  | {{ type: "DatabaseError" }}
+  | {{ type: "ValidationFailed"; errors: InvalidColumn[] }}
+  | {{ type: "InvalidValue"; column?: string; message: string }}
+  | {{ type: "TableNotSearchable"; table: string }}
+  | {{ type: "InvalidSearch"; message: string }}
+  | {{ type: "NotABlob"; column: string }}
+  | {{ type: "BlobOutOfBounds"; size: number }}
+  | {{ type: "Forbidden" }}
+  | {{ type: "InvalidSchemaChange"; message: string }}
+  | {{ type: "SchemaMismatch"; server: string; client: string }}
+  | {{ type: "UnsupportedProtocolVersion"; supported: number[] }}
+  | {{ type: "DatabaseNotFound"; database: string }};
+
+export type Request = ApiRequest;
+export type ApiError = ErrorResponse;
+export type Response =
+  | {{ data: ApiResponse }}
+  | {{ error: ApiError }};
+
+const TABLES: unknown[] = [{table_names}];
+
+const ERROR_TYPES: unknown[] = [
+  "BadRequest",
+  "NonTextMessage",
+  "TableNotFound",
+  "ColumnsNotFound",
+  "SortColumnNotFound",
+  "PageNumberCanNotBeZero",
+  "RowNotFound",
+  "DatabaseError",
+  "ValidationFailed",
+  "InvalidValue",
+  "TableNotSearchable",
+  "InvalidSearch",
+  "NotABlob",
+  "BlobOutOfBounds",
+  "Forbidden",
+  "InvalidSchemaChange",
+  "SchemaMismatch",
+  "UnsupportedProtocolVersion",
+  "DatabaseNotFound",
+];
+
+function isObject(value: unknown): value is Record<string, unknown> {{
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}}
+
+export function isErrorResponse(value: unknown): value is ErrorResponse {{
+  return isObject(value) && ERROR_TYPES.includes(value.type);
+}}
+
+export function isApiResponse(value: unknown): value is ApiResponse {{
+  if (!isObject(value) || typeof value.request_id !== "string") {{
+    return false;
+  }}
+
+  switch (value.type) {{
+    case "DescribeSchema":
+      return Array.isArray(value.tables) && Array.isArray(value.views);
+    case "DescribeTable":
+      return isObject(value.table) && Array.isArray(value.table.columns);
+    case "CreateTable":
+    case "AddColumn":
+    case "CreateIndex":
+    case "DropTable":
+      return typeof value.table === "string";
+  }}
+
+  if (!TABLES.includes(value.table)) {{
+    return false;
+  }}
+
+  switch (value.type) {{
+    case "ListRows":
+    case "ListRowsChunk":
+      return Array.isArray(value.rows) && value.rows.every(isObject);
+    case "ListRowsEnd":
+      return typeof value.total_rows === "number";
+    case "Search":
+      return (
+        Array.isArray(value.hits) &&
+        value.hits.every(
+          (hit) => isObject(hit) && isObject(hit.row) && typeof hit.rank === "number",
+        )
+      );
+    case "GetRow":
+      return isObject(value.row);
+    case "InsertRow":
+    case "BatchInsertRow":
+      return typeof value.inserted_rows === "number";
+    case "DeleteRow":
+      return typeof value.deleted_rows === "number";
+    case "UpdateRow":
+      return typeof value.updated_rows === "number";
+    case "ReadBlob":
+      return typeof value.offset === "number" && typeof value.size === "number";
+    case "WriteBlob":
+      return typeof value.written_bytes === "number" && typeof value.size === "number";
+    default:
+      return false;
+  }}
+}}
+
+const newRequestId = () => crypto.randomUUID();
+
+function narrow<T extends ApiResponse>(resp: Response): Result<T> {{
+  return resp as Result<T>;
+}}
+
+function parseMessage(message: unknown): Response {{
+  if (isErrorResponse(message)) {{
+    return {{ error: message }};
+  }}
+
+  if (isApiResponse(message)) {{
+    return {{ data: message }};
+  }}
+
+  throw new Error("received an unexpected message from the server");
+}}"#
+        )?;
+
+        Ok(types)
+    }
+
+    fn zod_type(typ: &SqlValueType, blob: BlobEncoding) -> String {
+        let zod = match typ {
+            SqlValueType::Null => "z.null()",
+            SqlValueType::Integer
+            | SqlValueType::Real
+            | SqlValueType::Numeric
+            | SqlValueType::Decimal => "z.number()",
+            SqlValueType::Text | SqlValueType::Date | SqlValueType::DateTime => "z.string()",
+            SqlValueType::Boolean => "z.boolean()",
+            SqlValueType::Json => "z.unknown()",
+            // Binary frames carry blobs as raw bytes.
+            SqlValueType::Blob => {
+                let encoded = match blob {
+                    BlobEncoding::Array => "z.array(z.number())",
+                    BlobEncoding::Base64 | BlobEncoding::Hex => "z.string()",
+                };
+                return format!(
+                    r#"{encoded}.describe("{}").or(z.instanceof(Uint8Array))"#,
+                    blob.description()
+                );
+            }
+        };
+        zod.into()
+    }
+
+    fn value_type(typ: &SqlValueType, blob: BlobEncoding) -> &'static str {
+        match typ {
+            SqlValueType::Null => "null",
+            SqlValueType::Integer
+            | SqlValueType::Real
+            | SqlValueType::Numeric
+            | SqlValueType::Decimal => "number",
+            SqlValueType::Text | SqlValueType::Date | SqlValueType::DateTime => "string",
+            SqlValueType::Boolean => "boolean",
+            SqlValueType::Json => "unknown",
+            // Binary frames carry blobs as raw bytes.
+            SqlValueType::Blob => match blob {
+                BlobEncoding::Array => "number[] | Uint8Array",
+                BlobEncoding::Base64 | BlobEncoding::Hex => "string | Uint8Array",
+            },
+        }
+    }
+
+    /// A `list`/`get`/`insert`/`batchInsert`/`update`/`delete` object per table whose
+    /// responses are narrowed to that table.
+    fn table_helpers(
+        tables: &[ClientTable<'_>],
+        searchable: &Searchable<'_>,
+        blob_columns: &BlobColumns<'_>,
+        runtime_validation: bool,
+    ) -> color_eyre::Result<String> {
+        let infer = |name: &str| {
+            if runtime_validation {
+                format!("z.infer<typeof {name}>")
+            } else {
+                name.to_string()
+            }
+        };
+        let narrow = |name: &str| {
+            if runtime_validation {
+                format!("(resp) => narrow(resp, {name})")
+            } else {
+                format!("(resp) => narrow<{name}>(resp)")
+            }
+        };
+
+        let mut helpers = r#"
+export type Fetch = {
+  (request: Request, options?: FetchOptions): Promise<Response>;
+  stream(request: ListRows, options?: FetchOptions): AsyncGenerator<Response>;
+};
+
+export function makeTableClient($fetch: Fetch) {
+  return {"#
+            .to_string();
+
+        for table in tables {
+            let name = table.as_str();
+            let columns = infer(&format!("{table}_columns"));
+            let sort = infer(&format!("{table}_sort_options"));
+            let key = infer(&format!("{table}_primary_key"));
+            let row = infer(&format!("{table}_schema"));
+            let row_optional = infer(&format!("{table}_schema_optional"));
+            let pagination = infer("Pagination");
+
+            writeln!(
+                helpers,
+                r#"
+    {table}: {{
+      list: (
+        options: {{
+          select?: {columns}[];
+          sort?: {sort};
+          page?: {pagination};
+        }} = {{}},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({{
+          type: "ListRows",
+          table: "{name}",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({list}),
+      stream: (
+        options: {{
+          select?: {columns}[];
+          sort?: {sort};
+          page?: {pagination};
+          chunkSize?: number;
+        }} = {{}},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({{
+          type: "ListRows",
+          table: "{name}",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }}, fetchOptions), {stream}),
+      get: (
+        key: {key},
+        options: {{ select?: {columns}[] }} = {{}},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({{
+          type: "GetRow",
+          table: "{name}",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }}, fetchOptions).then({get}),
+      insert: (data: {row}, fetchOptions?: FetchOptions) =>
+        $fetch({{
+          type: "InsertRow",
+          table: "{name}",
+          data,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({insert}),
+      batchInsert: (data: {row}[], fetchOptions?: FetchOptions) =>
+        $fetch({{
+          type: "BatchInsertRow",
+          table: "{name}",
+          data,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({batch_insert}),
+      update: (
+        key: {key},
+        data: {row_optional},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({{
+          type: "UpdateRow",
+          table: "{name}",
+          key,
+          data,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({update}),
+      delete: (key: {key}, fetchOptions?: FetchOptions) =>
+        $fetch({{
+          type: "DeleteRow",
+          table: "{name}",
+          key,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({delete}),"#,
+                list = narrow(&format!("{table}_list_rows_response")),
+                stream = narrow(&format!("{table}_list_rows_chunk_response")),
+                get = narrow(&format!("{table}_get_row_response")),
+                insert = narrow(&format!("{table}_insert_row_response")),
+                batch_insert = narrow(&format!("{table}_batch_insert_row_response")),
+                update = narrow(&format!("{table}_update_row_response")),
+                delete = narrow(&format!("{table}_delete_row_response")),
+            )?;
+
+            if searchable.iter().any(|(t, _)| t == table) {
+                writeln!(
+                    helpers,
+                    r#"      search: (
+        query: string,
+        options: {{
+          select?: {columns}[];
+          highlight?: {highlight};
+          snippet?: {snippet};
+          page?: {pagination};
+        }} = {{}},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({{
+          type: "Search",
+          table: "{name}",
+          query,
+          select: options.select ?? [],
+          highlight: options.highlight,
+          snippet: options.snippet,
+          page: options.page,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({search}),"#,
+                    highlight = infer("Highlight"),
+                    snippet = infer("Snippet"),
+                    search = narrow(&format!("{table}_search_response")),
+                )?;
+            }
+
+            if blob_columns.iter().any(|(t, _)| t == table) {
+                // Blobs are read in binary frames unless asked otherwise, sparing the encoding.
+                writeln!(
+                    helpers,
+                    r#"      readBlob: (
+        key: {key},
+        column: {columns},
+        options: {{ offset?: number; length?: number }} = {{}},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({{
+          type: "ReadBlob",
+          table: "{name}",
+          key,
+          column,
+          offset: options.offset,
+          length: options.length,
+          request_id: newRequestId(),
+        }}, {{ binary: true, ...fetchOptions }}).then({read}),
+      writeBlob: (
+        key: {key},
+        column: {columns},
+        data: {write}["data"],
+        options: {{ offset?: number; size?: number }} = {{}},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({{
+          type: "WriteBlob",
+          table: "{name}",
+          key,
+          column,
+          offset: options.offset,
+          data,
+          size: options.size,
+          request_id: newRequestId(),
+        }}, fetchOptions).then({written}),"#,
+                    columns = infer(&format!("{table}_blob_columns")),
+                    write = infer(&format!("{table}_write_blob_request")),
+                    read = narrow(&format!("{table}_read_blob_response")),
+                    written = narrow(&format!("{table}_write_blob_response")),
+                )?;
+            }
+
+            writeln!(helpers, "    }},")?;
+        }
+
+        writeln!(helpers, "  }};")?;
+        writeln!(helpers, "}}")?;
+
+        Ok(helpers)
+    }
+
+    fn capitalize(s: &str) -> String {
+        let mut chars = s.chars();
+        chars
+            .next()
+            .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+            .unwrap_or_default()
+    }
+
+    const RUNTIME: &str = r#"
+export type MakeFetchOptions = {
+  url: string;
+  connectionCount: number;
+  /** Default time in milliseconds to wait for a response, no limit when unset. */
+  timeout?: number;
+  /** Delay in milliseconds before reconnecting a closed socket, doubled after every failed attempt. */
+  reconnectDelay?: number;
+  /** Upper bound for the reconnection delay. */
+  maxReconnectDelay?: number;
+  /** Called when an admin changed the schema, once per connection in the pool. */
+  onSchemaChange?: (change: SchemaChanged) => void;
+  /**
+   * Encode messages with MessagePack instead of JSON, so blob columns arrive as `Uint8Array`s.
+   * Needs a server with the `message_pack` capability, older ones refuse the connection.
+   */
+  messagePack?: boolean;
+};
+
+export type FetchOptions = {
+  /** Time in milliseconds to wait for a response, overrides the pool default. */
+  timeout?: number;
+  signal?: AbortSignal;
+  /**
+   * Send the request in a binary frame, so blob columns in the response arrive as `Uint8Array`s.
+   * Requests containing `Uint8Array`s are always sent in binary frames.
+   */
+  binary?: boolean;
+};
+
+export type Result<T> = { data: T } | { error: ApiError };
+
+export class RequestTimeoutError extends Error {
+  constructor(public request_id: string, timeout: number) {
+    super(`request ${request_id} timed out after ${timeout}ms`);
+    this.name = "RequestTimeoutError";
+  }
+}
+
+export class ConnectionClosedError extends Error {
+  constructor(public request_id: string) {
+    super(`connection closed before request ${request_id} was answered`);
+    this.name = "ConnectionClosedError";
+  }
+}
+
+export class SchemaMismatchError extends Error {
+  constructor(public server: string, public client: string) {
+    super(`the client was generated for schema ${client} but the server has ${server}, regenerate it`);
+    this.name = "SchemaMismatchError";
+  }
+}
+
+/** Errors are wrapped in an `Error` message from version 2 on. */
+export const PROTOCOL_VERSION = 2;
+
+/** The WebSocket subprotocol asking the server for MessagePack messages. */
+export const MESSAGE_PACK_PROTOCOL = "jabroni.msgpack";
+
+/** The server's answer to the `Hello` every connection starts with. */
+export type HelloResponse = {
+  type: "Hello";
+  protocol_version: number;
+  capabilities: string[];
+  request_id: string;
+};
+
+type PendingRequest = {
+  resolve: (response: Response) => void;
+  reject: (error: unknown) => void;
+};
+
+type Connection = {
+  socket: WebSocket;
+  requests: Map<string, PendingRequest>;
+  retries: number;
+  /** Set until the server answered the `Hello`, the connection isn't used before that. */
+  hello?: { request_id: string; done: () => void };
+  capabilities: string[];
+  messagePack: boolean;
+};
+
+export async function makeWebSocketFetch(
+  {
+    url,
+    connectionCount,
+    timeout: defaultTimeout,
+    reconnectDelay = 250,
+    maxReconnectDelay = 10_000,
+    onSchemaChange,
+    messagePack = false,
+  }: MakeFetchOptions,
+) {
+  if (connectionCount < 1) {
+    throw new Error("WebSocket pool needs at least one connection.");
+  }
+
+  const connections: Connection[] = [];
+  let connectionIndex = 0;
+  const openPromises: (Promise<void>)[] = [];
+  // Requests waiting for any socket in the pool to open.
+  const queued = new Set<() => void>();
+  // Set once the server rejected the client, nothing can be sent after that.
+  let schemaMismatch: SchemaMismatchError | undefined;
+
+  function connect(connection: Connection, i: number, onOpen: () => void) {
+    const protocols = messagePack ? [MESSAGE_PACK_PROTOCOL] : [];
+    const socket = new WebSocket(connectionUrl(url), protocols);
+    connection.socket = socket;
+
+    socket.onopen = () => {
+      connection.messagePack = socket.protocol === MESSAGE_PACK_PROTOCOL;
+      const request_id = newRequestId();
+      connection.hello = {
+        request_id,
+        done: () => {
+          connection.hello = undefined;
+          connection.retries = 0;
+          onOpen();
+          for (const send of [...queued]) {
+            send();
+          }
+        },
+      };
+      socket.send(encode(connection, { type: "Hello", protocol_version: PROTOCOL_VERSION, request_id }));
+    };
+    socket.binaryType = "arraybuffer";
+    socket.onmessage = (event: MessageEvent<string | ArrayBuffer>) =>
+      handleMessage(connection, event);
+    // Some runtimes only report a failed connection attempt through `onerror`.
+    let closed = false;
+    const reconnect = () => {
+      if (closed) {
+        return;
+      }
+      closed = true;
+
+      for (const [request_id, request] of connection.requests) {
+        request.reject(new ConnectionClosedError(request_id));
+      }
+      if (schemaMismatch) {
+        return;
+      }
+
+      const delay = Math.min(
+        reconnectDelay * 2 ** connection.retries,
+        maxReconnectDelay,
+      );
+      connection.retries += 1;
+      setTimeout(() => connect(connection, i, onOpen), delay);
+    };
+
+    socket.onclose = () => {
+      console.log(i, "WebSocket disconnected");
+      reconnect();
+    };
+    socket.onerror = (error) => {
+      console.error(i, "WebSocket error:", error);
+      reconnect();
+    };
+  }
+
+  for (let i = 0; i < connectionCount; i++) {
+    const connection = { requests: new Map(), retries: 0, capabilities: [] } as Connection;
+    connections.push(connection);
+    openPromises.push(new Promise((res) => connect(connection, i, res)));
+  }
+
+  await Promise.all(openPromises);
+
+  function handleMessage(
+    connection: Connection,
+    event: MessageEvent<string | ArrayBuffer>,
+  ) {
+    const { requests } = connection;
+    const message: unknown = typeof event.data === "string"
+      ? JSON.parse(event.data)
+      : connection.messagePack
+      ? decodeMessagePack(event.data)
+      : decodeFrame(event.data);
+    const type = typeof message === "object" && message !== null && "type" in message
+      ? message.type
+      : undefined;
+    // Broadcast to every connection, not an answer to any request.
+    if (type === "SchemaChanged") {
+      onSchemaChange?.(message as SchemaChanged);
+      return;
+    }
+    // Sent right before the server closes the connection.
+    if (type === "SchemaMismatch") {
+      const { server, client } = message as { server: string; client: string };
+      schemaMismatch = new SchemaMismatchError(server, client);
+      for (const connection of connections) {
+        for (const request of connection.requests.values()) {
+          request.reject(schemaMismatch);
+        }
+        connection.hello?.done();
+      }
+      for (const send of [...queued]) {
+        send();
+      }
+      return;
+    }
+
+    const request_id = typeof message === "object" && message !== null &&
+        "request_id" in message && typeof message.request_id === "string"
+      ? message.request_id
+      // Messages without an id answer the oldest request, the server replies in order.
+      : requests.keys().next().value;
+
+    if (connection.hello && request_id === connection.hello.request_id) {
+      // Servers predating the handshake answer it with an error, and speak version 1.
+      connection.capabilities = type === "Hello" ? (message as HelloResponse).capabilities : [];
+      connection.hello.done();
+      return;
+    }
+
+    const request = request_id === undefined
+      ? undefined
+      : requests.get(request_id);
+    if (!request) {
+      console.warn("Received a response for an unknown request:", message);
+      return;
+    }
+
+    try {
+      request.resolve(parseMessage(type === "Error" ? (message as { error: unknown }).error : message));
+    } catch (error) {
+      request.reject(error);
+    }
+  }
+
+  // Round robin over the open sockets, skipping the ones that are reconnecting.
+  function getConnection(): Connection | undefined {
+    for (let i = 0; i < connections.length; i++) {
+      const connection = connections[connectionIndex];
+      connectionIndex = (connectionIndex + 1) % connections.length;
+
+      if (!connection.hello && connection.socket.readyState === WebSocket.OPEN) {
+        return connection;
+      }
+    }
+  }
+
+  // Sends the request, or queues it until a socket opens, and hands its responses to
+  // `onResponse` up to the one `isLast` accepts. The timeout restarts after every response.
+  function dispatch(
+    request: Request,
+    { timeout = defaultTimeout, signal, binary }: FetchOptions,
+    isLast: (response: Response) => boolean,
+    onResponse: (response: Response) => void,
+    onError: (error: unknown) => void,
+  ) {
+    const request_id = request.request_id ?? newRequestId();
+
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    let connection: Connection | undefined;
+    const onAbort = () => settle(() => onError(signal?.reason), true);
+
+    // Requests given up on before their last response stay registered until it
+    // arrives, so their remaining responses are dropped without a warning.
+    function settle(done: () => void, abandoned = false) {
+      clearTimeout(timer);
+      signal?.removeEventListener("abort", onAbort);
+      queued.delete(send);
+
+      const requests = connection?.requests;
+      if (abandoned && requests?.has(request_id)) {
+        requests.set(request_id, {
+          resolve: (response) => {
+            if (isLast(response)) requests.delete(request_id);
+          },
+          reject: () => requests.delete(request_id),
+        });
+      } else {
+        requests?.delete(request_id);
+      }
+
+      done();
+    }
+
+    function startTimer() {
+      clearTimeout(timer);
+      if (timeout !== undefined) {
+        timer = setTimeout(
+          () => settle(() => onError(new RequestTimeoutError(request_id, timeout)), true),
+          timeout,
+        );
+      }
+    }
+
+    function send() {
+      if (schemaMismatch) {
+        settle(() => onError(schemaMismatch));
+        return;
+      }
+
+      connection = getConnection();
+      if (!connection) {
+        queued.add(send);
+        return;
+      }
+
+      queued.delete(send);
+      connection.requests.set(request_id, {
+        resolve: (response) => {
+          if (isLast(response)) {
+            settle(() => onResponse(response));
+          } else {
+            onResponse(response);
+            startTimer();
+          }
+        },
+        reject: (error) => settle(() => onError(error)),
+      });
+      connection.socket.send(encode(connection, { ...request, request_id }, binary));
+    }
+
+    if (signal?.aborted) {
+      onError(signal.reason);
+      return;
+    }
+
+    startTimer();
+    signal?.addEventListener("abort", onAbort, { once: true });
+    send();
+  }
+
+  function $fetch(
+    request: Request,
+    options: FetchOptions = {},
+  ): Promise<Response> {
+    return new Promise<Response>((resolve, reject) => {
+      dispatch(request, options, () => true, resolve, reject);
+    });
+  }
+
+  // Yields the `ListRowsChunk` responses of a streamed `ListRows` request, ending after
+  // the `ListRowsEnd` or an error. Chunks arriving after the loop is left are dropped.
+  async function* stream(
+    request: ListRows,
+    options: FetchOptions = {},
+  ): AsyncGenerator<Response> {
+    const responses: Response[] = [];
+    let failure: { error: unknown } | undefined;
+    let stopped = false;
+    let wake = () => {};
+
+    dispatch(
+      { ...request, chunk_size: request.chunk_size ?? DEFAULT_CHUNK_SIZE },
+      options,
+      (response) => !isChunk(response),
+      (response) => {
+        if (!stopped) {
+          responses.push(response);
+          wake();
+        }
+      },
+      (error) => {
+        failure = { error };
+        wake();
+      },
+    );
+
+    try {
+      while (true) {
+        const response = responses.shift();
+        if (response) {
+          if ("data" in response && response.data.type === "ListRowsEnd") {
+            return;
+          }
+          yield response;
+          if (!isChunk(response)) {
+            return;
+          }
+        } else if (failure) {
+          throw failure.error;
+        } else {
+          await new Promise<void>((resolve) => (wake = resolve));
+        }
+      }
+    } finally {
+      stopped = true;
+    }
+  }
+
+  /** What the server supports, as it answered the `Hello`. */
+  function capabilities(): string[] {
+    return connections[0].capabilities;
+  }
+
+  return Object.assign($fetch, { stream, capabilities });
+}
+
+type ListRows = Extract<Request, { type: "ListRows" }>;
+
+function encode(connection: Connection, message: unknown, binary?: boolean) {
+  return connection.messagePack ? encodeMessagePack(message) : encodeFrame(message, binary);
+}
+
+function connectionUrl(url: string): string {
+  const connectionUrl = new URL(url);
+  connectionUrl.searchParams.set("schema", SCHEMA_FINGERPRINT);
+  if (DATABASE !== null) {
+    connectionUrl.pathname = `${connectionUrl.pathname.replace(/\/$/, "")}/${DATABASE}`;
+  }
+  return connectionUrl.toString();
+}
+
+const DEFAULT_CHUNK_SIZE = 1000;
+
+function isChunk(response: Response) {
+  return "data" in response && response.data.type === "ListRowsChunk";
+}
+
+async function* mapStream<T>(
+  responses: AsyncIterable<Response>,
+  map: (response: Response) => T,
+): AsyncGenerator<T> {
+  for await (const response of responses) {
+    yield map(response);
+  }
+}
+
+// Binary frames are a big endian u32 length, that many bytes of JSON and the raw bytes
+// of blobs, which the JSON refers to as `{ "$binary": [offset, length] }`.
+function encodeFrame(request: unknown, binary = false): string | Uint8Array {
+  const attachments: Uint8Array[] = [];
+  let length = 0;
+  const json = JSON.stringify(request, (_key, value) => {
+    if (!(value instanceof Uint8Array)) {
+      return value;
+    }
+    attachments.push(value);
+    length += value.length;
+    return { $binary: [length - value.length, value.length] };
+  });
+
+  if (!binary && attachments.length === 0) {
+    return json;
+  }
+
+  const header = new TextEncoder().encode(json);
+  const frame = new Uint8Array(4 + header.length + length);
+  new DataView(frame.buffer).setUint32(0, header.length);
+  frame.set(header, 4);
+
+  let offset = 4 + header.length;
+  for (const attachment of attachments) {
+    frame.set(attachment, offset);
+    offset += attachment.length;
+  }
+  return frame;
+}
+
+function decodeFrame(frame: ArrayBuffer): unknown {
+  const length = new DataView(frame).getUint32(0);
+  const json = new TextDecoder().decode(new Uint8Array(frame, 4, length));
+  return JSON.parse(json, (_key, value) => {
+    const range = value?.$binary;
+    return Array.isArray(range)
+      ? new Uint8Array(frame, 4 + length + range[0], range[1])
+      : value;
+  });
+}
+
+// Covers what the server sends and accepts: nil, booleans, numbers, strings, `bin` values
+// as `Uint8Array`s, arrays and maps with string keys.
+function encodeMessagePack(value: unknown): Uint8Array {
+  let buffer = new Uint8Array(256);
+  let view = new DataView(buffer.buffer);
+  let length = 0;
+
+  // Grows the buffer before `set` writes `size` bytes at the end of it.
+  function put(size: number, set: (view: DataView, offset: number) => void) {
+    if (length + size > buffer.length) {
+      const grown = new Uint8Array(Math.max(buffer.length * 2, length + size));
+      grown.set(buffer);
+      buffer = grown;
+      view = new DataView(buffer.buffer);
+    }
+    set(view, length);
+    length += size;
+  }
+
+  const u8 = (n: number) => put(1, (v, at) => v.setUint8(at, n));
+  const u16 = (n: number) => put(2, (v, at) => v.setUint16(at, n));
+  const u32 = (n: number) => put(4, (v, at) => v.setUint32(at, n));
+  const bytes = (data: Uint8Array) => put(data.length, (_, at) => buffer.set(data, at));
+
+  function header(size: number, fixed: number, fixedMax: number, codes: number[]) {
+    if (size < fixedMax) u8(fixed | size);
+    else if (size < 0x100 && codes[0]) { u8(codes[0]); u8(size); }
+    else if (size < 0x10000) { u8(codes[1]); u16(size); }
+    else { u8(codes[2]); u32(size); }
+  }
+
+  function write(value: unknown) {
+    if (value === null || value === undefined) {
+      u8(0xc0);
+    } else if (typeof value === "boolean") {
+      u8(value ? 0xc3 : 0xc2);
+    } else if (typeof value === "number" && !Number.isSafeInteger(value)) {
+      u8(0xcb);
+      put(8, (v, at) => v.setFloat64(at, value));
+    } else if (typeof value === "number" && value >= 0) {
+      if (value < 0x80) u8(value);
+      else if (value < 0x100) { u8(0xcc); u8(value); }
+      else if (value < 0x10000) { u8(0xcd); u16(value); }
+      else if (value < 0x100000000) { u8(0xce); u32(value); }
+      else { u8(0xcf); put(8, (v, at) => v.setBigUint64(at, BigInt(value))); }
+    } else if (typeof value === "number") {
+      if (value >= -0x20) u8(value & 0xff);
+      else if (value >= -0x80) { u8(0xd0); put(1, (v, at) => v.setInt8(at, value)); }
+      else if (value >= -0x8000) { u8(0xd1); put(2, (v, at) => v.setInt16(at, value)); }
+      else if (value >= -0x80000000) { u8(0xd2); put(4, (v, at) => v.setInt32(at, value)); }
+      else { u8(0xd3); put(8, (v, at) => v.setBigInt64(at, BigInt(value))); }
+    } else if (typeof value === "string") {
+      const data = new TextEncoder().encode(value);
+      header(data.length, 0xa0, 0x20, [0xd9, 0xda, 0xdb]);
+      bytes(data);
+    } else if (value instanceof Uint8Array) {
+      header(value.length, 0, 0, [0xc4, 0xc5, 0xc6]);
+      bytes(value);
+    } else if (Array.isArray(value)) {
+      header(value.length, 0x90, 0x10, [0, 0xdc, 0xdd]);
+      value.forEach(write);
+    } else if (typeof value === "object" && value !== null && "toJSON" in value) {
+      write((value as { toJSON: () => unknown }).toJSON());
+    } else if (typeof value === "object" && value !== null) {
+      const entries = Object.entries(value).filter(([, v]) => v !== undefined);
+      header(entries.length, 0x80, 0x10, [0, 0xde, 0xdf]);
+      for (const [key, v] of entries) {
+        write(key);
+        write(v);
+      }
+    } else {
+      throw new TypeError(`can't encode a ${typeof value} as MessagePack`);
+    }
+  }
+
+  write(value);
+  return buffer.subarray(0, length);
+}
+
+function decodeMessagePack(frame: ArrayBuffer): unknown {
+  const bytes = new Uint8Array(frame);
+  const view = new DataView(frame);
+  let offset = 0;
+
+  function number(size: number, get: (offset: number) => number | bigint): number {
+    offset += size;
+    return Number(get.call(view, offset - size));
+  }
+  const u8 = () => number(1, view.getUint8);
+  const u16 = () => number(2, view.getUint16);
+  const u32 = () => number(4, view.getUint32);
+
+  function take(size: number): Uint8Array {
+    offset += size;
+    return bytes.subarray(offset - size, offset);
+  }
+  const str = (size: number) => new TextDecoder().decode(take(size));
+
+  function array(size: number): unknown[] {
+    return Array.from({ length: size }, read);
+  }
+
+  function map(size: number): Record<string, unknown> {
+    const object: Record<string, unknown> = {};
+    for (let i = 0; i < size; i++) {
+      const key = String(read());
+      object[key] = read();
+    }
+    return object;
+  }
+
+  function read(): unknown {
+    const code = u8();
+    if (code < 0x80) return code;
+    if (code < 0x90) return map(code & 0x0f);
+    if (code < 0xa0) return array(code & 0x0f);
+    if (code < 0xc0) return str(code & 0x1f);
+    if (code >= 0xe0) return code - 0x100;
+
+    switch (code) {
+      case 0xc0: return null;
+      case 0xc2: return false;
+      case 0xc3: return true;
+      case 0xc4: return take(u8());
+      case 0xc5: return take(u16());
+      case 0xc6: return take(u32());
+      case 0xca: return number(4, view.getFloat32);
+      case 0xcb: return number(8, view.getFloat64);
+      case 0xcc: return u8();
+      case 0xcd: return u16();
+      case 0xce: return u32();
+      case 0xcf: return number(8, view.getBigUint64);
+      case 0xd0: return number(1, view.getInt8);
+      case 0xd1: return number(2, view.getInt16);
+      case 0xd2: return number(4, view.getInt32);
+      case 0xd3: return number(8, view.getBigInt64);
+      case 0xd9: return str(u8());
+      case 0xda: return str(u16());
+      case 0xdb: return str(u32());
+      case 0xdc: return array(u16());
+      case 0xdd: return array(u32());
+      case 0xde: return map(u16());
+      case 0xdf: return map(u32());
+    }
+    throw new Error(`unsupported MessagePack type 0x${code.toString(16)}`);
+  }
+
+  return read();
+}
+"#;
+}
+
+mod json_schema {
+    use color_eyre::eyre::Context;
+    use serde_json::{json, Map, Value as JsonValue};
+
+    use crate::{
+        db::{BlobEncoding, Database, SqlValueType, SqliteDatabase},
+        BoxStr,
+    };
+
+    const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+    pub async fn generate_json_schema(
+        db: SqliteDatabase,
+        out_path: BoxStr,
+        database: Option<&str>,
+    ) -> color_eyre::Result<()> {
+        tracing::info!("generating json schema");
+
+        let definitions = definitions(&db, "#/$defs/").await?;
+        let description = match database {
+            Some(name) => {
+                format!("Messages exchanged with the {name} database of a jabroni server.")
+            }
+            None => "Messages exchanged with a jabroni server.".into(),
+        };
+        // Not `oneOf`, a `DropTable` response is also a valid `DropTable` request.
+        let schema = json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": "jabroni",
+            "description": description,
+            "anyOf": [
+                { "$ref": "#/$defs/ApiRequest" },
+                { "$ref": "#/$defs/ApiResponse" },
+                { "$ref": "#/$defs/ErrorResponse" },
+                { "$ref": "#/$defs/HelloRequest" },
+                { "$ref": "#/$defs/HelloResponse" },
+                { "$ref": "#/$defs/Error" },
+            ],
+            "$defs": definitions,
+        });
+
+        std::fs::write(out_path.as_ref(), serde_json::to_string_pretty(&schema)?)?;
+        tracing::info!("json schema generated at {out_path}");
+        Ok(())
+    }
+
+    pub async fn generate_openapi(
+        db: SqliteDatabase,
+        out_path: BoxStr,
+        database: Option<&str>,
+    ) -> color_eyre::Result<()> {
+        tracing::info!("generating openapi document");
+
+        // Databases other than the default are reached under their name.
+        let prefix = database.map(|name| format!("/{name}")).unwrap_or_default();
+
+        let tables = db
+            .get_user_tables()
+            .await
+            .context("failed to fetch tables")?;
+
+        let schema_ref = |name: &str| json!({ "$ref": format!("#/components/schemas/{name}") });
+        let json_content = |schema: JsonValue| json!({ "application/json": { "schema": schema } });
+        let error_responses = json!({
+            "400": { "description": "Bad request", "content": json_content(schema_ref("ErrorResponse")) },
+            "404": { "description": "Not found", "content": json_content(schema_ref("ErrorResponse")) },
+            "500": { "description": "Database error", "content": json_content(schema_ref("ErrorResponse")) },
+        });
+        let with_errors = |ok: JsonValue| {
+            let mut responses = error_responses.clone();
+            responses["200"] = ok;
+            responses
+        };
+        let ok = |name: String| json!({ "description": "OK", "content": json_content(schema_ref(&name)) });
+
+        let request_id_header = json!({
+            "name": "X-Request-Id",
+            "in": "header",
+            "required": false,
+            "schema": { "type": "string" },
+        });
+        let select_param = json!({
+            "name": "select",
+            "in": "query",
+            "required": false,
+            "description": "Comma separated list of columns to return.",
+            "schema": { "type": "string" },
+        });
+
+        let mut paths = Map::new();
+        for table in tables.iter() {
+            let key_param = json!({
+                "name": "key",
+                "in": "path",
+                "required": true,
+                "schema": schema_ref(&format!("{table}_primary_key")),
+            });
+
+            paths.insert(
+                format!("{prefix}/tables/{table}"),
+                json!({
+                    "get": {
+                        "operationId": format!("list_{table}"),
+                        "parameters": [
+                            select_param,
+                            { "name": "sort", "in": "query", "required": false, "schema": schema_ref(&format!("{table}_columns")) },
+                            { "name": "order", "in": "query", "required": false, "schema": { "enum": ["asc", "desc"] } },
+                            { "name": "page", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 1 } },
+                            { "name": "size", "in": "query", "required": false, "schema": { "type": "integer", "minimum": 0 } },
+                            request_id_header,
+                        ],
+                        "responses": with_errors(ok(format!("{table}_list_rows_response"))),
+                    },
+                    "post": {
+                        "operationId": format!("insert_{table}"),
+                        "parameters": [request_id_header],
+                        "requestBody": {
+                            "required": true,
+                            "content": json_content(json!({
+                                "oneOf": [
+                                    schema_ref(&format!("{table}")),
+                                    { "type": "array", "items": schema_ref(&format!("{table}")) },
+                                ],
+                            })),
+                        },
+                        "responses": with_errors(json!({
+                            "description": "OK",
+                            "content": json_content(json!({
+                                "oneOf": [
+                                    schema_ref(&format!("{table}_insert_row_response")),
+                                    schema_ref(&format!("{table}_batch_insert_row_response")),
+                                ],
+                            })),
+                        })),
+                    },
+                }),
+            );
+
+            paths.insert(
+                format!("{prefix}/tables/{table}/{{key}}"),
+                json!({
+                    "get": {
+                        "operationId": format!("get_{table}"),
+                        "parameters": [key_param, select_param, request_id_header],
+                        "responses": with_errors(ok(format!("{table}_get_row_response"))),
+                    },
+                    "patch": {
+                        "operationId": format!("update_{table}"),
+                        "parameters": [key_param, request_id_header],
+                        "requestBody": {
+                            "required": true,
+                            "content": json_content(schema_ref(&format!("{table}_optional"))),
+                        },
+                        "responses": with_errors(ok(format!("{table}_update_row_response"))),
+                    },
+                    "delete": {
+                        "operationId": format!("delete_{table}"),
+                        "parameters": [key_param, request_id_header],
+                        "responses": with_errors(ok(format!("{table}_delete_row_response"))),
+                    },
+                }),
+            );
+        }
+
+        let document = json!({
+            "openapi": "3.1.0",
+            "jsonSchemaDialect": JSON_SCHEMA_DIALECT,
+            "info": {
+                "title": "jabroni",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "The HTTP endpoints of a jabroni server. The `ApiRequest` and `ApiResponse` schemas also describe the WebSocket messages.",
+            },
+            "paths": paths,
+            "components": {
+                "schemas": definitions(&db, "#/components/schemas/").await?,
+            },
+        });
+
+        std::fs::write(out_path.as_ref(), serde_json::to_string_pretty(&document)?)?;
+        tracing::info!("openapi document generated at {out_path}");
+        Ok(())
+    }
+
+    /// Builds a schema for every request and response type, `ref_prefix` is where the
+    /// definitions will live in the final document.
+    async fn definitions(
+        db: &SqliteDatabase,
+        ref_prefix: &str,
+    ) -> color_eyre::Result<Map<String, JsonValue>> {
+        let tables = db
+            .get_user_tables()
+            .await
+            .context("failed to fetch tables")?;
+
+        let schema_ref = |name: &str| json!({ "$ref": format!("{ref_prefix}{name}") });
+        let request_id = json!({ "type": "string" });
+
+        let mut defs = Map::new();
+
+        defs.insert(
+            "Pagination".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "number": { "type": "integer", "minimum": 1 },
+                    "size": { "type": "integer", "minimum": 0 },
+                },
+                "required": ["number", "size"],
+            }),
+        );
+        defs.insert("SortOrder".into(), json!({ "enum": ["Asc", "Desc"] }));
+        defs.insert(
+            "Highlight".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "open": { "type": "string", "default": "<b>" },
+                    "close": { "type": "string", "default": "</b>" },
+                },
+            }),
+        );
+        defs.insert(
+            "Snippet".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "open": { "type": "string", "default": "<b>" },
+                    "close": { "type": "string", "default": "</b>" },
+                    "ellipsis": { "type": "string", "default": "…" },
+                    "tokens": { "type": "integer", "minimum": 1, "maximum": 64, "default": 16 },
+                },
+            }),
+        );
+        describe_definitions(&mut defs, &schema_ref);
+        schema_change_definitions(&mut defs, &schema_ref);
+        hello_definitions(&mut defs, &schema_ref);
+
+        let blob = db.blob_encoding();
+        let searchable = db.get_searchable_tables(&tables).await?;
+        let blob_columns = db.get_blob_columns(&tables).await?;
+
+        for table in tables.iter() {
+            let primary_key_type = db.get_primary_key_type(table).await?;
+            defs.insert(
+                format!("{table}_primary_key"),
+                value_schema(&primary_key_type, blob),
+            );
+
+            let columns = db.get_column_types(table).await?;
+
+            let properties = columns
+                .iter()
+                .map(|(col, (typ, _))| (col.to_string(), nullable(value_schema(typ, blob))))
+                .collect::<Map<_, _>>();
+            let required = columns
+                .iter()
+                .filter(|(_, (_, required))| *required)
+                .map(|(col, _)| col.to_string())
+                .collect::<Vec<_>>();
+
+            let strict_properties = columns
+                .iter()
+                .map(|(col, (typ, required))| {
+                    let schema = value_schema(typ, blob);
+                    let schema = if *required { schema } else { nullable(schema) };
+                    (col.to_string(), schema)
+                })
+                .collect::<Map<_, _>>();
+
+            defs.insert(
+                format!("{table}"),
+                json!({
+                    "type": "object",
+                    "properties": strict_properties,
+                    "required": required,
+                    "additionalProperties": false,
+                }),
+            );
+            defs.insert(
+                format!("{table}_optional"),
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "additionalProperties": false,
+                }),
+            );
+
+            let column_names = columns
+                .iter()
+                .map(|(col, _)| col.to_string())
+                .collect::<Vec<_>>();
+            defs.insert(format!("{table}_columns"), json!({ "enum": column_names }));
+
+            defs.insert(
+                format!("{table}_sort_options"),
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "column": schema_ref(&format!("{table}_columns")),
+                        "order": schema_ref("SortOrder"),
+                    },
+                    "required": ["column", "order"],
+                }),
+            );
+
+            let select =
+                json!({ "type": "array", "items": schema_ref(&format!("{table}_columns")) });
+            let key = schema_ref(&format!("{table}_primary_key"));
+            let row = schema_ref(&format!("{table}"));
+            let row_optional = schema_ref(&format!("{table}_optional"));
+
+            let mut requests = vec![
+                (
+                    "list_rows_request",
+                    "ListRows",
+                    json!({
+                        "select": select,
+                        "sort": schema_ref(&format!("{table}_sort_options")),
+                        "page": schema_ref("Pagination"),
+                        "chunk_size": { "type": "integer", "minimum": 1 },
+                    }),
+                    vec!["select"],
+                ),
+                (
+                    "get_row_request",
+                    "GetRow",
+                    json!({ "key": key, "select": select }),
+                    vec!["key", "select"],
+                ),
+                (
+                    "insert_row_request",
+                    "InsertRow",
+                    json!({ "data": row }),
+                    vec!["data"],
+                ),
+                (
+                    "batch_insert_row_request",
+                    "BatchInsertRow",
+                    json!({ "data": { "type": "array", "items": row, "minItems": 1 } }),
+                    vec!["data"],
+                ),
+                (
+                    "delete_row_request",
+                    "DeleteRow",
+                    json!({ "key": key }),
+                    vec!["key"],
+                ),
+                (
+                    "update_row_request",
+                    "UpdateRow",
+                    json!({ "key": key, "data": row_optional }),
+                    vec!["key", "data"],
+                ),
+            ];
+
+            let mut responses = vec![
+                (
+                    "list_rows_response",
+                    "ListRows",
+                    json!({ "rows": { "type": "array", "items": row_optional } }),
+                    vec!["rows"],
+                ),
+                (
+                    "list_rows_chunk_response",
+                    "ListRowsChunk",
+                    json!({ "rows": { "type": "array", "items": row_optional } }),
+                    vec!["rows"],
+                ),
+                (
+                    "list_rows_end_response",
+                    "ListRowsEnd",
+                    json!({ "total_rows": { "type": "integer" } }),
+                    vec!["total_rows"],
+                ),
+                (
+                    "get_row_response",
+                    "GetRow",
+                    json!({ "row": row_optional }),
+                    vec!["row"],
+                ),
+                (
+                    "insert_row_response",
+                    "InsertRow",
+                    json!({ "inserted_rows": { "type": "integer" } }),
+                    vec!["inserted_rows"],
+                ),
+                (
+                    "batch_insert_row_response",
+                    "BatchInsertRow",
+                    json!({ "inserted_rows": { "type": "integer" } }),
+                    vec!["inserted_rows"],
+                ),
+                (
+                    "delete_row_response",
+                    "DeleteRow",
+                    json!({ "deleted_rows": { "type": "integer" } }),
+                    vec!["deleted_rows"],
+                ),
+                (
+                    "update_row_response",
+                    "UpdateRow",
+                    json!({ "updated_rows": { "type": "integer" } }),
+                    vec!["updated_rows"],
+                ),
+            ];
+
+            if let Some((_, columns)) = searchable.iter().find(|(t, _)| t == table) {
+                let markup = json!({
+                    "type": "object",
+                    "properties": columns
+                        .iter()
+                        .map(|col| (col.to_string(), json!({ "type": ["string", "null"] })))
+                        .collect::<Map<_, _>>(),
+                });
+
+                requests.push((
+                    "search_request",
+                    "Search",
+                    json!({
+                        "query": { "type": "string" },
+                        "select": select,
+                        "highlight": schema_ref("Highlight"),
+                        "snippet": schema_ref("Snippet"),
+                        "page": schema_ref("Pagination"),
+                    }),
+                    vec!["query", "select"],
+                ));
+                responses.push((
+                    "search_response",
+                    "Search",
+                    json!({
+                        "hits": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "row": row_optional,
+                                    "rank": { "type": "number" },
+                                    "highlights": markup,
+                                    "snippets": markup,
+                                },
+                                "required": ["row", "rank"],
+                            },
+                        },
+                    }),
+                    vec!["hits"],
+                ));
+            }
+
+            if let Some((_, columns)) = blob_columns.iter().find(|(t, _)| t == table) {
+                let column =
+                    json!({ "enum": columns.iter().map(|c| c.to_string()).collect::<Vec<_>>() });
+                let position = json!({ "type": "integer", "minimum": 0 });
+                let data = value_schema(&SqlValueType::Blob, blob);
+
+                requests.push((
+                    "read_blob_request",
+                    "ReadBlob",
+                    json!({
+                        "key": key,
+                        "column": column,
+                        "offset": position,
+                        "length": position,
+                    }),
+                    vec!["key", "column"],
+                ));
+                requests.push((
+                    "write_blob_request",
+                    "WriteBlob",
+                    json!({
+                        "key": key,
+                        "column": column,
+                        "offset": position,
+                        "data": data,
+                        "size": position,
+                    }),
+                    vec!["key", "column", "data"],
+                ));
+                responses.push((
+                    "read_blob_response",
+                    "ReadBlob",
+                    json!({
+                        "column": column,
+                        "offset": position,
+                        "data": data,
+                        "size": position,
+                    }),
+                    vec!["column", "offset", "data", "size"],
+                ));
+                responses.push((
+                    "write_blob_response",
+                    "WriteBlob",
+                    json!({
+                        "column": column,
+                        "written_bytes": position,
+                        "size": position,
+                    }),
+                    vec!["column", "written_bytes", "size"],
+                ));
+            }
+
+            for (name, typ, fields, required) in requests.into_iter().chain(responses) {
+                let mut properties = json!({
+                    "type": { "const": typ },
+                    "table": { "const": table.as_str() },
+                    "request_id": request_id,
+                });
+                properties
+                    .as_object_mut()
+                    .expect("properties is an object")
+                    .extend(fields.as_object().cloned().unwrap_or_default());
+
+                let required = ["type", "table", "request_id"]
+                    .into_iter()
+                    .chain(required)
+                    .collect::<Vec<_>>();
+
+                defs.insert(
+                    format!("{table}_{name}"),
+                    json!({
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    }),
+                );
+            }
+        }
+
+        let union = |suffix: &str| {
+            let variants = tables
+                .iter()
+                .map(|table| schema_ref(&format!("{table}_{suffix}")))
+                .collect::<Vec<_>>();
+            json!({ "oneOf": variants })
+        };
+
+        let messages = [
+            ("ListRows", "list_rows"),
+            ("GetRow", "get_row"),
+            ("InsertRow", "insert_row"),
+            ("BatchInsertRow", "batch_insert_row"),
+            ("DeleteRow", "delete_row"),
+            ("UpdateRow", "update_row"),
+        ];
+
+        let streamed = [
+            ("ListRowsChunk", "list_rows_chunk"),
+            ("ListRowsEnd", "list_rows_end"),
+        ];
+
+        for kind in ["Request", "Response"] {
+            let streamed = match kind {
+                "Response" => &streamed[..],
+                _ => &[],
+            };
+
+            let mut variants = Vec::with_capacity(messages.len() + streamed.len());
+            for &(name, suffix) in messages.iter().chain(streamed) {
+                let name = format!("{name}{kind}");
+                defs.insert(
+                    name.clone(),
+                    union(&format!("{suffix}_{}", kind.to_lowercase())),
+                );
+                variants.push(schema_ref(&name));
+            }
+            if !searchable.is_empty() {
+                let name = format!("Search{kind}");
+                let suffix = format!("search_{}", kind.to_lowercase());
+                let search = searchable
+                    .iter()
+                    .map(|(table, _)| schema_ref(&format!("{table}_{suffix}")))
+                    .collect::<Vec<_>>();
+                defs.insert(name.clone(), json!({ "oneOf": search }));
+                variants.push(schema_ref(&name));
+            }
+            if !blob_columns.is_empty() {
+                for (name, suffix) in [("ReadBlob", "read_blob"), ("WriteBlob", "write_blob")] {
+                    let name = format!("{name}{kind}");
+                    let suffix = format!("{suffix}_{}", kind.to_lowercase());
+                    let blobs = blob_columns
+                        .iter()
+                        .map(|(table, _)| schema_ref(&format!("{table}_{suffix}")))
+                        .collect::<Vec<_>>();
+                    defs.insert(name.clone(), json!({ "oneOf": blobs }));
+                    variants.push(schema_ref(&name));
+                }
+            }
+            for name in [
+                "DescribeSchema",
+                "DescribeTable",
+                "CreateTable",
+                "AddColumn",
+                "CreateIndex",
+                "DropTable",
+            ] {
+                variants.push(schema_ref(&format!("{name}{kind}")));
+            }
+            if kind == "Response" {
+                variants.push(schema_ref("SchemaChanged"));
+            }
+            defs.insert(format!("Api{kind}"), json!({ "oneOf": variants }));
+        }
+
+        let error = |typ: &str, fields: JsonValue| {
+            let mut properties = json!({
+                "type": { "const": typ },
+                "request_id": { "type": "string" },
+            });
+            let mut required = vec![JsonValue::from("type")];
+
+            for (field, schema) in fields.as_object().cloned().unwrap_or_default() {
+                required.push(field.clone().into());
+                properties[field] = schema;
+            }
+
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+            })
+        };
+        let string = json!({ "type": "string" });
+        // The column is missing when the invalid value is a row key.
+        let mut invalid_value = error("InvalidValue", json!({ "message": string }));
+        invalid_value["properties"]["column"] = string.clone();
+
+        defs.insert(
+            "ErrorResponse".into(),
+            json!({
+                "oneOf": [
+                    error("BadRequest", json!({ "message": string })),
+                    error("NonTextMessage", json!({})),
+                    error("RouteNotFound", json!({ "path": string })),
+                    error("MethodNotAllowed", json!({ "method": string })),
+                    error("InternalError", json!({})),
+                    error("DatabaseError", json!({})),
+                    error("TableNotFound", json!({ "table": string })),
+                    error("ColumnsNotFound", json!({ "columns": { "type": "array", "items": string } })),
+                    error("SortColumnNotFound", json!({ "column": string })),
+                    error("PageNumberCanNotBeZero", json!({})),
+                    error("RowNotFound", json!({})),
+                    error("BatchInsertWithNoData", json!({})),
+                    error("BatchInsertWithIrregularColumns", json!({})),
+                    error(
+                        "ValidationFailed",
+                        json!({
+                            "errors": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "column": string,
+                                        "row": { "type": "integer" },
+                                        "message": string,
+                                    },
+                                    "required": ["column", "message"],
+                                },
+                            },
+                        }),
+                    ),
+                    invalid_value,
+                    error("TableNotSearchable", json!({ "table": string })),
+                    error("InvalidSearch", json!({ "message": string })),
+                    error("NotABlob", json!({ "column": string })),
+                    error("BlobOutOfBounds", json!({ "size": { "type": "integer" } })),
+                    error("Forbidden", json!({})),
+                    error("InvalidSchemaChange", json!({ "message": string })),
+                    error("SchemaMismatch", json!({ "server": string, "client": string })),
+                    error(
+                        "UnsupportedProtocolVersion",
+                        json!({ "supported": { "type": "array", "items": { "type": "integer" } } }),
+                    ),
+                    error("DatabaseNotFound", json!({ "database": string })),
+                ],
+            }),
+        );
+
+        Ok(defs)
+    }
+
+    /// The `DescribeSchema` and `DescribeTable` messages, which don't depend on the tables.
+    fn describe_definitions(
+        defs: &mut Map<String, JsonValue>,
+        schema_ref: &impl Fn(&str) -> JsonValue,
+    ) {
+        let string = json!({ "type": "string" });
+        let boolean = json!({ "type": "boolean" });
+        // Expressions in indexes and implicit primary key references have no column name.
+        let names = json!({ "type": "array", "items": { "type": ["string", "null"] } });
+        let object = |properties: JsonValue| {
+            let required = properties
+                .as_object()
+                .map(|properties| properties.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            json!({ "type": "object", "properties": properties, "required": required })
+        };
+
+        defs.insert(
+            "ColumnDescription".into(),
+            object(json!({
+                "name": string,
+                "declared_type": string,
+                "affinity": { "enum": ["INTEGER", "TEXT", "BLOB", "REAL", "NUMERIC"] },
+                "nullable": boolean,
+                "default": { "type": ["string", "null"] },
+                "primary_key": { "type": ["integer", "null"], "minimum": 1 },
+            })),
+        );
+        defs.insert(
+            "IndexDescription".into(),
+            object(json!({
+                "name": string,
+                "columns": names,
+                "unique": boolean,
+                "origin": { "enum": ["create_index", "unique", "primary_key"] },
+                "partial": boolean,
+            })),
+        );
+        defs.insert(
+            "ForeignKeyDescription".into(),
+            object(json!({
+                "columns": { "type": "array", "items": string },
+                "table": string,
+                "references": names,
+                "on_update": string,
+                "on_delete": string,
+            })),
+        );
+        let columns = json!({ "type": "array", "items": schema_ref("ColumnDescription") });
+        defs.insert(
+            "TableDescription".into(),
+            object(json!({
+                "name": string,
+                "columns": columns,
+                "indexes": { "type": "array", "items": schema_ref("IndexDescription") },
+                "foreign_keys": { "type": "array", "items": schema_ref("ForeignKeyDescription") },
+                "strict": boolean,
+            })),
+        );
+        defs.insert(
+            "ViewDescription".into(),
+            object(json!({ "name": string, "columns": columns })),
+        );
+
+        let messages = [
+            ("DescribeSchemaRequest", "DescribeSchema", json!({})),
+            (
+                "DescribeTableRequest",
+                "DescribeTable",
+                json!({ "table": string }),
+            ),
+            (
+                "DescribeSchemaResponse",
+                "DescribeSchema",
+                json!({
+                    "tables": { "type": "array", "items": schema_ref("TableDescription") },
+                    "views": { "type": "array", "items": schema_ref("ViewDescription") },
+                }),
+            ),
+            (
+                "DescribeTableResponse",
+                "DescribeTable",
+                json!({ "table": schema_ref("TableDescription") }),
+            ),
+        ];
+        for (name, typ, fields) in messages {
+            let mut properties = json!({ "type": { "const": typ }, "request_id": string });
+            properties
+                .as_object_mut()
+                .expect("properties is an object")
+                .extend(fields.as_object().cloned().unwrap_or_default());
+            defs.insert(name.into(), object(properties));
+        }
+        // Otherwise a `DescribeSchema` response would also be a valid request.
+        defs["DescribeSchemaRequest"]["additionalProperties"] = false.into();
+    }
+
+    /// The admin requests changing the schema and the `SchemaChanged` notification.
+    fn schema_change_definitions(
+        defs: &mut Map<String, JsonValue>,
+        schema_ref: &impl Fn(&str) -> JsonValue,
+    ) {
+        let string = json!({ "type": "string" });
+        let boolean = json!({ "type": "boolean" });
+        let changes = ["CreateTable", "AddColumn", "CreateIndex", "DropTable"];
+
+        defs.insert(
+            "ColumnDefinition".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": string,
+                    "declared_type": string,
+                    "not_null": boolean,
+                    "primary_key": boolean,
+                    "unique": boolean,
+                    "default": { "type": ["string", "number", "boolean", "null"] },
+                    "references": {
+                        "type": "object",
+                        "properties": { "table": string, "column": string },
+                        "required": ["table"],
+                    },
+                },
+                "required": ["name"],
+            }),
+        );
+
+        let requests = [
+            (
+                "CreateTable",
+                json!({
+                    "table": string,
+                    "columns": {
+                        "type": "array",
+                        "items": schema_ref("ColumnDefinition"),
+                        "minItems": 1,
+                    },
+                    "strict": boolean,
+                }),
+                vec!["type", "table", "columns"],
+            ),
+            (
+                "AddColumn",
+                json!({ "table": string, "column": schema_ref("ColumnDefinition") }),
+                vec!["type", "table", "column"],
+            ),
+            (
+                "CreateIndex",
+                json!({
+                    "table": string,
+                    "name": string,
+                    "columns": { "type": "array", "items": string, "minItems": 1 },
+                    "unique": boolean,
+                }),
+                vec!["type", "table", "name", "columns"],
+            ),
+            (
+                "DropTable",
+                json!({ "table": string }),
+                vec!["type", "table"],
+            ),
+        ];
+        for (typ, fields, required) in requests {
+            let mut properties = json!({ "type": { "const": typ }, "request_id": string });
+            properties
+                .as_object_mut()
+                .expect("properties is an object")
+                .extend(fields.as_object().cloned().unwrap_or_default());
+            defs.insert(
+                format!("{typ}Request"),
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                }),
+            );
+        }
+
+        for typ in changes {
+            defs.insert(
+                format!("{typ}Response"),
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "type": { "const": typ },
+                        "table": string,
+                        "request_id": string,
+                    },
+                    "required": ["type", "table", "request_id"],
+                }),
+            );
+        }
+
+        defs.insert(
+            "SchemaChanged".into(),
+            json!({
+                "type": "object",
+                "description": "Sent to every WebSocket connection after an admin changed the schema.",
+                "properties": {
+                    "type": { "const": "SchemaChanged" },
+                    "table": string,
+                    "change": { "enum": changes },
+                },
+                "required": ["type", "table", "change"],
+            }),
+        );
+    }
+
+    /// The WebSocket handshake, and the `Error` message errors are wrapped in after it
+    /// settled on protocol version 2.
+    fn hello_definitions(
+        defs: &mut Map<String, JsonValue>,
+        schema_ref: &impl Fn(&str) -> JsonValue,
+    ) {
+        let capabilities = json!({ "type": "array", "items": { "type": "string" } });
+        let version = json!({ "type": "integer", "minimum": 1 });
+
+        defs.insert(
+            "HelloRequest".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": "Hello" },
+                    "protocol_version": version,
+                    "capabilities": capabilities,
+                    "request_id": { "type": "string" },
+                },
+                "required": ["type", "protocol_version", "request_id"],
+            }),
+        );
+        defs.insert(
+            "HelloResponse".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": "Hello" },
+                    "protocol_version": version,
+                    "capabilities": capabilities,
+                    "request_id": { "type": "string" },
+                },
+                "required": ["type", "protocol_version", "capabilities", "request_id"],
+            }),
+        );
+        defs.insert(
+            "Error".into(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": "Error" },
+                    "error": schema_ref("ErrorResponse"),
+                    "request_id": { "type": "string" },
+                },
+                "required": ["type", "error"],
+            }),
+        );
+    }
+
+    fn value_schema(typ: &SqlValueType, blob: BlobEncoding) -> JsonValue {
+        match typ {
+            SqlValueType::Null => json!({ "type": "null" }),
+            SqlValueType::Integer => json!({ "type": "integer" }),
+            SqlValueType::Real | SqlValueType::Numeric => json!({ "type": "number" }),
+            SqlValueType::Decimal => json!({ "type": "number", "format": "decimal" }),
+            SqlValueType::Text => json!({ "type": "string" }),
+            SqlValueType::Blob => match blob {
+                BlobEncoding::Base64 => json!({
+                    "type": "string",
+                    "contentEncoding": "base64",
+                    "description": blob.description(),
+                }),
+                BlobEncoding::Hex => json!({
+                    "type": "string",
+                    "contentEncoding": "base16",
+                    "description": blob.description(),
+                }),
+                BlobEncoding::Array => json!({
+                    "type": "array",
+                    "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+                    "description": blob.description(),
+                }),
+            },
+            SqlValueType::Boolean => json!({ "type": "boolean" }),
+            SqlValueType::Date => json!({ "type": "string", "format": "date" }),
+            SqlValueType::DateTime => json!({ "type": "string", "format": "date-time" }),
+            // Any json document.
+            SqlValueType::Json => json!({}),
+        }
+    }
+
+    fn nullable(mut schema: JsonValue) -> JsonValue {
+        match schema["type"].as_str() {
+            Some("null") | None => schema,
+            Some(typ) => {
+                schema["type"] = json!([typ, "null"]);
+                schema
+            }
+        }
+    }
+}
+
+mod naming {
+    pub fn words(s: &str) -> Vec<String> {
+        let chars = s.chars().collect::<Vec<_>>();
+        let mut words = Vec::new();
+        let mut word = String::new();
+
+        for (i, c) in chars.iter().enumerate() {
+            if !c.is_ascii_alphanumeric() {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let starts_word = c.is_ascii_uppercase()
+                && (prev.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+                    || (prev.is_some_and(|p| p.is_ascii_uppercase())
+                        && next.is_some_and(|n| n.is_ascii_lowercase())));
+
+            if starts_word && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.push(*c);
+        }
+
+        if !word.is_empty() {
+            words.push(word);
+        }
+
+        words
+    }
+
+    pub fn pascal_case(s: &str) -> String {
+        let name = words(s)
+            .iter()
+            .map(|w| {
+                let mut chars = w.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<String>();
+
+        if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+            format!("T{name}")
+        } else {
+            name
+        }
+    }
+
+    pub fn singular(s: &str) -> String {
+        if let Some(stem) = s.strip_suffix("ies") {
+            format!("{stem}y")
+        } else if s.ends_with("ss") {
+            s.to_string()
+        } else {
+            s.strip_suffix('s').unwrap_or(s).to_string()
+        }
+    }
+}
+
+mod rust_client {
+    use std::fmt::Write;
+
+    use color_eyre::eyre::Context;
+
+    use crate::{
+        db::{BlobEncoding, Database, SqlValueType, SqliteDatabase},
+        naming::{pascal_case, singular, words},
+        BoxStr,
+    };
+
+    const RUST_KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
+        "enum", "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match",
+        "mod", "move", "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while", "yield",
+    ];
+
+    pub async fn generate_rust_client(
+        db: SqliteDatabase,
+        out_path: BoxStr,
+        database: Option<&str>,
+    ) -> color_eyre::Result<()> {
+        tracing::info!("generating rust client library");
+
+        let tables = db
+            .get_user_tables()
+            .await
+            .context("failed to fetch tables")?;
+
+        let mut client = RUNTIME.to_string();
+        writeln!(
+            client,
+            "\n/// Identifies the schema this client was generated for.\npub const SCHEMA_FINGERPRINT: &str = \"{}\";",
+            db.schema_fingerprint().await?
+        )?;
+        writeln!(
+            client,
+            "/// The database this client connects to, the server's default when `None`.\npub const DATABASE: Option<&str> = {database:?};"
+        )?;
+
+        let blob = db.blob_encoding();
+        let searchable = db.get_searchable_tables(&tables).await?;
+        let blob_columns = db.get_blob_columns(&tables).await?;
+
+        for table in tables.iter() {
+            let name = singular(&pascal_case(table.as_str()));
+            let primary_key_type = db.get_primary_key_type(table).await?;
+            let columns = db.get_column_types(table).await?;
+
+            writeln!(client)?;
+            writeln!(client, "/// A row in the `{table}` table.")?;
+            writeln!(
+                client,
+                "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]"
+            )?;
+            writeln!(client, "pub struct {name} {{")?;
+            for (col, (typ, required)) in columns.iter() {
+                if *typ == SqlValueType::Blob {
+                    writeln!(client, "    /// A {}.", blob.description())?;
+                }
+                let field = field_name(col.as_str());
+                let typ = value_type(typ, blob);
+                if *required {
+                    writeln!(client, "    #[serde(rename = \"{col}\")]")?;
+                    writeln!(client, "    pub {field}: {typ},")?;
+                } else {
+                    writeln!(
+                        client,
+                        "    #[serde(rename = \"{col}\", default, skip_serializing_if = \"Option::is_none\")]"
+                    )?;
+                    writeln!(client, "    pub {field}: Option<{typ}>,")?;
+                }
+            }
+            writeln!(client, "}}")?;
+
+            writeln!(client)?;
+            writeln!(
+                client,
+                "/// A row in the `{table}` table where every column is optional."
+            )?;
+            writeln!(
+                client,
+                "#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]"
+            )?;
+            writeln!(client, "pub struct {name}Optional {{")?;
+            for (col, (typ, _)) in columns.iter() {
+                if *typ == SqlValueType::Blob {
+                    writeln!(client, "    /// A {}.", blob.description())?;
+                }
+                let field = field_name(col.as_str());
+                let typ = value_type(typ, blob);
+                writeln!(
+                    client,
+                    "    #[serde(rename = \"{col}\", default, skip_serializing_if = \"Option::is_none\")]"
+                )?;
+                writeln!(client, "    pub {field}: Option<{typ}>,")?;
+            }
+            writeln!(client, "}}")?;
+
+            writeln!(client)?;
+            writeln!(client, "/// The primary key of the `{table}` table.")?;
+            writeln!(
+                client,
+                "pub type {name}Key = {};",
+                value_type(&primary_key_type, blob)
+            )?;
+
+            writeln!(client)?;
+            writeln!(client, "/// The columns of the `{table}` table.")?;
+            writeln!(
+                client,
+                "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]"
+            )?;
+            writeln!(client, "pub enum {name}Column {{")?;
+            for (col, _) in columns.iter() {
+                writeln!(client, "    #[serde(rename = \"{col}\")]")?;
+                writeln!(client, "    {},", variant_name(col.as_str()))?;
+            }
+            writeln!(client, "}}")?;
+
+            writeln!(
+                client,
+                r#"
+impl Table for {name} {{
+    const NAME: &'static str = "{table}";
+    type Optional = {name}Optional;
+    type Key = {name}Key;
+    type Column = {name}Column;
+}}"#
+            )?;
+
+            if searchable.iter().any(|(t, _)| t == table) {
+                writeln!(client)?;
+                writeln!(client, "impl Searchable for {name} {{}}")?;
+            }
+
+            if let Some((_, columns)) = blob_columns.iter().find(|(t, _)| t == table) {
+                writeln!(client)?;
+                writeln!(client, "/// The blob columns of the `{table}` table.")?;
+                writeln!(
+                    client,
+                    "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]"
+                )?;
+                writeln!(client, "pub enum {name}BlobColumn {{")?;
+                for col in columns.iter() {
+                    writeln!(client, "    #[serde(rename = \"{col}\")]")?;
+                    writeln!(client, "    {},", variant_name(col.as_str()))?;
+                }
+                writeln!(client, "}}")?;
+
+                writeln!(
+                    client,
+                    r#"
+impl BlobTable for {name} {{
+    type BlobColumn = {name}BlobColumn;
+    type Blob = {};
+}}"#,
+                    value_type(&SqlValueType::Blob, blob)
+                )?;
+            }
+        }
+
+        std::fs::write(out_path.as_ref(), client)?;
+        tracing::info!("rust client library generated at {out_path}");
+        Ok(())
+    }
+
+    fn value_type(typ: &SqlValueType, blob: BlobEncoding) -> &'static str {
+        match typ {
+            SqlValueType::Null => "()",
+            SqlValueType::Integer => "i64",
+            SqlValueType::Real | SqlValueType::Numeric | SqlValueType::Decimal => "f64",
+            SqlValueType::Text | SqlValueType::Date | SqlValueType::DateTime => "String",
+            SqlValueType::Blob => match blob {
+                BlobEncoding::Array => "Vec<u8>",
+                BlobEncoding::Base64 | BlobEncoding::Hex => "String",
+            },
+            SqlValueType::Boolean => "bool",
+            SqlValueType::Json => "serde_json::Value",
+        }
+    }
+
+    fn field_name(s: &str) -> String {
+        let name = words(s)
+            .iter()
+            .map(|w| w.to_ascii_lowercase())
+            .collect::<Vec<_>>()
+            .join("_");
+
+        if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+            format!("_{name}")
+        } else if RUST_KEYWORDS.contains(&name.as_str()) {
+            format!("r#{name}")
+        } else {
+            name
+        }
+    }
+
+    fn variant_name(s: &str) -> String {
+        match pascal_case(s).as_str() {
+            "Self" => "Self_".into(),
+            name => name.into(),
+        }
+    }
+
+    const RUNTIME: &str = r#"//! Generated by jabroni, do not edit.
+//!
+//! Depends on `serde` (with the `derive` feature), `serde_json`, `futures-util`,
+//! `tokio` (with the `rt` and `sync` features) and `tokio-tungstenite`.
+
+#![allow(dead_code)]
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio_tungstenite::tungstenite::{
+    self, client::IntoClientRequest, http::HeaderValue, Message,
+};
+
+/// A table in the database, implemented by the full row type of every table.
+pub trait Table: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+    type Optional: Serialize + DeserializeOwned;
+    type Key: Serialize;
+    type Column: Serialize;
+}
+
+/// A table with a search index, which [`Search`] requests can query.
+pub trait Searchable: Table {}
+
+/// A table with blob columns, which [`ReadBlob`] and [`WriteBlob`] requests can target.
+pub trait BlobTable: Table {
+    type BlobColumn: Serialize + DeserializeOwned;
+    /// A blob in the encoding the server was started with.
+    type Blob: Serialize + DeserializeOwned;
+}
+
+/// A request that can be sent with [`Client::send`].
+pub trait Request {
+    /// The `type` the server tags a successful response with.
+    const TYPE: &'static str;
+    type Response: DeserializeOwned;
+
+    fn to_json(&self, request_id: &str) -> Value;
+}
+
+/// Errors are wrapped in an `Error` message from version 2 on.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pagination {
+    pub number: u32,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SortInfo<C> {
+    column: C,
+    order: SortOrder,
+}
+
+/// Lists the rows of a table, e.g. `ListRows::<Employee>::new().page(1, 10)`.
+pub struct ListRows<T: Table> {
+    select: Vec<T::Column>,
+    sort: Option<SortInfo<T::Column>>,
+    page: Option<Pagination>,
+}
+
+impl<T: Table> Default for ListRows<T> {
+    fn default() -> Self {
+        Self {
+            select: Vec::new(),
+            sort: None,
+            page: None,
+        }
+    }
+}
+
+impl<T: Table> ListRows<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn select(mut self, columns: impl IntoIterator<Item = T::Column>) -> Self {
+        self.select = columns.into_iter().collect();
+        self
+    }
+
+    pub fn sort(mut self, column: T::Column, order: SortOrder) -> Self {
+        self.sort = Some(SortInfo { column, order });
+        self
+    }
+
+    pub fn page(mut self, number: u32, size: u32) -> Self {
+        self.page = Some(Pagination { number, size });
+        self
+    }
+}
+
+impl<T: Table> Request for ListRows<T> {
+    const TYPE: &'static str = "ListRows";
+    type Response = ListRowsResponse<T::Optional>;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "select": self.select,
+            "sort": self.sort,
+            "page": self.page,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Gets a single row by its primary key.
+pub struct GetRow<T: Table> {
+    key: T::Key,
+    select: Vec<T::Column>,
+}
+
+impl<T: Table> GetRow<T> {
+    pub fn new(key: T::Key) -> Self {
+        Self {
+            key,
+            select: Vec::new(),
+        }
+    }
+
+    pub fn select(mut self, columns: impl IntoIterator<Item = T::Column>) -> Self {
+        self.select = columns.into_iter().collect();
+        self
+    }
+}
+
+impl<T: Table> Request for GetRow<T> {
+    const TYPE: &'static str = "GetRow";
+    type Response = GetRowResponse<T::Optional>;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "key": self.key,
+            "select": self.select,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Inserts a single row.
+pub struct InsertRow<T: Table> {
+    data: T,
+}
+
+impl<T: Table> InsertRow<T> {
+    pub fn new(data: T) -> Self {
+        Self { data }
+    }
+}
+
+impl<T: Table> Request for InsertRow<T> {
+    const TYPE: &'static str = "InsertRow";
+    type Response = InsertRowResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "data": self.data,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Inserts several rows in a single statement.
+pub struct BatchInsertRow<T: Table> {
+    data: Vec<T>,
+}
+
+impl<T: Table> BatchInsertRow<T> {
+    pub fn new(data: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            data: data.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: Table> Request for BatchInsertRow<T> {
+    const TYPE: &'static str = "BatchInsertRow";
+    type Response = InsertRowResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "data": self.data,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Deletes a single row by its primary key.
+pub struct DeleteRow<T: Table> {
+    key: T::Key,
+    table: PhantomData<T>,
+}
+
+impl<T: Table> DeleteRow<T> {
+    pub fn new(key: T::Key) -> Self {
+        Self {
+            key,
+            table: PhantomData,
+        }
+    }
+}
+
+impl<T: Table> Request for DeleteRow<T> {
+    const TYPE: &'static str = "DeleteRow";
+    type Response = DeleteRowResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "key": self.key,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Updates the columns that are set in `data` of a single row.
+pub struct UpdateRow<T: Table> {
+    key: T::Key,
+    data: T::Optional,
+}
+
+impl<T: Table> UpdateRow<T> {
+    pub fn new(key: T::Key, data: T::Optional) -> Self {
+        Self { key, data }
+    }
+}
+
+impl<T: Table> Request for UpdateRow<T> {
+    const TYPE: &'static str = "UpdateRow";
+    type Response = UpdateRowResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "key": self.key,
+            "data": self.data,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Wraps the matches in the indexed columns in `open` and `close`, `<b>` and `</b>` by default.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Highlight {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close: Option<String>,
+}
+
+/// Like [`Highlight`], but only returns the part of each column with the most matches,
+/// at most `tokens` (1 to 64, 16 by default) long.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Snippet {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ellipsis: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<u8>,
+}
+
+/// Runs a full-text query against a table's search index, e.g. `Search::<Track>::new("love")`.
+pub struct Search<T: Searchable> {
+    query: String,
+    select: Vec<T::Column>,
+    highlight: Option<Highlight>,
+    snippet: Option<Snippet>,
+    page: Option<Pagination>,
+}
+
+impl<T: Searchable> Search<T> {
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            select: Vec::new(),
+            highlight: None,
+            snippet: None,
+            page: None,
+        }
+    }
+
+    pub fn select(mut self, columns: impl IntoIterator<Item = T::Column>) -> Self {
+        self.select = columns.into_iter().collect();
+        self
+    }
+
+    pub fn highlight(mut self, highlight: Highlight) -> Self {
+        self.highlight = Some(highlight);
+        self
+    }
+
+    pub fn snippet(mut self, snippet: Snippet) -> Self {
+        self.snippet = Some(snippet);
+        self
+    }
+
+    pub fn page(mut self, number: u32, size: u32) -> Self {
+        self.page = Some(Pagination { number, size });
+        self
+    }
+}
+
+impl<T: Searchable> Request for Search<T> {
+    const TYPE: &'static str = "Search";
+    type Response = SearchResponse<T::Optional>;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "query": self.query,
+            "select": self.select,
+            "highlight": self.highlight,
+            "snippet": self.snippet,
+            "page": self.page,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Reads part of a blob, from `offset` (0 by default) up to `length` bytes or its end.
+pub struct ReadBlob<T: BlobTable> {
+    key: T::Key,
+    column: T::BlobColumn,
+    offset: Option<usize>,
+    length: Option<usize>,
+}
+
+impl<T: BlobTable> ReadBlob<T> {
+    pub fn new(key: T::Key, column: T::BlobColumn) -> Self {
+        Self {
+            key,
+            column,
+            offset: None,
+            length: None,
+        }
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+}
+
+impl<T: BlobTable> Request for ReadBlob<T> {
+    const TYPE: &'static str = "ReadBlob";
+    type Response = ReadBlobResponse<T::BlobColumn, T::Blob>;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "key": self.key,
+            "column": self.column,
+            "offset": self.offset,
+            "length": self.length,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Writes `data` into a blob at `offset`. Writes can't grow a blob, set `size` to
+/// replace it with that many zero bytes first.
+pub struct WriteBlob<T: BlobTable> {
+    key: T::Key,
+    column: T::BlobColumn,
+    data: T::Blob,
+    offset: Option<usize>,
+    size: Option<usize>,
+}
+
+impl<T: BlobTable> WriteBlob<T> {
+    pub fn new(key: T::Key, column: T::BlobColumn, data: T::Blob) -> Self {
+        Self {
+            key,
+            column,
+            data,
+            offset: None,
+            size: None,
+        }
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+impl<T: BlobTable> Request for WriteBlob<T> {
+    const TYPE: &'static str = "WriteBlob";
+    type Response = WriteBlobResponse<T::BlobColumn>;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": T::NAME,
+            "key": self.key,
+            "column": self.column,
+            "offset": self.offset,
+            "data": self.data,
+            "size": self.size,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Describes every table and view in the database.
+pub struct DescribeSchema;
+
+impl Request for DescribeSchema {
+    const TYPE: &'static str = "DescribeSchema";
+    type Response = DescribeSchemaResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Describes a single table by name, e.g. `DescribeTable::new(Employee::NAME)`.
+pub struct DescribeTable {
+    table: String,
+}
+
+impl DescribeTable {
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+        }
+    }
+}
+
+impl Request for DescribeTable {
+    const TYPE: &'static str = "DescribeTable";
+    type Response = DescribeTableResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": self.table,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// A column of a [`CreateTable`] or [`AddColumn`] request,
+/// e.g. `ColumnDefinition::new("id").declared_type("INTEGER").primary_key()`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ColumnDefinition {
+    name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    declared_type: String,
+    not_null: bool,
+    primary_key: bool,
+    unique: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    references: Option<ColumnReference>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ColumnReference {
+    table: String,
+    column: Option<String>,
+}
+
+impl ColumnDefinition {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn declared_type(mut self, declared_type: impl Into<String>) -> Self {
+        self.declared_type = declared_type.into();
+        self
+    }
+
+    pub fn not_null(mut self) -> Self {
+        self.not_null = true;
+        self
+    }
+
+    /// Every column marked as primary key becomes part of the table's primary key.
+    pub fn primary_key(mut self) -> Self {
+        self.primary_key = true;
+        self
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    pub fn default_value(mut self, value: impl Into<Value>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    /// References `column` of `table`, or its primary key when `column` is `None`.
+    pub fn references(mut self, table: impl Into<String>, column: Option<String>) -> Self {
+        self.references = Some(ColumnReference {
+            table: table.into(),
+            column,
+        });
+        self
+    }
+}
+
+/// Creates a table, only allowed on admin connections.
+pub struct CreateTable {
+    table: String,
+    columns: Vec<ColumnDefinition>,
+    strict: bool,
+}
+
+impl CreateTable {
+    pub fn new(
+        table: impl Into<String>,
+        columns: impl IntoIterator<Item = ColumnDefinition>,
+    ) -> Self {
+        Self {
+            table: table.into(),
+            columns: columns.into_iter().collect(),
+            strict: false,
+        }
+    }
+
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+}
+
+impl Request for CreateTable {
+    const TYPE: &'static str = "CreateTable";
+    type Response = SchemaChangeResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": self.table,
+            "columns": self.columns,
+            "strict": self.strict,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Adds a column to a table, only allowed on admin connections.
+pub struct AddColumn {
+    table: String,
+    column: ColumnDefinition,
+}
+
+impl AddColumn {
+    pub fn new(table: impl Into<String>, column: ColumnDefinition) -> Self {
+        Self {
+            table: table.into(),
+            column,
+        }
+    }
+}
+
+impl Request for AddColumn {
+    const TYPE: &'static str = "AddColumn";
+    type Response = SchemaChangeResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": self.table,
+            "column": self.column,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Creates an index on a table, only allowed on admin connections.
+pub struct CreateIndex {
+    table: String,
+    name: String,
+    columns: Vec<String>,
+    unique: bool,
+}
+
+impl CreateIndex {
+    pub fn new<C: Into<String>>(
+        table: impl Into<String>,
+        name: impl Into<String>,
+        columns: impl IntoIterator<Item = C>,
+    ) -> Self {
+        Self {
+            table: table.into(),
+            name: name.into(),
+            columns: columns.into_iter().map(Into::into).collect(),
+            unique: false,
+        }
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+}
+
+impl Request for CreateIndex {
+    const TYPE: &'static str = "CreateIndex";
+    type Response = SchemaChangeResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": self.table,
+            "name": self.name,
+            "columns": self.columns,
+            "unique": self.unique,
+            "request_id": request_id,
+        })
+    }
+}
+
+/// Drops a table, only allowed on admin connections.
+pub struct DropTable {
+    table: String,
+}
+
+impl DropTable {
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+        }
+    }
+}
+
+impl Request for DropTable {
+    const TYPE: &'static str = "DropTable";
+    type Response = SchemaChangeResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "table": self.table,
+            "request_id": request_id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListRowsResponse<R> {
+    pub table: String,
+    pub rows: Vec<R>,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GetRowResponse<R> {
+    pub table: String,
+    pub row: R,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InsertRowResponse {
+    pub table: String,
+    pub inserted_rows: usize,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeleteRowResponse {
+    pub table: String,
+    pub deleted_rows: usize,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateRowResponse {
+    pub table: String,
+    pub updated_rows: usize,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchResponse<R> {
+    pub table: String,
+    pub hits: Vec<SearchHit<R>>,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchHit<R> {
+    pub row: R,
+    /// Lower is better.
+    pub rank: f64,
+    #[serde(default)]
+    pub highlights: Option<HashMap<String, Option<String>>>,
+    #[serde(default)]
+    pub snippets: Option<HashMap<String, Option<String>>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReadBlobResponse<C, B> {
+    pub table: String,
+    pub column: C,
+    pub offset: usize,
+    pub data: B,
+    /// Size of the whole blob.
+    pub size: usize,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WriteBlobResponse<C> {
+    pub table: String,
+    pub column: C,
+    pub written_bytes: usize,
+    pub size: usize,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DescribeSchemaResponse {
+    pub tables: Vec<TableDescription>,
+    pub views: Vec<ViewDescription>,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DescribeTableResponse {
+    pub table: TableDescription,
+    pub request_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TableDescription {
+    pub name: String,
+    pub columns: Vec<ColumnDescription>,
+    pub indexes: Vec<IndexDescription>,
+    pub foreign_keys: Vec<ForeignKeyDescription>,
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ViewDescription {
+    pub name: String,
+    pub columns: Vec<ColumnDescription>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnDescription {
+    pub name: String,
+    pub declared_type: String,
+    pub affinity: Affinity,
+    pub nullable: bool,
+    pub default: Option<String>,
+    /// Position in the primary key, starting at 1.
+    pub primary_key: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Affinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IndexDescription {
+    pub name: String,
+    /// `None` for expressions.
+    pub columns: Vec<Option<String>>,
+    pub unique: bool,
+    pub origin: IndexOrigin,
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexOrigin {
+    CreateIndex,
+    Unique,
+    PrimaryKey,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForeignKeyDescription {
+    pub columns: Vec<String>,
+    pub table: String,
+    /// `None` when the parent's primary key is referenced implicitly.
+    pub references: Vec<Option<String>>,
+    pub on_update: String,
+    pub on_delete: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaChangeResponse {
+    pub table: String,
+    pub request_id: String,
+}
+
+/// Sent by [`Client::connect`], asking for every capability the server has.
+struct Hello;
+
+impl Request for Hello {
+    const TYPE: &'static str = "Hello";
+    type Response = HelloResponse;
+
+    fn to_json(&self, request_id: &str) -> Value {
+        json!({
+            "type": Self::TYPE,
+            "protocol_version": PROTOCOL_VERSION,
+            "request_id": request_id,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct HelloResponse {
+    protocol_version: u32,
+    capabilities: Vec<String>,
+}
+
+/// Sent to every connection after an admin changed the schema, see [`Client::schema_changes`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct SchemaChanged {
+    pub table: String,
+    pub change: SchemaChange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum SchemaChange {
+    CreateTable,
+    AddColumn,
+    CreateIndex,
+    DropTable,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum ErrorResponse {
+    BadRequest { message: String },
+    NonTextMessage,
+    DatabaseError,
+    TableNotFound { table: String },
+    ColumnsNotFound { columns: Vec<String> },
+    SortColumnNotFound { column: String },
+    PageNumberCanNotBeZero,
+    RowNotFound,
+    BatchInsertWithNoData,
+    BatchInsertWithIrregularColumns,
+    ValidationFailed { errors: Vec<InvalidColumn> },
+    InvalidValue {
+        /// Missing when the value is a row key.
+        column: Option<String>,
+        message: String,
+    },
+    TableNotSearchable { table: String },
+    InvalidSearch { message: String },
+    NotABlob { column: String },
+    BlobOutOfBounds { size: usize },
+    Forbidden,
+    InvalidSchemaChange { message: String },
+    /// The server's schema differs from the one this client was generated for.
+    SchemaMismatch { server: String, client: String },
+    UnsupportedProtocolVersion { supported: Vec<u32> },
+    DatabaseNotFound { database: String },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InvalidColumn {
+    pub column: String,
+    /// Index of the offending row in a batch insert.
+    pub row: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    WebSocket(tungstenite::Error),
+    Json(serde_json::Error),
+    Server(ErrorResponse),
+    ConnectionClosed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::WebSocket(err) => write!(f, "websocket error: {err}"),
+            Error::Json(err) => write!(f, "json error: {err}"),
+            Error::Server(err) => write!(f, "server error: {err:?}"),
+            Error::ConnectionClosed => write!(f, "connection closed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<tungstenite::Error> for Error {
+    fn from(err: tungstenite::Error) -> Self {
+        Error::WebSocket(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Json(err)
+    }
+}
+
+/// Appends the database to the path of `url`, which is where the server looks for it.
+fn with_database(url: &str, database: &str) -> String {
+    let (url, query) = match url.split_once('?') {
+        Some((url, query)) => (url, Some(query)),
+        None => (url, None),
+    };
+    let mut url = format!("{}/{database}", url.trim_end_matches('/'));
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(query);
+    }
+    url
+}
+
+/// Requests waiting for a response, `None` once the connection is closed.
+type Pending = Arc<Mutex<Option<VecDeque<(String, oneshot::Sender<Value>)>>>>;
+
+/// A single WebSocket connection to a jabroni server.
+///
+/// Responses are matched to requests by their `request_id`. Messages without one are
+/// handed to the oldest pending request, the server answers requests in order.
+pub struct Client {
+    outgoing: mpsc::UnboundedSender<Message>,
+    pending: Pending,
+    next_id: AtomicU64,
+    schema_changes: broadcast::Sender<SchemaChanged>,
+    /// Why the server closed the connection, if it was generated for another schema.
+    mismatch: Arc<OnceLock<ErrorResponse>>,
+    protocol_version: u32,
+    capabilities: Vec<String>,
+}
+
+impl Client {
+    pub async fn connect(url: &str) -> Result<Self, Error> {
+        let url = match DATABASE {
+            Some(database) => with_database(url, database),
+            None => url.to_string(),
+        };
+        let mut request = url.into_client_request()?;
+        request.headers_mut().insert(
+            "x-jabroni-schema",
+            HeaderValue::from_static(SCHEMA_FINGERPRINT),
+        );
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        let (mut sink, mut stream) = socket.split();
+
+        let (outgoing, mut rx) = mpsc::unbounded_channel::<Message>();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let pending: Pending = Arc::new(Mutex::new(Some(VecDeque::new())));
+        let reader_pending = pending.clone();
+        let (schema_changes, _) = broadcast::channel(16);
+        let reader_schema_changes = schema_changes.clone();
+        let mismatch = Arc::new(OnceLock::new());
+        let reader_mismatch = mismatch.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                if !message.is_text() {
+                    continue;
+                }
+                let Ok(value) = serde_json::from_str::<Value>(message.to_text().unwrap_or_default())
+                else {
+                    continue;
+                };
+
+                // Not an answer to any request.
+                if value.get("type").and_then(Value::as_str) == Some("SchemaChanged") {
+                    if let Ok(change) = serde_json::from_value(value) {
+                        let _ = reader_schema_changes.send(change);
+                    }
+                    continue;
+                }
+                // Sent right before the server closes the connection, every request fails with it.
+                if value.get("type").and_then(Value::as_str) == Some("SchemaMismatch") {
+                    if let Ok(error) = serde_json::from_value(value) {
+                        let _ = reader_mismatch.set(error);
+                    }
+                    break;
+                }
+
+                let mut pending = reader_pending.lock().expect("pending lock poisoned");
+                let Some(pending) = pending.as_mut() else {
+                    break;
+                };
+
+                let index = value
+                    .get("request_id")
+                    .and_then(Value::as_str)
+                    .and_then(|id| pending.iter().position(|(p, _)| p == id))
+                    .unwrap_or(0);
+
+                if let Some((_, tx)) = pending.remove(index) {
+                    let _ = tx.send(value);
+                }
+            }
+
+            reader_pending.lock().expect("pending lock poisoned").take();
+        });
+
+        let mut client = Self {
+            outgoing,
+            pending,
+            next_id: AtomicU64::new(0),
+            schema_changes,
+            mismatch,
+            protocol_version: 1,
+            capabilities: Vec::new(),
+        };
+        match client.send(&Hello).await {
+            Ok(hello) => {
+                client.protocol_version = hello.protocol_version;
+                client.capabilities = hello.capabilities;
+            }
+            // Servers predating the handshake don't know the request, and speak version 1.
+            Err(Error::Server(ErrorResponse::BadRequest { .. })) => {}
+            Err(err) => return Err(err),
+        }
+        Ok(client)
+    }
+
+    /// The version both sides speak, 1 for servers predating the handshake.
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// What the server supports, e.g. `admin` when the connection may change the schema.
+    pub fn capabilities(&self) -> &[String] {
+        &self.capabilities
+    }
+
+    /// Schema changes made by admins from now on.
+    pub fn schema_changes(&self) -> broadcast::Receiver<SchemaChanged> {
+        self.schema_changes.subscribe()
+    }
+
+    pub async fn send<R: Request>(&self, request: &R) -> Result<R::Response, Error> {
+        let request_id = self.next_id.fetch_add(1, Ordering::Relaxed).to_string();
+        let (tx, rx) = oneshot::channel();
+        let closed = || match self.mismatch.get() {
+            Some(error) => Error::Server(error.clone()),
+            None => Error::ConnectionClosed,
+        };
+
+        self.pending
+            .lock()
+            .expect("pending lock poisoned")
+            .as_mut()
+            .ok_or_else(closed)?
+            .push_back((request_id.clone(), tx));
+
+        let message = request.to_json(&request_id).to_string();
+        self.outgoing
+            .send(Message::text(message))
+            .map_err(|_| closed())?;
+
+        let mut value = rx.await.map_err(|_| closed())?;
+        match value.get("type").and_then(Value::as_str) {
+            Some(typ) if typ == R::TYPE => Ok(serde_json::from_value(value)?),
+            Some("Error") => Err(Error::Server(serde_json::from_value(value["error"].take())?)),
+            _ => Err(Error::Server(serde_json::from_value(value)?)),
+        }
+    }
+}
+"#;
+}
+
+mod python_client {
+    use std::fmt::Write;
+
+    use color_eyre::eyre::Context;
+
+    use crate::{
+        db::{BlobEncoding, Database, SqlValueType, SqliteDatabase},
+        naming::{pascal_case, singular, words},
+        BoxStr,
+    };
+
+    const PYTHON_KEYWORDS: &[&str] = &[
+        "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+        "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+        "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+        "try", "while", "with", "yield",
+    ];
+
+    const MESSAGES: [(&str, &str); 6] = [
+        ("ListRows", "list_rows"),
+        ("GetRow", "get_row"),
+        ("InsertRow", "insert_row"),
+        ("BatchInsertRow", "batch_insert_row"),
+        ("DeleteRow", "delete_row"),
+        ("UpdateRow", "update_row"),
+    ];
+
+    pub async fn generate_python_client(
+        db: SqliteDatabase,
+        out_path: BoxStr,
+        database: Option<&str>,
+    ) -> color_eyre::Result<()> {
+        tracing::info!("generating python client library");
+
+        let tables = db
+            .get_user_tables()
+            .await
+            .context("failed to fetch tables")?;
+
+        let mut client = PRELUDE.to_string();
+        writeln!(client)?;
+        writeln!(client)?;
+        writeln!(
+            client,
+            "# identifies the schema this client was generated for"
+        )?;
+        writeln!(
+            client,
+            "SCHEMA_FINGERPRINT = \"{}\"",
+            db.schema_fingerprint().await?
+        )?;
+        writeln!(
+            client,
+            "# the database this client connects to, the server's default when None"
+        )?;
+        let database = database.map_or("None".into(), |name| format!("\"{name}\""));
+        writeln!(client, "DATABASE: Optional[str] = {database}")?;
+
+        let blob = db.blob_encoding();
+        let searchable = db.get_searchable_tables(&tables).await?;
+        let blob_columns = db.get_blob_columns(&tables).await?;
+
+        for table in tables.iter() {
+            let name = singular(&pascal_case(table.as_str()));
+            let primary_key_type = db.get_primary_key_type(table).await?;
+            let columns = db.get_column_types(table).await?;
+
+            let column_names = columns
+                .iter()
+                .map(|(col, _)| format!("{:?}", col.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            writeln!(client)?;
+            writeln!(client)?;
+            writeln!(client, "{name}Column = Literal[{column_names}]")?;
+            let table_blob_columns = blob_columns.iter().find(|(t, _)| t == table);
+            if let Some((_, columns)) = table_blob_columns {
+                let column_names = columns
+                    .iter()
+                    .map(|col| format!("{:?}", col.as_str()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(client, "{name}BlobColumn = Literal[{column_names}]")?;
+            }
+            writeln!(
+                client,
+                "{name}Key = {}",
+                value_type(&primary_key_type, blob)
+            )?;
+
+            writeln!(client)?;
+            writeln!(client)?;
+            writeln!(client, "class {name}(BaseModel):")?;
+            writeln!(client, "    \"\"\"A row in the `{table}` table.\"\"\"")?;
+            writeln!(client)?;
+            writeln!(client, "    model_config = MODEL_CONFIG")?;
+            writeln!(client)?;
+            for (col, (typ, required)) in columns.iter() {
+                if *typ == SqlValueType::Blob {
+                    writeln!(client, "    # A {}.", blob.description())?;
+                }
+                let typ = value_type(typ, blob);
+                if *required {
+                    writeln!(client, "    {}", field(col.as_str(), typ, ""))?;
+                } else {
+                    writeln!(
+                        client,
+                        "    {}",
+                        field(col.as_str(), &format!("Optional[{typ}]"), "None")
+                    )?;
+                }
+            }
+
+            writeln!(client)?;
+            writeln!(client)?;
+            writeln!(client, "class {name}Optional(BaseModel):")?;
+            writeln!(
+                client,
+                "    \"\"\"A row in the `{table}` table where every column is optional.\"\"\""
+            )?;
+            writeln!(client)?;
+            writeln!(client, "    model_config = MODEL_CONFIG")?;
+            writeln!(client)?;
+            for (col, (typ, _)) in columns.iter() {
+                if *typ == SqlValueType::Blob {
+                    writeln!(client, "    # A {}.", blob.description())?;
+                }
+                let typ = format!("Optional[{}]", value_type(typ, blob));
+                writeln!(client, "    {}", field(col.as_str(), &typ, "None"))?;
+            }
+
+            writeln!(
+                client,
+                r#"
+
+class {name}SortOptions(BaseModel):
+    column: {name}Column
+    order: SortOrder
+
+
+class {name}ListRowsResponse(BaseModel):
+    type: Literal["ListRows"]
+    table: Literal["{table}"]
+    rows: List[{name}Optional]
+    request_id: str
+
+
+class {name}GetRowResponse(BaseModel):
+    type: Literal["GetRow"]
+    table: Literal["{table}"]
+    row: {name}Optional
+    request_id: str
+
+
+class {name}InsertRowResponse(BaseModel):
+    type: Literal["InsertRow"]
+    table: Literal["{table}"]
+    inserted_rows: int
+    request_id: str
+
+
+class {name}BatchInsertRowResponse(BaseModel):
+    type: Literal["BatchInsertRow"]
+    table: Literal["{table}"]
+    inserted_rows: int
+    request_id: str
+
+
+class {name}DeleteRowResponse(BaseModel):
+    type: Literal["DeleteRow"]
+    table: Literal["{table}"]
+    deleted_rows: int
+    request_id: str
+
+
+class {name}UpdateRowResponse(BaseModel):
+    type: Literal["UpdateRow"]
+    table: Literal["{table}"]
+    updated_rows: int
+    request_id: str
+
+
+class {name}ListRowsRequest(Request[{name}ListRowsResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}ListRowsResponse
+
+    type: Literal["ListRows"] = "ListRows"
+    table: Literal["{table}"] = "{table}"
+    select: List[{name}Column] = []
+    sort: Optional[{name}SortOptions] = None
+    page: Optional[Pagination] = None
+
+
+class {name}GetRowRequest(Request[{name}GetRowResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}GetRowResponse
+
+    type: Literal["GetRow"] = "GetRow"
+    table: Literal["{table}"] = "{table}"
+    key: {name}Key
+    select: List[{name}Column] = []
+
+
+class {name}InsertRowRequest(Request[{name}InsertRowResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}InsertRowResponse
+
+    type: Literal["InsertRow"] = "InsertRow"
+    table: Literal["{table}"] = "{table}"
+    data: {name}
+
+
+class {name}BatchInsertRowRequest(Request[{name}BatchInsertRowResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}BatchInsertRowResponse
+
+    type: Literal["BatchInsertRow"] = "BatchInsertRow"
+    table: Literal["{table}"] = "{table}"
+    data: List[{name}]
+
+
+class {name}DeleteRowRequest(Request[{name}DeleteRowResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}DeleteRowResponse
+
+    type: Literal["DeleteRow"] = "DeleteRow"
+    table: Literal["{table}"] = "{table}"
+    key: {name}Key
+
+
+class {name}UpdateRowRequest(Request[{name}UpdateRowResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}UpdateRowResponse
+
+    type: Literal["UpdateRow"] = "UpdateRow"
+    table: Literal["{table}"] = "{table}"
+    key: {name}Key
+    data: {name}Optional
+
+
+class {namespace}:
+    """Request constructors for the `{table}` table."""
+
+    @staticmethod
+    def list_rows(
+        select: Sequence[{name}Column] = (),
+        sort: Optional[Tuple[{name}Column, SortOrder]] = None,
+        page: Optional[Tuple[int, int]] = None,
+    ) -> {name}ListRowsRequest:
+        return {name}ListRowsRequest(
+            select=list(select),
+            sort={name}SortOptions(column=sort[0], order=sort[1]) if sort else None,
+            page=Pagination(number=page[0], size=page[1]) if page else None,
+        )
+
+    @staticmethod
+    def get_row(key: {name}Key, select: Sequence[{name}Column] = ()) -> {name}GetRowRequest:
+        return {name}GetRowRequest(key=key, select=list(select))
+
+    @staticmethod
+    def insert_row(data: {name}) -> {name}InsertRowRequest:
+        return {name}InsertRowRequest(data=data)
+
+    @staticmethod
+    def batch_insert_row(data: Sequence[{name}]) -> {name}BatchInsertRowRequest:
+        return {name}BatchInsertRowRequest(data=list(data))
+
+    @staticmethod
+    def delete_row(key: {name}Key) -> {name}DeleteRowRequest:
+        return {name}DeleteRowRequest(key=key)
+
+    @staticmethod
+    def update_row(key: {name}Key, data: {name}Optional) -> {name}UpdateRowRequest:
+        return {name}UpdateRowRequest(key=key, data=data)"#,
+                namespace = identifier(table.as_str()),
+            )?;
+
+            // The namespace class's methods come first, then the models they return.
+            let is_searchable = searchable.iter().any(|(t, _)| t == table);
+            if is_searchable {
+                writeln!(
+                    client,
+                    r#"
+    @staticmethod
+    def search(
+        query: str,
+        select: Sequence[{name}Column] = (),
+        highlight: Optional[Highlight] = None,
+        snippet: Optional[Snippet] = None,
+        page: Optional[Tuple[int, int]] = None,
+    ) -> {name}SearchRequest:
+        return {name}SearchRequest(
+            query=query,
+            select=list(select),
+            highlight=highlight,
+            snippet=snippet,
+            page=Pagination(number=page[0], size=page[1]) if page else None,
+        )"#
+                )?;
+            }
+
+            let blob_type = value_type(&SqlValueType::Blob, blob);
+            if table_blob_columns.is_some() {
+                writeln!(
+                    client,
+                    r#"
+    @staticmethod
+    def read_blob(
+        key: {name}Key,
+        column: {name}BlobColumn,
+        offset: Optional[int] = None,
+        length: Optional[int] = None,
+    ) -> {name}ReadBlobRequest:
+        return {name}ReadBlobRequest(key=key, column=column, offset=offset, length=length)
+
+    @staticmethod
+    def write_blob(
+        key: {name}Key,
+        column: {name}BlobColumn,
+        data: {blob_type},
+        offset: Optional[int] = None,
+        size: Optional[int] = None,
+    ) -> {name}WriteBlobRequest:
+        return {name}WriteBlobRequest(key=key, column=column, data=data, offset=offset, size=size)"#
+                )?;
+            }
+
+            if is_searchable {
+                writeln!(
+                    client,
+                    r#"
+
+class {name}SearchHit(BaseModel):
+    row: {name}Optional
+    rank: float
+    highlights: Optional[Dict[str, Optional[str]]] = None
+    snippets: Optional[Dict[str, Optional[str]]] = None
+
+
+class {name}SearchResponse(BaseModel):
+    type: Literal["Search"]
+    table: Literal["{table}"]
+    hits: List[{name}SearchHit]
+    request_id: str
+
+
+class {name}SearchRequest(Request[{name}SearchResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}SearchResponse
+
+    type: Literal["Search"] = "Search"
+    table: Literal["{table}"] = "{table}"
+    query: str
+    select: List[{name}Column] = []
+    highlight: Optional[Highlight] = None
+    snippet: Optional[Snippet] = None
+    page: Optional[Pagination] = None"#
+                )?;
+            }
+
+            if table_blob_columns.is_some() {
+                writeln!(
+                    client,
+                    r#"
+
+class {name}ReadBlobResponse(BaseModel):
+    type: Literal["ReadBlob"]
+    table: Literal["{table}"]
+    column: {name}BlobColumn
+    offset: int
+    data: {blob_type}
+    size: int
+    request_id: str
+
+
+class {name}WriteBlobResponse(BaseModel):
+    type: Literal["WriteBlob"]
+    table: Literal["{table}"]
+    column: {name}BlobColumn
+    written_bytes: int
+    size: int
+    request_id: str
+
+
+class {name}ReadBlobRequest(Request[{name}ReadBlobResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}ReadBlobResponse
+
+    type: Literal["ReadBlob"] = "ReadBlob"
+    table: Literal["{table}"] = "{table}"
+    key: {name}Key
+    column: {name}BlobColumn
+    offset: Optional[int] = None
+    # up to the end of the blob when missing
+    length: Optional[int] = None
+
+
+class {name}WriteBlobRequest(Request[{name}WriteBlobResponse]):
+    response: ClassVar[Type[BaseModel]] = {name}WriteBlobResponse
+
+    type: Literal["WriteBlob"] = "WriteBlob"
+    table: Literal["{table}"] = "{table}"
+    key: {name}Key
+    column: {name}BlobColumn
+    offset: Optional[int] = None
+    # A {description}.
+    data: {blob_type}
+    # replaces the blob with this many zero bytes before writing, writes can't grow a blob
+    size: Optional[int] = None"#,
+                    description = blob.description(),
+                )?;
+            }
+        }
+
+        writeln!(client)?;
+        for (message, _) in MESSAGES {
+            let variants = tables
+                .iter()
+                .map(|table| {
+                    let name = singular(&pascal_case(table.as_str()));
+                    format!("{name}{message}Request")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(client)?;
+            writeln!(client, "{message}Request = Union[{variants}]")?;
+        }
+        let mut requests = MESSAGES
+            .iter()
+            .map(|(message, _)| format!("{message}Request"))
+            .collect::<Vec<_>>();
+        if !searchable.is_empty() {
+            let variants = searchable
+                .iter()
+                .map(|(table, _)| {
+                    let name = singular(&pascal_case(table.as_str()));
+                    format!("{name}SearchRequest")
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(client)?;
+            writeln!(client, "SearchRequest = Union[{variants}]")?;
+            requests.push("SearchRequest".into());
+        }
+        if !blob_columns.is_empty() {
+            for message in ["ReadBlob", "WriteBlob"] {
+                let variants = blob_columns
+                    .iter()
+                    .map(|(table, _)| {
+                        let name = singular(&pascal_case(table.as_str()));
+                        format!("{name}{message}Request")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                writeln!(client)?;
+                writeln!(client, "{message}Request = Union[{variants}]")?;
+                requests.push(format!("{message}Request"));
+            }
+        }
+        requests.extend([
+            "DescribeSchemaRequest".into(),
+            "DescribeTableRequest".into(),
+            "CreateTableRequest".into(),
+            "AddColumnRequest".into(),
+            "CreateIndexRequest".into(),
+            "DropTableRequest".into(),
+        ]);
+        writeln!(client)?;
+        writeln!(client, "ApiRequest = Union[{}]", requests.join(", "))?;
+
+        client.push_str(CLIENT);
+
+        std::fs::write(out_path.as_ref(), client)?;
+        tracing::info!("python client library generated at {out_path}");
+        Ok(())
+    }
+
+    fn value_type(typ: &SqlValueType, blob: BlobEncoding) -> &'static str {
+        match typ {
+            SqlValueType::Null => "None",
+            SqlValueType::Integer => "int",
+            SqlValueType::Real | SqlValueType::Numeric | SqlValueType::Decimal => "float",
+            SqlValueType::Text | SqlValueType::Date | SqlValueType::DateTime => "str",
+            SqlValueType::Blob => match blob {
+                BlobEncoding::Array => "List[int]",
+                BlobEncoding::Base64 | BlobEncoding::Hex => "str",
+            },
+            SqlValueType::Boolean => "bool",
+            SqlValueType::Json => "Any",
+        }
+    }
+
+    fn identifier(s: &str) -> String {
+        let valid = s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !s.starts_with(|c: char| c.is_ascii_digit())
+            && !s.is_empty();
+
+        let name = if valid {
+            s.to_string()
+        } else {
+            words(s).join("_")
+        };
+
+        if name.starts_with(|c: char| c.is_ascii_digit())
+            || name.is_empty()
+            || PYTHON_KEYWORDS.contains(&name.as_str())
+            || name.starts_with('_')
+        {
+            format!("c_{name}")
+        } else {
+            name
+        }
+    }
+
+    /// Columns that aren't valid python identifiers keep their name through an alias.
+    fn field(column: &str, typ: &str, default: &str) -> String {
+        let name = identifier(column);
+        match (name == column, default.is_empty()) {
+            (true, true) => format!("{name}: {typ}"),
+            (true, false) => format!("{name}: {typ} = {default}"),
+            (false, true) => format!("{name}: {typ} = Field(alias={column:?})"),
+            (false, false) => {
+                format!("{name}: {typ} = Field(default={default}, alias={column:?})")
+            }
+        }
+    }
+
+    const PRELUDE: &str = r#""""Generated by jabroni, do not edit.
+
+Depends on `pydantic` (v2) and `websockets`.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import itertools
+import json
+import uuid
+from collections import OrderedDict
+from typing import (
+    Any,
+    Callable,
+    ClassVar,
+    Dict,
+    Generic,
+    List,
+    Literal,
+    Optional,
+    Sequence,
+    Tuple,
+    Type,
+    TypeVar,
+    Union,
+)
+from urllib.parse import parse_qsl, urlencode, urlsplit, urlunsplit
+
+import websockets
+from pydantic import BaseModel, ConfigDict, Field
+
+MODEL_CONFIG = ConfigDict(populate_by_name=True, extra="forbid")
+
+R = TypeVar("R", bound=BaseModel)
+
+SortOrder = Literal["Asc", "Desc"]
+
+
+class Pagination(BaseModel):
+    number: int
+    size: int
+
+
+class Highlight(BaseModel):
+    open: str = "<b>"
+    close: str = "</b>"
+
+
+class Snippet(BaseModel):
+    open: str = "<b>"
+    close: str = "</b>"
+    ellipsis: str = "…"
+    tokens: int = Field(default=16, ge=1, le=64)
+
+
+class InvalidColumn(BaseModel):
+    column: str
+    row: Optional[int] = None
+    message: str
+
+
+class ErrorResponse(BaseModel):
+    model_config = ConfigDict(extra="allow")
+
+    type: str
+    message: Optional[str] = None
+    table: Optional[str] = None
+    columns: Optional[List[str]] = None
+    column: Optional[str] = None
+    errors: Optional[List[InvalidColumn]] = None
+    size: Optional[int] = None
+    server: Optional[str] = None
+    client: Optional[str] = None
+    supported: Optional[List[int]] = None
+    database: Optional[str] = None
+
+
+class ApiError(Exception):
+    """Raised when the server answers a request with an error."""
+
+    def __init__(self, error: ErrorResponse) -> None:
+        super().__init__(error.type)
+        self.error = error
 
-async fn generate_client(db: SqliteDatabase, out_path: BoxStr) -> color_eyre::Result<()> {
-    use color_eyre::eyre::Context;
-    use std::fmt::Write;
 
-    tracing::info!("generating client library");
+class Request(BaseModel, Generic[R]):
+    response: ClassVar[Type[BaseModel]]
 
-    let tables = db.get_tables().await.context("failed to fetch tables")?;
-    let tables = tables
-        .iter()
-        .filter(|t| !t.as_str().starts_with("sqlite_"))
-        .collect::<BoxList<_>>();
+    request_id: str = Field(default_factory=lambda: uuid.uuid4().hex)
 
-    let mut schema = r#"
-import { z } from "zod";
-import { nanoid } from "nanoid";
+    def to_message(self) -> str:
+        message = self.model_dump(mode="json", by_alias=True)
+        data = getattr(self, "data", None)
+        if isinstance(data, BaseModel):
+            # only send the columns that were set, so updates don't null out the rest
+            message["data"] = data.model_dump(mode="json", by_alias=True, exclude_unset=True)
+        return json.dumps(message)
 
-export const Pagination = z
-  .object({
-    number: z.number(),
-    size: z.number(),
-  });
 
-"#
-    .to_string();
+class ColumnDescription(BaseModel):
+    name: str
+    declared_type: str
+    affinity: Literal["INTEGER", "TEXT", "BLOB", "REAL", "NUMERIC"]
+    nullable: bool
+    default: Optional[str]
+    # position in the primary key, starting at 1
+    primary_key: Optional[int]
 
-    for table in tables.iter() {
-        let primary_key_type = db.get_primary_key_type(table).await?;
-        let primary_key_type_schema = match primary_key_type {
-            db::SqlValueType::Null => format!("export const {table}_primary_key = z.null();"),
-            db::SqlValueType::Integer | db::SqlValueType::Real => {
-                format!("export const {table}_primary_key = z.number();")
-            }
-            db::SqlValueType::Text | db::SqlValueType::Blob => {
-                format!("export const {table}_primary_key = z.string();")
-            }
-        };
 
-        writeln!(schema, "{primary_key_type_schema}")?;
-
-        let columns = db.get_column_types(table).await?;
-
-        let mut table_schema = format!("export const {table}_schema = z.object({{");
-        for (col, typ) in columns.iter() {
-            let typ = match typ {
-                (SqlValueType::Null, true) => "z.null(),",
-                (SqlValueType::Null, false) => "z.null().nullable().optional(),",
-                (SqlValueType::Integer, true) => "z.number(),",
-                (SqlValueType::Integer, false) => "z.number().nullable().optional(),",
-                (SqlValueType::Real, true) => "z.number(),",
-                (SqlValueType::Real, false) => "z.number().nullable().optional(),",
-                (SqlValueType::Text, true) => "z.string(),",
-                (SqlValueType::Text, false) => "z.string().nullable().optional(),",
-                (SqlValueType::Blob, true) => "z.string(),",
-                (SqlValueType::Blob, false) => "z.string().nullable().optional(),",
-            };
-            writeln!(table_schema, "  {col}: {typ}")?;
-        }
-        writeln!(table_schema, "}});")?;
-        writeln!(schema, "{table_schema}")?;
-
-        let mut table_schema = format!("export const {table}_schema_optional = z.object({{");
-        for (col, typ) in columns {
-            let typ = match typ {
-                (SqlValueType::Null, _) => "z.null().nullable().optional(),",
-                (SqlValueType::Integer, _) => "z.number().nullable().optional(),",
-                (SqlValueType::Real, _) => "z.number().nullable().optional(),",
-                (SqlValueType::Text, _) => "z.string().nullable().optional(),",
-                (SqlValueType::Blob, _) => "z.string().nullable().optional(),",
-            };
-            writeln!(table_schema, "  {col}: {typ}")?;
-        }
-        writeln!(table_schema, "}});")?;
-        writeln!(schema, "{table_schema}")?;
-    }
+class IndexDescription(BaseModel):
+    name: str
+    # None for expressions
+    columns: List[Optional[str]]
+    unique: bool
+    origin: Literal["create_index", "unique", "primary_key"]
+    partial: bool
 
-    for table in tables.iter() {
-        let columns = db.get_columns(table).await?;
 
-        let columns_schema = columns
-            .iter()
-            .map(|col| format!("z.literal('{col}')"))
-            .collect::<Vec<_>>()
-            .join(",");
-        writeln!(
-            schema,
-            "const {table}_columns = z.union([{columns_schema}]);"
-        )?;
+class ForeignKeyDescription(BaseModel):
+    columns: List[str]
+    table: str
+    # None when the parent's primary key is referenced implicitly
+    references: List[Optional[str]]
+    on_update: str
+    on_delete: str
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_sort_options = z
-  .object({{
-    column: {table}_columns,
-    order: z.enum(["Asc", "Desc"]),
-  }});
-"#
-        )?;
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_list_rows_request = z.object({{
-  type: z.literal("ListRows"),
-  table: z.literal('{table}'),
-  select: z.array({table}_columns).default([]),
-  sort: {table}_sort_options.optional(),
-  page: Pagination.optional(),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
+class TableDescription(BaseModel):
+    name: str
+    columns: List[ColumnDescription]
+    indexes: List[IndexDescription]
+    foreign_keys: List[ForeignKeyDescription]
+    strict: bool
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_get_row_request = z.object({{
-  type: z.literal("GetRow"),
-  table: z.literal('{table}'),
-  key: {table}_primary_key,
-  select: z.array({table}_columns).default([]),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_insert_row_request = z.object({{
-  type: z.literal("InsertRow"),
-  table: z.literal('{table}'),
-  data: {table}_schema,
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
+class ViewDescription(BaseModel):
+    name: str
+    columns: List[ColumnDescription]
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_batch_insert_row_request = z.object({{
-  type: z.literal("BatchInsertRow"),
-  table: z.literal('{table}'),
-  data: z.array({table}_schema),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_delete_row_request = z.object({{
-  type: z.literal("DeleteRow"),
-  table: z.literal('{table}'),
-  key: {table}_primary_key,
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
+class DescribeSchemaResponse(BaseModel):
+    type: Literal["DescribeSchema"]
+    tables: List[TableDescription]
+    views: List[ViewDescription]
+    request_id: str
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_update_row_request = z.object({{
-  type: z.literal("UpdateRow"),
-  table: z.literal('{table}'),
-  key: {table}_primary_key,
-  data: {table}_schema_optional,
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
-    }
 
-    let list_rows_request = tables
-        .iter()
-        .map(|table| format!("{table}_list_rows_request"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const ListRowsRequest = z.discriminatedUnion('table', [{list_rows_request}]);"
-    )?;
+class DescribeTableResponse(BaseModel):
+    type: Literal["DescribeTable"]
+    table: TableDescription
+    request_id: str
 
-    let get_row_request = tables
-        .iter()
-        .map(|table| format!("{table}_get_row_request"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const GetRowRequest = z.discriminatedUnion('table', [{get_row_request}]);"
-    )?;
 
-    let insert_row_request = tables
-        .iter()
-        .map(|table| format!("{table}_insert_row_request"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const InsertRowRequest = z.discriminatedUnion('table', [{insert_row_request}]);"
-    )?;
+class DescribeSchemaRequest(Request[DescribeSchemaResponse]):
+    response: ClassVar[Type[BaseModel]] = DescribeSchemaResponse
 
-    let batch_insert_row_request = tables
-        .iter()
-        .map(|table| format!("{table}_batch_insert_row_request"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const BatchInsertRowRequest = z.discriminatedUnion('table', [{batch_insert_row_request}]);"
-    )?;
+    type: Literal["DescribeSchema"] = "DescribeSchema"
 
-    let delete_row_request = tables
-        .iter()
-        .map(|table| format!("{table}_delete_row_request"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const DeleteRowRequest = z.discriminatedUnion('table', [{delete_row_request}]);"
-    )?;
 
-    let update_row_request = tables
-        .iter()
-        .map(|table| format!("{table}_update_row_request"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const UpdateRowRequest = z.discriminatedUnion('table', [{update_row_request}]);"
-    )?;
+class DescribeTableRequest(Request[DescribeTableResponse]):
+    response: ClassVar[Type[BaseModel]] = DescribeTableResponse
 
-    writeln!(
-        schema,
-        "export const ApiRequest = z.union([ListRowsRequest, GetRowRequest, InsertRowRequest, BatchInsertRowRequest, DeleteRowRequest, UpdateRowRequest]);"
-    )?;
+    type: Literal["DescribeTable"] = "DescribeTable"
+    table: str
 
-    for table in tables.iter() {
-        writeln!(
-            schema,
-            r#"
-export const {table}_list_rows_response = z.object({{
-  type: z.literal('ListRows'),
-  table: z.literal('{table}'),
-  rows: z.array({table}_schema_optional),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_get_row_response = z.object({{
-  type: z.literal('GetRow'),
-  table: z.literal('{table}'),
-  row: {table}_schema_optional,
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
+SchemaChange = Literal["CreateTable", "AddColumn", "CreateIndex", "DropTable"]
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_insert_row_response = z.object({{
-  type: z.literal('InsertRow'),
-  table: z.literal('{table}'),
-  inserted_rows: z.number(),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_batch_insert_row_response = z.object({{
-  type: z.literal('BatchInsertRow'),
-  table: z.literal('{table}'),
-  inserted_rows: z.number(),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
+class ColumnReference(BaseModel):
+    table: str
+    # the referenced table's primary key when missing
+    column: Optional[str] = None
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_delete_row_response = z.object({{
-  type: z.literal('DeleteRow'),
-  table: z.literal('{table}'),
-  deleted_rows: z.number(),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
 
-        writeln!(
-            schema,
-            r#"
-export const {table}_update_row_response = z.object({{
-  type: z.literal('UpdateRow'),
-  table: z.literal('{table}'),
-  updated_rows: z.number(),
-  request_id: z.string().default(() => nanoid()),
-}});
-"#
-        )?;
-    }
+class ColumnDefinition(BaseModel):
+    name: str
+    declared_type: str = ""
+    not_null: bool = False
+    # every column marked here becomes part of the primary key
+    primary_key: bool = False
+    unique: bool = False
+    default: Optional[Union[str, int, float, bool]] = None
+    references: Optional[ColumnReference] = None
 
-    let list_rows_response = tables
-        .iter()
-        .map(|table| format!("{table}_list_rows_response"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const ListRowsResponse = z.discriminatedUnion('table', [{list_rows_response}]);"
-    )?;
 
-    let get_row_response = tables
-        .iter()
-        .map(|table| format!("{table}_get_row_response"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const GetRowResponse = z.discriminatedUnion('table', [{get_row_response}]);"
-    )?;
+class SchemaChangeResponse(BaseModel):
+    type: SchemaChange
+    table: str
+    request_id: str
 
-    let insert_row_response = tables
-        .iter()
-        .map(|table| format!("{table}_insert_row_response"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const InsertRowResponse = z.discriminatedUnion('table', [{insert_row_response}]);"
-    )?;
 
-    let batch_insert_row_response = tables
-        .iter()
-        .map(|table| format!("{table}_batch_insert_row_response"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const BatchInsertRowResponse = z.discriminatedUnion('table', [{batch_insert_row_response}]);"
-    )?;
+class SchemaChanged(BaseModel):
+    """Sent to every connection after an admin changed the schema."""
 
-    let delete_row_response = tables
-        .iter()
-        .map(|table| format!("{table}_delete_row_response"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const DeleteRowResponse = z.discriminatedUnion('table', [{delete_row_response}]);"
-    )?;
+    type: Literal["SchemaChanged"]
+    table: str
+    change: SchemaChange
 
-    let update_row_response = tables
-        .iter()
-        .map(|table| format!("{table}_update_row_response"))
-        .collect::<Vec<_>>()
-        .join(",");
-    writeln!(
-        schema,
-        "export const UpdateRowResponse = z.discriminatedUnion('table', [{update_row_response}]);"
-    )?;
 
-    writeln!(
-        schema,
-        "export const ApiResponse = z.union([ListRowsResponse, GetRowResponse, InsertRowResponse, BatchInsertRowResponse, DeleteRowResponse, UpdateRowResponse]);"
-    )?;
+class CreateTableRequest(Request[SchemaChangeResponse]):
+    response: ClassVar[Type[BaseModel]] = SchemaChangeResponse
 
-    writeln!(
-        schema,
-        r#"
-export const BadRequest = z.object({{
-  message: z.string(),
-}});
+    type: Literal["CreateTable"] = "CreateTable"
+    table: str
+    columns: List[ColumnDefinition] = Field(min_length=1)
+    strict: bool = False
 
-export const TableNotFound = z.object({{
-  table: z.string(),
-}});
 
-export const ColumnsNotFound = z.object({{
-  columns: z.array(z.string()),
-}});
+class AddColumnRequest(Request[SchemaChangeResponse]):
+    response: ClassVar[Type[BaseModel]] = SchemaChangeResponse
 
-export const SortColumnNotFound = z.object({{
-  column: z.string(),
-}});
+    type: Literal["AddColumn"] = "AddColumn"
+    table: str
+    column: ColumnDefinition
 
-export const ErrorResponse = z.discriminatedUnion("type", [
-  z.object({{ type: z.literal("BadRequest"), ...BadRequest.shape }}),
-  z.object({{ type: z.literal("NonTextMessage") }}),
-  z.object({{ type: z.literal("TableNotFound"), ...TableNotFound.shape }}),
-  z.object({{ type: z.literal("ColumnsNotFound"), ...ColumnsNotFound.shape }}),
-  z.object({{ type: z.literal("SortColumnNotFound"), ...SortColumnNotFound.shape }}),
-  z.object({{ type: z.literal("PageNumberCanNotBeZero") }}),
-  z.object({{ type: z.literal("RowNotFound") }}),
-  z.object({{ type: z.literal("DatabaseError") }}),
-]);
 
-export type MakeFetchOptions = {{
-  url: string;
-  connectionCount: number;
-}};
+class CreateIndexRequest(Request[SchemaChangeResponse]):
+    response: ClassVar[Type[BaseModel]] = SchemaChangeResponse
 
-export type Request = z.infer<typeof ApiRequest>;
-export type Response =
-  | {{ data: z.infer<typeof ApiResponse> }}
-  | {{ error: z.infer<typeof ErrorResponse> }};
+    type: Literal["CreateIndex"] = "CreateIndex"
+    table: str
+    name: str
+    columns: List[str] = Field(min_length=1)
+    unique: bool = False
 
-export async function makeWebSocketFetch(
-  {{ url, connectionCount }}: MakeFetchOptions,
-) {{
-  let sockets: WebSocket[] = [];
-  let connectionIndex = 0;
-  const openPromises: (Promise<void>)[] = [];
 
-  sockets = new Array(connectionCount).fill(0).map((_, i) => {{
-    const socket = new WebSocket(url);
+class DropTableRequest(Request[SchemaChangeResponse]):
+    response: ClassVar[Type[BaseModel]] = SchemaChangeResponse
 
-    openPromises.push(
-      new Promise((res) => {{
-        socket.onopen = () => {{
-          res();
-        }};
-      }}),
-    );
+    type: Literal["DropTable"] = "DropTable"
+    table: str
 
-    socket.onclose = () => console.log(i, "WebSocket disconnected");
-    socket.onerror = (error) => console.error(i, "WebSocket error:", error);
 
-    return socket;
-  }});
+# errors are wrapped in an `Error` message from version 2 on
+PROTOCOL_VERSION = 2
 
-  await Promise.all(openPromises);
 
-  function getWebSocket(): WebSocket {{
-    if (sockets.length === 0) {{
-      throw new Error(
-        "WebSocket pool is not initialized.",
-      );
-    }}
+class HelloResponse(BaseModel):
+    type: Literal["Hello"]
+    protocol_version: int
+    capabilities: List[str]
+    request_id: str
 
-    const socket = sockets[connectionIndex];
-    connectionIndex = (connectionIndex + 1) % sockets.length;
-    return socket;
-  }}
 
-  function $fetch(request: Request): Promise<Response> {{
-    const socket = getWebSocket();
-    const request_id = request.request_id;
+class HelloRequest(Request[HelloResponse]):
+    """Sent once per connection before anything else, see `Connection.hello`."""
 
-    const promise = new Promise<Response>((resolve) => {{
-      function handleMessage(event: MessageEvent<string>) {{
-        const error = ErrorResponse.safeParse(JSON.parse(event.data));
-        if (error.data) {{
-          socket.removeEventListener("message", handleMessage);
-          return resolve({{ error: error.data }});
-        }}
+    response: ClassVar[Type[BaseModel]] = HelloResponse
 
-        const resp = ApiResponse.parse(JSON.parse(event.data));
-        socket.removeEventListener("message", handleMessage);
-        return resolve({{ data: resp }});
-      }}
+    type: Literal["Hello"] = "Hello"
+    protocol_version: int = PROTOCOL_VERSION
+    # every capability the server has when empty
+    capabilities: List[str] = []
+"#;
 
-      socket.addEventListener("message", handleMessage);
-    }});
+    const CLIENT: &str = r#"
 
-    socket.send(JSON.stringify({{ ...request, request_id }}));
-    return promise;
-  }}
+class Connection:
+    """A single WebSocket connection and the requests waiting on it.
 
-  return $fetch;
-}}
-"#
-    )?;
+    Responses are matched to requests by their `request_id`. Messages without one are
+    handed to the oldest pending request, the server answers requests in order.
+    """
 
-    std::fs::write(out_path.as_ref(), schema)?;
-    tracing::info!("client library generated at {out_path}");
-    Ok(())
+    def __init__(
+        self,
+        socket: Any,
+        on_schema_change: Optional[Callable[[SchemaChanged], None]] = None,
+    ) -> None:
+        self.socket = socket
+        self.on_schema_change = on_schema_change
+        self.mismatch: Optional[ErrorResponse] = None
+        self.protocol_version = 1
+        self.capabilities: List[str] = []
+        self.pending: "OrderedDict[str, asyncio.Future[Dict[str, Any]]]" = OrderedDict()
+        self.reader = asyncio.ensure_future(self._read())
+
+    async def _read(self) -> None:
+        try:
+            async for message in self.socket:
+                if not isinstance(message, str):
+                    continue
+
+                data = json.loads(message)
+                # broadcast to every connection, not an answer to any request
+                if data.get("type") == "SchemaChanged":
+                    if self.on_schema_change is not None:
+                        self.on_schema_change(SchemaChanged.model_validate(data))
+                    continue
+                # sent right before the server closes the connection, every request fails
+                if data.get("type") == "SchemaMismatch":
+                    self.mismatch = ErrorResponse.model_validate(data)
+                    for future in self.pending.values():
+                        if not future.done():
+                            future.set_result(data)
+                    self.pending.clear()
+                    continue
+
+                future = self.pending.pop(data.get("request_id"), None)
+                if future is None and self.pending:
+                    _, future = self.pending.popitem(last=False)
+
+                if future is not None and not future.done():
+                    future.set_result(data)
+        finally:
+            for future in self.pending.values():
+                if not future.done():
+                    future.set_exception(ConnectionError("WebSocket disconnected"))
+            self.pending.clear()
+
+    async def fetch(self, request: Request[R]) -> R:
+        if self.mismatch is not None:
+            raise ApiError(self.mismatch)
+
+        future: "asyncio.Future[Dict[str, Any]]" = asyncio.get_running_loop().create_future()
+        self.pending[request.request_id] = future
+
+        try:
+            await self.socket.send(request.to_message())
+        except Exception:
+            self.pending.pop(request.request_id, None)
+            raise
+
+        data = await future
+        if data.get("type") == request.type:  # type: ignore[attr-defined]
+            return request.response.model_validate(data)  # type: ignore[return-value]
+        if data.get("type") == "Error":
+            data = data["error"]
+        raise ApiError(ErrorResponse.model_validate(data))
+
+    async def hello(self) -> None:
+        try:
+            hello = await self.fetch(HelloRequest())
+        except ApiError as e:
+            # servers predating the handshake don't know the request, and speak version 1
+            if e.error.type != "BadRequest":
+                raise
+            return
+        self.protocol_version = hello.protocol_version
+        self.capabilities = hello.capabilities
+
+    async def close(self) -> None:
+        await self.socket.close()
+        await self.reader
+
+
+class Client:
+    """A round robin pool of WebSocket connections."""
+
+    def __init__(self, connections: List[Connection]) -> None:
+        if not connections:
+            raise ValueError("WebSocket pool is not initialized.")
+        self.connections = connections
+        self._next = itertools.cycle(connections)
+
+    @property
+    def capabilities(self) -> List[str]:
+        """What the server supports, e.g. `admin` when the connections may change the schema."""
+        return self.connections[0].capabilities
+
+    async def fetch(self, request: Request[R]) -> R:
+        return await next(self._next).fetch(request)
+
+    async def close(self) -> None:
+        await asyncio.gather(*(c.close() for c in self.connections))
+
+    async def __aenter__(self) -> "Client":
+        return self
+
+    async def __aexit__(self, *_: Any) -> None:
+        await self.close()
+
+
+def _connection_url(url: str) -> str:
+    parts = urlsplit(url)
+    query = urlencode([*parse_qsl(parts.query), ("schema", SCHEMA_FINGERPRINT)])
+    path = parts.path or "/"
+    if DATABASE is not None:
+        path = f"{parts.path.rstrip('/')}/{DATABASE}"
+    return urlunsplit(parts._replace(path=path, query=query))
+
+
+async def make_websocket_fetch(
+    url: str,
+    connection_count: int = 1,
+    on_schema_change: Optional[Callable[[SchemaChanged], None]] = None,
+) -> Client:
+    """`on_schema_change` is called once per connection for every change an admin makes."""
+    url = _connection_url(url)
+    sockets = await asyncio.gather(*(websockets.connect(url) for _ in range(connection_count)))
+    client = Client([Connection(socket, on_schema_change) for socket in sockets])
+    try:
+        await asyncio.gather(*(c.hello() for c in client.connections))
+    except BaseException:
+        await client.close()
+        raise
+    return client
+"#;
 }
@@ -0,0 +1,69 @@
+import { assertEquals, nanoid, openRawSocket } from "./wrapper.ts";
+
+// The sample database has no blob columns, so the test makes a table of its
+// own with the `--admin-token` that `just serve` starts the server with.
+const url = "ws://127.0.0.1:3030/?access_token=jabroni";
+
+Deno.test("write and read a blob in parts", async () => {
+  const socket = await openRawSocket(url);
+  const request = (message: Record<string, unknown>) =>
+    socket.request({ ...message, request_id: nanoid() });
+
+  try {
+    const created = await request({
+      type: "CreateTable",
+      table: "blob_test",
+      columns: [
+        { name: "id", declared_type: "INTEGER", primary_key: true },
+        { name: "name", declared_type: "TEXT" },
+        { name: "data", declared_type: "BLOB" },
+      ],
+    });
+    assertEquals(created.type, "CreateTable");
+    await request({
+      type: "InsertRow",
+      table: "blob_test",
+      data: { id: 1, name: "one", data: null },
+    });
+
+    const blob = { table: "blob_test", key: 1, column: "data" };
+    const written = await request({
+      type: "WriteBlob",
+      ...blob,
+      data: "AQID",
+      size: 6,
+    });
+    assertEquals([written.written_bytes, written.size], [3, 6]);
+    await request({ type: "WriteBlob", ...blob, offset: 3, data: "BAUG" });
+
+    const read = await request({
+      type: "ReadBlob",
+      ...blob,
+      offset: 2,
+      length: 3,
+    });
+    assertEquals([read.data, read.size], ["AwQF", 6]);
+
+    const past = await request({
+      type: "WriteBlob",
+      ...blob,
+      offset: 5,
+      data: "AQI=",
+    });
+    assertEquals([past.type, past.size], ["BlobOutOfBounds", 6]);
+
+    const huge = await request({
+      type: "WriteBlob",
+      ...blob,
+      data: "AQI=",
+      size: 2_000_000_000,
+    });
+    assertEquals([huge.type, huge.size], ["BlobOutOfBounds", 1_000_000_000]);
+
+    const text = await request({ type: "ReadBlob", ...blob, column: "name" });
+    assertEquals([text.type, text.column], ["NotABlob", "name"]);
+  } finally {
+    await request({ type: "DropTable", table: "blob_test" });
+    await socket.close();
+  }
+});
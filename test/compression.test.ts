@@ -0,0 +1,149 @@
+import { assert, assertEquals } from "./wrapper.ts";
+import { ApiResponse } from "./jabroni.ts";
+
+// Browsers offer permessage-deflate on their own, Deno's WebSocket doesn't,
+// so this speaks the extension over a plain TCP connection. Messages are
+// compressed with `CompressionStream`, which ends them with a final deflate
+// block as some clients do.
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+function concat(a: Uint8Array, b: Uint8Array) {
+  const out = new Uint8Array(a.length + b.length);
+  out.set(a);
+  out.set(b, a.length);
+  return out;
+}
+
+async function deflate(data: Uint8Array) {
+  const stream = new Blob([data]).stream()
+    .pipeThrough(new CompressionStream("deflate-raw"));
+  return new Uint8Array(await new Response(stream).arrayBuffer());
+}
+
+// The server's messages end in a sync flush, an empty final block ends the
+// stream.
+async function inflate(data: Uint8Array) {
+  const ending = new Uint8Array([0x00, 0x00, 0xff, 0xff, 0x03, 0x00]);
+  const stream = new Blob([concat(data, ending)]).stream()
+    .pipeThrough(new DecompressionStream("deflate-raw"));
+  return new Uint8Array(await new Response(stream).arrayBuffer());
+}
+
+async function connect() {
+  const conn = await Deno.connect({ hostname: "127.0.0.1", port: 3030 });
+  let buffer = new Uint8Array(0);
+
+  async function read(n: number) {
+    while (buffer.length < n) {
+      const chunk = new Uint8Array(64 * 1024);
+      const len = await conn.read(chunk);
+      if (len === null) {
+        throw new Error("connection closed");
+      }
+      buffer = concat(buffer, chunk.subarray(0, len));
+    }
+    const out = buffer.slice(0, n);
+    buffer = buffer.slice(n);
+    return out;
+  }
+
+  async function write(data: Uint8Array) {
+    while (data.length > 0) {
+      data = data.subarray(await conn.write(data));
+    }
+  }
+
+  // Without context takeover every server message inflates on its own.
+  await write(encoder.encode(
+    "GET / HTTP/1.1\r\n" +
+      "Host: 127.0.0.1:3030\r\n" +
+      "Upgrade: websocket\r\n" +
+      "Connection: Upgrade\r\n" +
+      "Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n" +
+      "Sec-WebSocket-Version: 13\r\n" +
+      "Sec-WebSocket-Extensions: permessage-deflate; " +
+      "server_no_context_takeover\r\n" +
+      "\r\n",
+  ));
+  let head = "";
+  while (!head.endsWith("\r\n\r\n")) {
+    head += decoder.decode(await read(1));
+  }
+
+  async function send(message: unknown) {
+    const payload = await deflate(encoder.encode(JSON.stringify(message)));
+    assert(payload.length < 65536);
+    const header = payload.length < 126
+      ? [0xc1, 0x80 | payload.length]
+      : [0xc1, 0x80 | 126, payload.length >> 8, payload.length & 0xff];
+    const mask = crypto.getRandomValues(new Uint8Array(4));
+
+    const frame = new Uint8Array(header.length + 4 + payload.length);
+    frame.set(header);
+    frame.set(mask, header.length);
+    payload.forEach((b, i) => frame[header.length + 4 + i] = b ^ mask[i % 4]);
+    await write(frame);
+  }
+
+  async function receive() {
+    const [first, second] = await read(2);
+    let len = second & 0x7f;
+    if (len === 126) {
+      const bytes = await read(2);
+      len = (bytes[0] << 8) | bytes[1];
+    } else if (len === 127) {
+      const bytes = await read(8);
+      len = Number(new DataView(bytes.buffer).getBigUint64(0));
+    }
+
+    const payload = await read(len);
+    const compressed = (first & 0x40) !== 0;
+    const data = compressed ? await inflate(payload) : payload;
+    return { compressed, message: JSON.parse(decoder.decode(data)) };
+  }
+
+  return { head, send, receive, close: () => conn.close() };
+}
+
+Deno.test("compressed requests and responses", async () => {
+  const socket = await connect();
+  try {
+    assert(socket.head.startsWith("HTTP/1.1 101"));
+    assert(socket.head.includes("permessage-deflate"));
+
+    await socket.send({
+      type: "ListRows",
+      table: "tracks",
+      select: [],
+      request_id: "1",
+    });
+    const list = await socket.receive();
+    assert(list.compressed);
+    const resp = ApiResponse.parse(list.message);
+    assert(resp.type === "ListRows");
+    assertEquals(resp.rows.length, 3503);
+
+    // The first request ended its deflate stream, this one starts another.
+    await socket.send({
+      type: "GetRow",
+      table: "tracks",
+      key: 2,
+      select: ["Name"],
+      request_id: "2",
+    });
+    const row = await socket.receive();
+    assertEquals(row, {
+      compressed: false,
+      message: {
+        type: "GetRow",
+        table: "tracks",
+        row: { Name: "Balls to the Wall" },
+        request_id: "2",
+      },
+    });
+  } finally {
+    socket.close();
+  }
+});
@@ -1,3 +1,4 @@
+
 import { z } from "zod";
 import { nanoid } from "nanoid";
 
@@ -7,33 +8,172 @@ export const Pagination = z
     size: z.number(),
   });
 
+export const Highlight = z.object({
+  open: z.string().optional(),
+  close: z.string().optional(),
+});
+
+export const Snippet = z.object({
+  open: z.string().optional(),
+  close: z.string().optional(),
+  ellipsis: z.string().optional(),
+  tokens: z.number().int().min(1).max(64).optional(),
+});
+
+export const ColumnDescription = z.object({
+  name: z.string(),
+  declared_type: z.string(),
+  affinity: z.enum(["INTEGER", "TEXT", "BLOB", "REAL", "NUMERIC"]),
+  nullable: z.boolean(),
+  default: z.string().nullable(),
+  primary_key: z.number().nullable(),
+});
+
+export const IndexDescription = z.object({
+  name: z.string(),
+  columns: z.array(z.string().nullable()),
+  unique: z.boolean(),
+  origin: z.enum(["create_index", "unique", "primary_key"]),
+  partial: z.boolean(),
+});
+
+export const ForeignKeyDescription = z.object({
+  columns: z.array(z.string()),
+  table: z.string(),
+  references: z.array(z.string().nullable()),
+  on_update: z.string(),
+  on_delete: z.string(),
+});
+
+export const TableDescription = z.object({
+  name: z.string(),
+  columns: z.array(ColumnDescription),
+  indexes: z.array(IndexDescription),
+  foreign_keys: z.array(ForeignKeyDescription),
+  strict: z.boolean(),
+});
+
+export const ViewDescription = z.object({
+  name: z.string(),
+  columns: z.array(ColumnDescription),
+});
+
+export const DescribeSchemaRequest = z.object({
+  type: z.literal("DescribeSchema"),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DescribeTableRequest = z.object({
+  type: z.literal("DescribeTable"),
+  table: z.string(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DescribeSchemaResponse = z.object({
+  type: z.literal("DescribeSchema"),
+  tables: z.array(TableDescription),
+  views: z.array(ViewDescription),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DescribeTableResponse = z.object({
+  type: z.literal("DescribeTable"),
+  table: TableDescription,
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const ColumnDefinition = z.object({
+  name: z.string(),
+  declared_type: z.string().optional(),
+  not_null: z.boolean().optional(),
+  primary_key: z.boolean().optional(),
+  unique: z.boolean().optional(),
+  default: z.union([z.string(), z.number(), z.boolean(), z.null()]).optional(),
+  references: z
+    .object({
+      table: z.string(),
+      column: z.string().optional(),
+    })
+    .optional(),
+});
+
+export const CreateTableRequest = z.object({
+  type: z.literal("CreateTable"),
+  table: z.string(),
+  columns: z.array(ColumnDefinition).min(1),
+  strict: z.boolean().optional(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const AddColumnRequest = z.object({
+  type: z.literal("AddColumn"),
+  table: z.string(),
+  column: ColumnDefinition,
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const CreateIndexRequest = z.object({
+  type: z.literal("CreateIndex"),
+  table: z.string(),
+  name: z.string(),
+  columns: z.array(z.string()).min(1),
+  unique: z.boolean().optional(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const DropTableRequest = z.object({
+  type: z.literal("DropTable"),
+  table: z.string(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const CreateTableResponse = z.object({
+  type: z.literal("CreateTable"),
+  table: z.string(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const AddColumnResponse = CreateTableResponse.extend({
+  type: z.literal("AddColumn"),
+});
+
+export const CreateIndexResponse = CreateTableResponse.extend({
+  type: z.literal("CreateIndex"),
+});
+
+export const DropTableResponse = CreateTableResponse.extend({
+  type: z.literal("DropTable"),
+});
+
+export const SchemaChanged = z.object({
+  type: z.literal("SchemaChanged"),
+  table: z.string(),
+  change: z.enum(["CreateTable", "AddColumn", "CreateIndex", "DropTable"]),
+});
+export type SchemaChanged = z.infer<typeof SchemaChanged>;
+
 export const albums_primary_key = z.number();
-export const albums_schema = z.object({
-  AlbumId: z.number().nullable().optional(),
+export const albums_schema = z.object({  AlbumId: z.number().nullable().optional(),
   Title: z.string(),
   ArtistId: z.number(),
 });
 
-export const albums_schema_optional = z.object({
-  AlbumId: z.number().nullable().optional(),
+export const albums_schema_optional = z.object({  AlbumId: z.number().nullable().optional(),
   Title: z.string().nullable().optional(),
   ArtistId: z.number().nullable().optional(),
 });
 
 export const artists_primary_key = z.number();
-export const artists_schema = z.object({
-  ArtistId: z.number().nullable().optional(),
+export const artists_schema = z.object({  ArtistId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
-export const artists_schema_optional = z.object({
-  ArtistId: z.number().nullable().optional(),
+export const artists_schema_optional = z.object({  ArtistId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
 export const customers_primary_key = z.number();
-export const customers_schema = z.object({
-  CustomerId: z.number().nullable().optional(),
+export const customers_schema = z.object({  CustomerId: z.number().nullable().optional(),
   FirstName: z.string(),
   LastName: z.string(),
   Company: z.string().nullable().optional(),
@@ -48,8 +188,7 @@ export const customers_schema = z.object({
   SupportRepId: z.number().nullable().optional(),
 });
 
-export const customers_schema_optional = z.object({
-  CustomerId: z.number().nullable().optional(),
+export const customers_schema_optional = z.object({  CustomerId: z.number().nullable().optional(),
   FirstName: z.string().nullable().optional(),
   LastName: z.string().nullable().optional(),
   Company: z.string().nullable().optional(),
@@ -65,8 +204,7 @@ export const customers_schema_optional = z.object({
 });
 
 export const employees_primary_key = z.number();
-export const employees_schema = z.object({
-  EmployeeId: z.number().nullable().optional(),
+export const employees_schema = z.object({  EmployeeId: z.number().nullable().optional(),
   LastName: z.string(),
   FirstName: z.string(),
   Title: z.string().nullable().optional(),
@@ -83,8 +221,7 @@ export const employees_schema = z.object({
   Email: z.string().nullable().optional(),
 });
 
-export const employees_schema_optional = z.object({
-  EmployeeId: z.number().nullable().optional(),
+export const employees_schema_optional = z.object({  EmployeeId: z.number().nullable().optional(),
   LastName: z.string().nullable().optional(),
   FirstName: z.string().nullable().optional(),
   Title: z.string().nullable().optional(),
@@ -102,19 +239,16 @@ export const employees_schema_optional = z.object({
 });
 
 export const genres_primary_key = z.number();
-export const genres_schema = z.object({
-  GenreId: z.number().nullable().optional(),
+export const genres_schema = z.object({  GenreId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
-export const genres_schema_optional = z.object({
-  GenreId: z.number().nullable().optional(),
+export const genres_schema_optional = z.object({  GenreId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
 export const invoices_primary_key = z.number();
-export const invoices_schema = z.object({
-  InvoiceId: z.number().nullable().optional(),
+export const invoices_schema = z.object({  InvoiceId: z.number().nullable().optional(),
   CustomerId: z.number(),
   InvoiceDate: z.string(),
   BillingAddress: z.string().nullable().optional(),
@@ -122,11 +256,10 @@ export const invoices_schema = z.object({
   BillingState: z.string().nullable().optional(),
   BillingCountry: z.string().nullable().optional(),
   BillingPostalCode: z.string().nullable().optional(),
-  Total: z.string(),
+  Total: z.number(),
 });
 
-export const invoices_schema_optional = z.object({
-  InvoiceId: z.number().nullable().optional(),
+export const invoices_schema_optional = z.object({  InvoiceId: z.number().nullable().optional(),
   CustomerId: z.number().nullable().optional(),
   InvoiceDate: z.string().nullable().optional(),
   BillingAddress: z.string().nullable().optional(),
@@ -134,62 +267,53 @@ export const invoices_schema_optional = z.object({
   BillingState: z.string().nullable().optional(),
   BillingCountry: z.string().nullable().optional(),
   BillingPostalCode: z.string().nullable().optional(),
-  Total: z.string().nullable().optional(),
+  Total: z.number().nullable().optional(),
 });
 
 export const invoice_items_primary_key = z.number();
-export const invoice_items_schema = z.object({
-  InvoiceLineId: z.number().nullable().optional(),
+export const invoice_items_schema = z.object({  InvoiceLineId: z.number().nullable().optional(),
   InvoiceId: z.number(),
   TrackId: z.number(),
-  UnitPrice: z.string(),
+  UnitPrice: z.number(),
   Quantity: z.number(),
 });
 
-export const invoice_items_schema_optional = z.object({
-  InvoiceLineId: z.number().nullable().optional(),
+export const invoice_items_schema_optional = z.object({  InvoiceLineId: z.number().nullable().optional(),
   InvoiceId: z.number().nullable().optional(),
   TrackId: z.number().nullable().optional(),
-  UnitPrice: z.string().nullable().optional(),
+  UnitPrice: z.number().nullable().optional(),
   Quantity: z.number().nullable().optional(),
 });
 
 export const media_types_primary_key = z.number();
-export const media_types_schema = z.object({
-  MediaTypeId: z.number().nullable().optional(),
+export const media_types_schema = z.object({  MediaTypeId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
-export const media_types_schema_optional = z.object({
-  MediaTypeId: z.number().nullable().optional(),
+export const media_types_schema_optional = z.object({  MediaTypeId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
 export const playlists_primary_key = z.number();
-export const playlists_schema = z.object({
-  PlaylistId: z.number().nullable().optional(),
+export const playlists_schema = z.object({  PlaylistId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
-export const playlists_schema_optional = z.object({
-  PlaylistId: z.number().nullable().optional(),
+export const playlists_schema_optional = z.object({  PlaylistId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
 });
 
 export const playlist_track_primary_key = z.number();
-export const playlist_track_schema = z.object({
-  PlaylistId: z.number().nullable().optional(),
+export const playlist_track_schema = z.object({  PlaylistId: z.number().nullable().optional(),
   TrackId: z.number().nullable().optional(),
 });
 
-export const playlist_track_schema_optional = z.object({
-  PlaylistId: z.number().nullable().optional(),
+export const playlist_track_schema_optional = z.object({  PlaylistId: z.number().nullable().optional(),
   TrackId: z.number().nullable().optional(),
 });
 
 export const tracks_primary_key = z.number();
-export const tracks_schema = z.object({
-  TrackId: z.number().nullable().optional(),
+export const tracks_schema = z.object({  TrackId: z.number().nullable().optional(),
   Name: z.string(),
   AlbumId: z.number().nullable().optional(),
   MediaTypeId: z.number(),
@@ -197,11 +321,10 @@ export const tracks_schema = z.object({
   Composer: z.string().nullable().optional(),
   Milliseconds: z.number(),
   Bytes: z.number().nullable().optional(),
-  UnitPrice: z.string(),
+  UnitPrice: z.number(),
 });
 
-export const tracks_schema_optional = z.object({
-  TrackId: z.number().nullable().optional(),
+export const tracks_schema_optional = z.object({  TrackId: z.number().nullable().optional(),
   Name: z.string().nullable().optional(),
   AlbumId: z.number().nullable().optional(),
   MediaTypeId: z.number().nullable().optional(),
@@ -209,14 +332,10 @@ export const tracks_schema_optional = z.object({
   Composer: z.string().nullable().optional(),
   Milliseconds: z.number().nullable().optional(),
   Bytes: z.number().nullable().optional(),
-  UnitPrice: z.string().nullable().optional(),
+  UnitPrice: z.number().nullable().optional(),
 });
 
-const albums_columns = z.union([
-  z.literal("AlbumId"),
-  z.literal("Title"),
-  z.literal("ArtistId"),
-]);
+const albums_columns = z.union([z.literal('AlbumId'),z.literal('Title'),z.literal('ArtistId')]);
 
 export const albums_sort_options = z
   .object({
@@ -224,53 +343,60 @@ export const albums_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const albums_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("albums"),
+  table: z.literal('albums'),
   select: z.array(albums_columns).default([]),
   sort: albums_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("albums"),
+  table: z.literal('albums'),
   key: albums_primary_key,
   select: z.array(albums_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("albums"),
+  table: z.literal('albums'),
   data: albums_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("albums"),
+  table: z.literal('albums'),
   data: z.array(albums_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("albums"),
+  table: z.literal('albums'),
   key: albums_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("albums"),
+  table: z.literal('albums'),
   key: albums_primary_key,
   data: albums_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const artists_columns = z.union([z.literal("ArtistId"), z.literal("Name")]);
+const artists_columns = z.union([z.literal('ArtistId'),z.literal('Name')]);
 
 export const artists_sort_options = z
   .object({
@@ -278,67 +404,60 @@ export const artists_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const artists_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("artists"),
+  table: z.literal('artists'),
   select: z.array(artists_columns).default([]),
   sort: artists_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("artists"),
+  table: z.literal('artists'),
   key: artists_primary_key,
   select: z.array(artists_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("artists"),
+  table: z.literal('artists'),
   data: artists_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("artists"),
+  table: z.literal('artists'),
   data: z.array(artists_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("artists"),
+  table: z.literal('artists'),
   key: artists_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("artists"),
+  table: z.literal('artists'),
   key: artists_primary_key,
   data: artists_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const customers_columns = z.union([
-  z.literal("CustomerId"),
-  z.literal("FirstName"),
-  z.literal("LastName"),
-  z.literal("Company"),
-  z.literal("Address"),
-  z.literal("City"),
-  z.literal("State"),
-  z.literal("Country"),
-  z.literal("PostalCode"),
-  z.literal("Phone"),
-  z.literal("Fax"),
-  z.literal("Email"),
-  z.literal("SupportRepId"),
-]);
+const customers_columns = z.union([z.literal('CustomerId'),z.literal('FirstName'),z.literal('LastName'),z.literal('Company'),z.literal('Address'),z.literal('City'),z.literal('State'),z.literal('Country'),z.literal('PostalCode'),z.literal('Phone'),z.literal('Fax'),z.literal('Email'),z.literal('SupportRepId')]);
 
 export const customers_sort_options = z
   .object({
@@ -346,69 +465,60 @@ export const customers_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const customers_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("customers"),
+  table: z.literal('customers'),
   select: z.array(customers_columns).default([]),
   sort: customers_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("customers"),
+  table: z.literal('customers'),
   key: customers_primary_key,
   select: z.array(customers_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("customers"),
+  table: z.literal('customers'),
   data: customers_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("customers"),
+  table: z.literal('customers'),
   data: z.array(customers_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("customers"),
+  table: z.literal('customers'),
   key: customers_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("customers"),
+  table: z.literal('customers'),
   key: customers_primary_key,
   data: customers_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const employees_columns = z.union([
-  z.literal("EmployeeId"),
-  z.literal("LastName"),
-  z.literal("FirstName"),
-  z.literal("Title"),
-  z.literal("ReportsTo"),
-  z.literal("BirthDate"),
-  z.literal("HireDate"),
-  z.literal("Address"),
-  z.literal("City"),
-  z.literal("State"),
-  z.literal("Country"),
-  z.literal("PostalCode"),
-  z.literal("Phone"),
-  z.literal("Fax"),
-  z.literal("Email"),
-]);
+const employees_columns = z.union([z.literal('EmployeeId'),z.literal('LastName'),z.literal('FirstName'),z.literal('Title'),z.literal('ReportsTo'),z.literal('BirthDate'),z.literal('HireDate'),z.literal('Address'),z.literal('City'),z.literal('State'),z.literal('Country'),z.literal('PostalCode'),z.literal('Phone'),z.literal('Fax'),z.literal('Email')]);
 
 export const employees_sort_options = z
   .object({
@@ -416,53 +526,60 @@ export const employees_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const employees_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("employees"),
+  table: z.literal('employees'),
   select: z.array(employees_columns).default([]),
   sort: employees_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("employees"),
+  table: z.literal('employees'),
   key: employees_primary_key,
   select: z.array(employees_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("employees"),
+  table: z.literal('employees'),
   data: employees_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("employees"),
+  table: z.literal('employees'),
   data: z.array(employees_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("employees"),
+  table: z.literal('employees'),
   key: employees_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("employees"),
+  table: z.literal('employees'),
   key: employees_primary_key,
   data: employees_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const genres_columns = z.union([z.literal("GenreId"), z.literal("Name")]);
+const genres_columns = z.union([z.literal('GenreId'),z.literal('Name')]);
 
 export const genres_sort_options = z
   .object({
@@ -470,63 +587,60 @@ export const genres_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const genres_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("genres"),
+  table: z.literal('genres'),
   select: z.array(genres_columns).default([]),
   sort: genres_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("genres"),
+  table: z.literal('genres'),
   key: genres_primary_key,
   select: z.array(genres_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("genres"),
+  table: z.literal('genres'),
   data: genres_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("genres"),
+  table: z.literal('genres'),
   data: z.array(genres_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("genres"),
+  table: z.literal('genres'),
   key: genres_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("genres"),
+  table: z.literal('genres'),
   key: genres_primary_key,
   data: genres_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const invoices_columns = z.union([
-  z.literal("InvoiceId"),
-  z.literal("CustomerId"),
-  z.literal("InvoiceDate"),
-  z.literal("BillingAddress"),
-  z.literal("BillingCity"),
-  z.literal("BillingState"),
-  z.literal("BillingCountry"),
-  z.literal("BillingPostalCode"),
-  z.literal("Total"),
-]);
+const invoices_columns = z.union([z.literal('InvoiceId'),z.literal('CustomerId'),z.literal('InvoiceDate'),z.literal('BillingAddress'),z.literal('BillingCity'),z.literal('BillingState'),z.literal('BillingCountry'),z.literal('BillingPostalCode'),z.literal('Total')]);
 
 export const invoices_sort_options = z
   .object({
@@ -534,59 +648,60 @@ export const invoices_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const invoices_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("invoices"),
+  table: z.literal('invoices'),
   select: z.array(invoices_columns).default([]),
   sort: invoices_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("invoices"),
+  table: z.literal('invoices'),
   key: invoices_primary_key,
   select: z.array(invoices_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("invoices"),
+  table: z.literal('invoices'),
   data: invoices_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("invoices"),
+  table: z.literal('invoices'),
   data: z.array(invoices_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("invoices"),
+  table: z.literal('invoices'),
   key: invoices_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("invoices"),
+  table: z.literal('invoices'),
   key: invoices_primary_key,
   data: invoices_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const invoice_items_columns = z.union([
-  z.literal("InvoiceLineId"),
-  z.literal("InvoiceId"),
-  z.literal("TrackId"),
-  z.literal("UnitPrice"),
-  z.literal("Quantity"),
-]);
+const invoice_items_columns = z.union([z.literal('InvoiceLineId'),z.literal('InvoiceId'),z.literal('TrackId'),z.literal('UnitPrice'),z.literal('Quantity')]);
 
 export const invoice_items_sort_options = z
   .object({
@@ -594,56 +709,60 @@ export const invoice_items_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const invoice_items_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("invoice_items"),
+  table: z.literal('invoice_items'),
   select: z.array(invoice_items_columns).default([]),
   sort: invoice_items_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("invoice_items"),
+  table: z.literal('invoice_items'),
   key: invoice_items_primary_key,
   select: z.array(invoice_items_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("invoice_items"),
+  table: z.literal('invoice_items'),
   data: invoice_items_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("invoice_items"),
+  table: z.literal('invoice_items'),
   data: z.array(invoice_items_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("invoice_items"),
+  table: z.literal('invoice_items'),
   key: invoice_items_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("invoice_items"),
+  table: z.literal('invoice_items'),
   key: invoice_items_primary_key,
   data: invoice_items_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const media_types_columns = z.union([
-  z.literal("MediaTypeId"),
-  z.literal("Name"),
-]);
+const media_types_columns = z.union([z.literal('MediaTypeId'),z.literal('Name')]);
 
 export const media_types_sort_options = z
   .object({
@@ -651,53 +770,60 @@ export const media_types_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const media_types_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("media_types"),
+  table: z.literal('media_types'),
   select: z.array(media_types_columns).default([]),
   sort: media_types_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("media_types"),
+  table: z.literal('media_types'),
   key: media_types_primary_key,
   select: z.array(media_types_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("media_types"),
+  table: z.literal('media_types'),
   data: media_types_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("media_types"),
+  table: z.literal('media_types'),
   data: z.array(media_types_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("media_types"),
+  table: z.literal('media_types'),
   key: media_types_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("media_types"),
+  table: z.literal('media_types'),
   key: media_types_primary_key,
   data: media_types_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const playlists_columns = z.union([z.literal("PlaylistId"), z.literal("Name")]);
+const playlists_columns = z.union([z.literal('PlaylistId'),z.literal('Name')]);
 
 export const playlists_sort_options = z
   .object({
@@ -705,56 +831,60 @@ export const playlists_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const playlists_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("playlists"),
+  table: z.literal('playlists'),
   select: z.array(playlists_columns).default([]),
   sort: playlists_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("playlists"),
+  table: z.literal('playlists'),
   key: playlists_primary_key,
   select: z.array(playlists_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("playlists"),
+  table: z.literal('playlists'),
   data: playlists_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("playlists"),
+  table: z.literal('playlists'),
   data: z.array(playlists_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("playlists"),
+  table: z.literal('playlists'),
   key: playlists_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("playlists"),
+  table: z.literal('playlists'),
   key: playlists_primary_key,
   data: playlists_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const playlist_track_columns = z.union([
-  z.literal("PlaylistId"),
-  z.literal("TrackId"),
-]);
+const playlist_track_columns = z.union([z.literal('PlaylistId'),z.literal('TrackId')]);
 
 export const playlist_track_sort_options = z
   .object({
@@ -762,63 +892,60 @@ export const playlist_track_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const playlist_track_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("playlist_track"),
+  table: z.literal('playlist_track'),
   select: z.array(playlist_track_columns).default([]),
   sort: playlist_track_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("playlist_track"),
+  table: z.literal('playlist_track'),
   key: playlist_track_primary_key,
   select: z.array(playlist_track_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("playlist_track"),
+  table: z.literal('playlist_track'),
   data: playlist_track_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("playlist_track"),
+  table: z.literal('playlist_track'),
   data: z.array(playlist_track_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("playlist_track"),
+  table: z.literal('playlist_track'),
   key: playlist_track_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("playlist_track"),
+  table: z.literal('playlist_track'),
   key: playlist_track_primary_key,
   data: playlist_track_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-const tracks_columns = z.union([
-  z.literal("TrackId"),
-  z.literal("Name"),
-  z.literal("AlbumId"),
-  z.literal("MediaTypeId"),
-  z.literal("GenreId"),
-  z.literal("Composer"),
-  z.literal("Milliseconds"),
-  z.literal("Bytes"),
-  z.literal("UnitPrice"),
-]);
+const tracks_columns = z.union([z.literal('TrackId'),z.literal('Name'),z.literal('AlbumId'),z.literal('MediaTypeId'),z.literal('GenreId'),z.literal('Composer'),z.literal('Milliseconds'),z.literal('Bytes'),z.literal('UnitPrice')]);
 
 export const tracks_sort_options = z
   .object({
@@ -826,687 +953,810 @@ export const tracks_sort_options = z
     order: z.enum(["Asc", "Desc"]),
   });
 
+
 export const tracks_list_rows_request = z.object({
   type: z.literal("ListRows"),
-  table: z.literal("tracks"),
+  table: z.literal('tracks'),
   select: z.array(tracks_columns).default([]),
   sort: tracks_sort_options.optional(),
   page: Pagination.optional(),
+  chunk_size: z.number().int().positive().optional(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_get_row_request = z.object({
   type: z.literal("GetRow"),
-  table: z.literal("tracks"),
+  table: z.literal('tracks'),
   key: tracks_primary_key,
   select: z.array(tracks_columns).default([]),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_insert_row_request = z.object({
   type: z.literal("InsertRow"),
-  table: z.literal("tracks"),
+  table: z.literal('tracks'),
   data: tracks_schema,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_batch_insert_row_request = z.object({
   type: z.literal("BatchInsertRow"),
-  table: z.literal("tracks"),
+  table: z.literal('tracks'),
   data: z.array(tracks_schema),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_delete_row_request = z.object({
   type: z.literal("DeleteRow"),
-  table: z.literal("tracks"),
+  table: z.literal('tracks'),
   key: tracks_primary_key,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_update_row_request = z.object({
   type: z.literal("UpdateRow"),
-  table: z.literal("tracks"),
+  table: z.literal('tracks'),
   key: tracks_primary_key,
   data: tracks_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
-export const ListRowsRequest = z.discriminatedUnion("table", [
-  albums_list_rows_request,
-  artists_list_rows_request,
-  customers_list_rows_request,
-  employees_list_rows_request,
-  genres_list_rows_request,
-  invoices_list_rows_request,
-  invoice_items_list_rows_request,
-  media_types_list_rows_request,
-  playlists_list_rows_request,
-  playlist_track_list_rows_request,
-  tracks_list_rows_request,
-]);
-export const GetRowRequest = z.discriminatedUnion("table", [
-  albums_get_row_request,
-  artists_get_row_request,
-  customers_get_row_request,
-  employees_get_row_request,
-  genres_get_row_request,
-  invoices_get_row_request,
-  invoice_items_get_row_request,
-  media_types_get_row_request,
-  playlists_get_row_request,
-  playlist_track_get_row_request,
-  tracks_get_row_request,
-]);
-export const InsertRowRequest = z.discriminatedUnion("table", [
-  albums_insert_row_request,
-  artists_insert_row_request,
-  customers_insert_row_request,
-  employees_insert_row_request,
-  genres_insert_row_request,
-  invoices_insert_row_request,
-  invoice_items_insert_row_request,
-  media_types_insert_row_request,
-  playlists_insert_row_request,
-  playlist_track_insert_row_request,
-  tracks_insert_row_request,
-]);
-export const BatchInsertRowRequest = z.discriminatedUnion("table", [
-  albums_batch_insert_row_request,
-  artists_batch_insert_row_request,
-  customers_batch_insert_row_request,
-  employees_batch_insert_row_request,
-  genres_batch_insert_row_request,
-  invoices_batch_insert_row_request,
-  invoice_items_batch_insert_row_request,
-  media_types_batch_insert_row_request,
-  playlists_batch_insert_row_request,
-  playlist_track_batch_insert_row_request,
-  tracks_batch_insert_row_request,
-]);
-export const DeleteRowRequest = z.discriminatedUnion("table", [
-  albums_delete_row_request,
-  artists_delete_row_request,
-  customers_delete_row_request,
-  employees_delete_row_request,
-  genres_delete_row_request,
-  invoices_delete_row_request,
-  invoice_items_delete_row_request,
-  media_types_delete_row_request,
-  playlists_delete_row_request,
-  playlist_track_delete_row_request,
-  tracks_delete_row_request,
-]);
-export const UpdateRowRequest = z.discriminatedUnion("table", [
-  albums_update_row_request,
-  artists_update_row_request,
-  customers_update_row_request,
-  employees_update_row_request,
-  genres_update_row_request,
-  invoices_update_row_request,
-  invoice_items_update_row_request,
-  media_types_update_row_request,
-  playlists_update_row_request,
-  playlist_track_update_row_request,
-  tracks_update_row_request,
-]);
-export const ApiRequest = z.union([
-  ListRowsRequest,
-  GetRowRequest,
-  InsertRowRequest,
-  BatchInsertRowRequest,
-  DeleteRowRequest,
-  UpdateRowRequest,
-]);
+export const ListRowsRequest = z.discriminatedUnion('table', [albums_list_rows_request,artists_list_rows_request,customers_list_rows_request,employees_list_rows_request,genres_list_rows_request,invoices_list_rows_request,invoice_items_list_rows_request,media_types_list_rows_request,playlists_list_rows_request,playlist_track_list_rows_request,tracks_list_rows_request]);
+export const GetRowRequest = z.discriminatedUnion('table', [albums_get_row_request,artists_get_row_request,customers_get_row_request,employees_get_row_request,genres_get_row_request,invoices_get_row_request,invoice_items_get_row_request,media_types_get_row_request,playlists_get_row_request,playlist_track_get_row_request,tracks_get_row_request]);
+export const InsertRowRequest = z.discriminatedUnion('table', [albums_insert_row_request,artists_insert_row_request,customers_insert_row_request,employees_insert_row_request,genres_insert_row_request,invoices_insert_row_request,invoice_items_insert_row_request,media_types_insert_row_request,playlists_insert_row_request,playlist_track_insert_row_request,tracks_insert_row_request]);
+export const BatchInsertRowRequest = z.discriminatedUnion('table', [albums_batch_insert_row_request,artists_batch_insert_row_request,customers_batch_insert_row_request,employees_batch_insert_row_request,genres_batch_insert_row_request,invoices_batch_insert_row_request,invoice_items_batch_insert_row_request,media_types_batch_insert_row_request,playlists_batch_insert_row_request,playlist_track_batch_insert_row_request,tracks_batch_insert_row_request]);
+export const DeleteRowRequest = z.discriminatedUnion('table', [albums_delete_row_request,artists_delete_row_request,customers_delete_row_request,employees_delete_row_request,genres_delete_row_request,invoices_delete_row_request,invoice_items_delete_row_request,media_types_delete_row_request,playlists_delete_row_request,playlist_track_delete_row_request,tracks_delete_row_request]);
+export const UpdateRowRequest = z.discriminatedUnion('table', [albums_update_row_request,artists_update_row_request,customers_update_row_request,employees_update_row_request,genres_update_row_request,invoices_update_row_request,invoice_items_update_row_request,media_types_update_row_request,playlists_update_row_request,playlist_track_update_row_request,tracks_update_row_request]);
+
+export const tracks_search_request = z.object({
+  type: z.literal("Search"),
+  table: z.literal('tracks'),
+  query: z.string(),
+  select: z.array(tracks_columns).default([]),
+  highlight: Highlight.optional(),
+  snippet: Snippet.optional(),
+  page: Pagination.optional(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const SearchRequest = z.discriminatedUnion('table', [tracks_search_request]);
+export const ApiRequest = z.union([ListRowsRequest, GetRowRequest, InsertRowRequest, BatchInsertRowRequest, DeleteRowRequest, UpdateRowRequest, DescribeSchemaRequest, DescribeTableRequest, CreateTableRequest, AddColumnRequest, CreateIndexRequest, DropTableRequest, SearchRequest]);
 
 export const albums_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("albums"),
+  type: z.literal('ListRows'),
+  table: z.literal('albums'),
+  rows: z.array(albums_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const albums_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('albums'),
   rows: z.array(albums_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const albums_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('albums'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const albums_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("albums"),
+  type: z.literal('GetRow'),
+  table: z.literal('albums'),
   row: albums_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("albums"),
+  type: z.literal('InsertRow'),
+  table: z.literal('albums'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("albums"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('albums'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("albums"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('albums'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const albums_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("albums"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('albums'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("artists"),
+  type: z.literal('ListRows'),
+  table: z.literal('artists'),
   rows: z.array(artists_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const artists_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('artists'),
+  rows: z.array(artists_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const artists_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('artists'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const artists_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("artists"),
+  type: z.literal('GetRow'),
+  table: z.literal('artists'),
   row: artists_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("artists"),
+  type: z.literal('InsertRow'),
+  table: z.literal('artists'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("artists"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('artists'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("artists"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('artists'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const artists_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("artists"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('artists'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("customers"),
+  type: z.literal('ListRows'),
+  table: z.literal('customers'),
+  rows: z.array(customers_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const customers_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('customers'),
   rows: z.array(customers_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const customers_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('customers'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const customers_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("customers"),
+  type: z.literal('GetRow'),
+  table: z.literal('customers'),
   row: customers_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("customers"),
+  type: z.literal('InsertRow'),
+  table: z.literal('customers'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("customers"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('customers'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("customers"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('customers'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const customers_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("customers"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('customers'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("employees"),
+  type: z.literal('ListRows'),
+  table: z.literal('employees'),
+  rows: z.array(employees_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const employees_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('employees'),
   rows: z.array(employees_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const employees_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('employees'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const employees_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("employees"),
+  type: z.literal('GetRow'),
+  table: z.literal('employees'),
   row: employees_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("employees"),
+  type: z.literal('InsertRow'),
+  table: z.literal('employees'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("employees"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('employees'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("employees"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('employees'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const employees_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("employees"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('employees'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("genres"),
+  type: z.literal('ListRows'),
+  table: z.literal('genres'),
   rows: z.array(genres_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
-export const genres_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("genres"),
-  row: genres_schema_optional,
+
+export const genres_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('genres'),
+  rows: z.array(genres_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
-export const genres_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("genres"),
-  inserted_rows: z.number(),
+
+export const genres_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('genres'),
+  total_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const genres_get_row_response = z.object({
+  type: z.literal('GetRow'),
+  table: z.literal('genres'),
+  row: genres_schema_optional,
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const genres_insert_row_response = z.object({
+  type: z.literal('InsertRow'),
+  table: z.literal('genres'),
+  inserted_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const genres_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("genres"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('genres'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("genres"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('genres'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const genres_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("genres"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('genres'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("invoices"),
+  type: z.literal('ListRows'),
+  table: z.literal('invoices'),
+  rows: z.array(invoices_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const invoices_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('invoices'),
   rows: z.array(invoices_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const invoices_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('invoices'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const invoices_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("invoices"),
+  type: z.literal('GetRow'),
+  table: z.literal('invoices'),
   row: invoices_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("invoices"),
+  type: z.literal('InsertRow'),
+  table: z.literal('invoices'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("invoices"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('invoices'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("invoices"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('invoices'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoices_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("invoices"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('invoices'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("invoice_items"),
+  type: z.literal('ListRows'),
+  table: z.literal('invoice_items'),
+  rows: z.array(invoice_items_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const invoice_items_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('invoice_items'),
   rows: z.array(invoice_items_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const invoice_items_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('invoice_items'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const invoice_items_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("invoice_items"),
+  type: z.literal('GetRow'),
+  table: z.literal('invoice_items'),
   row: invoice_items_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("invoice_items"),
+  type: z.literal('InsertRow'),
+  table: z.literal('invoice_items'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("invoice_items"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('invoice_items'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("invoice_items"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('invoice_items'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const invoice_items_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("invoice_items"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('invoice_items'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("media_types"),
+  type: z.literal('ListRows'),
+  table: z.literal('media_types'),
+  rows: z.array(media_types_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const media_types_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('media_types'),
   rows: z.array(media_types_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const media_types_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('media_types'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const media_types_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("media_types"),
+  type: z.literal('GetRow'),
+  table: z.literal('media_types'),
   row: media_types_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("media_types"),
+  type: z.literal('InsertRow'),
+  table: z.literal('media_types'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("media_types"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('media_types'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("media_types"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('media_types'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const media_types_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("media_types"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('media_types'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("playlists"),
+  type: z.literal('ListRows'),
+  table: z.literal('playlists'),
   rows: z.array(playlists_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const playlists_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('playlists'),
+  rows: z.array(playlists_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const playlists_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('playlists'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const playlists_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("playlists"),
+  type: z.literal('GetRow'),
+  table: z.literal('playlists'),
   row: playlists_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("playlists"),
+  type: z.literal('InsertRow'),
+  table: z.literal('playlists'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("playlists"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('playlists'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("playlists"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('playlists'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlists_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("playlists"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('playlists'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("playlist_track"),
+  type: z.literal('ListRows'),
+  table: z.literal('playlist_track'),
   rows: z.array(playlist_track_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const playlist_track_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('playlist_track'),
+  rows: z.array(playlist_track_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const playlist_track_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('playlist_track'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const playlist_track_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("playlist_track"),
+  type: z.literal('GetRow'),
+  table: z.literal('playlist_track'),
   row: playlist_track_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("playlist_track"),
+  type: z.literal('InsertRow'),
+  table: z.literal('playlist_track'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("playlist_track"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('playlist_track'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("playlist_track"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('playlist_track'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const playlist_track_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("playlist_track"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('playlist_track'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_list_rows_response = z.object({
-  type: z.literal("ListRows"),
-  table: z.literal("tracks"),
+  type: z.literal('ListRows'),
+  table: z.literal('tracks'),
   rows: z.array(tracks_schema_optional),
   request_id: z.string().default(() => nanoid()),
 });
 
+
+export const tracks_list_rows_chunk_response = z.object({
+  type: z.literal('ListRowsChunk'),
+  table: z.literal('tracks'),
+  rows: z.array(tracks_schema_optional),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
+export const tracks_list_rows_end_response = z.object({
+  type: z.literal('ListRowsEnd'),
+  table: z.literal('tracks'),
+  total_rows: z.number(),
+  request_id: z.string().default(() => nanoid()),
+});
+
+
 export const tracks_get_row_response = z.object({
-  type: z.literal("GetRow"),
-  table: z.literal("tracks"),
+  type: z.literal('GetRow'),
+  table: z.literal('tracks'),
   row: tracks_schema_optional,
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_insert_row_response = z.object({
-  type: z.literal("InsertRow"),
-  table: z.literal("tracks"),
+  type: z.literal('InsertRow'),
+  table: z.literal('tracks'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_batch_insert_row_response = z.object({
-  type: z.literal("BatchInsertRow"),
-  table: z.literal("tracks"),
+  type: z.literal('BatchInsertRow'),
+  table: z.literal('tracks'),
   inserted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_delete_row_response = z.object({
-  type: z.literal("DeleteRow"),
-  table: z.literal("tracks"),
+  type: z.literal('DeleteRow'),
+  table: z.literal('tracks'),
   deleted_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
+
 export const tracks_update_row_response = z.object({
-  type: z.literal("UpdateRow"),
-  table: z.literal("tracks"),
+  type: z.literal('UpdateRow'),
+  table: z.literal('tracks'),
   updated_rows: z.number(),
   request_id: z.string().default(() => nanoid()),
 });
 
-export const ListRowsResponse = z.discriminatedUnion("table", [
-  albums_list_rows_response,
-  artists_list_rows_response,
-  customers_list_rows_response,
-  employees_list_rows_response,
-  genres_list_rows_response,
-  invoices_list_rows_response,
-  invoice_items_list_rows_response,
-  media_types_list_rows_response,
-  playlists_list_rows_response,
-  playlist_track_list_rows_response,
-  tracks_list_rows_response,
-]);
-export const GetRowResponse = z.discriminatedUnion("table", [
-  albums_get_row_response,
-  artists_get_row_response,
-  customers_get_row_response,
-  employees_get_row_response,
-  genres_get_row_response,
-  invoices_get_row_response,
-  invoice_items_get_row_response,
-  media_types_get_row_response,
-  playlists_get_row_response,
-  playlist_track_get_row_response,
-  tracks_get_row_response,
-]);
-export const InsertRowResponse = z.discriminatedUnion("table", [
-  albums_insert_row_response,
-  artists_insert_row_response,
-  customers_insert_row_response,
-  employees_insert_row_response,
-  genres_insert_row_response,
-  invoices_insert_row_response,
-  invoice_items_insert_row_response,
-  media_types_insert_row_response,
-  playlists_insert_row_response,
-  playlist_track_insert_row_response,
-  tracks_insert_row_response,
-]);
-export const BatchInsertRowResponse = z.discriminatedUnion("table", [
-  albums_batch_insert_row_response,
-  artists_batch_insert_row_response,
-  customers_batch_insert_row_response,
-  employees_batch_insert_row_response,
-  genres_batch_insert_row_response,
-  invoices_batch_insert_row_response,
-  invoice_items_batch_insert_row_response,
-  media_types_batch_insert_row_response,
-  playlists_batch_insert_row_response,
-  playlist_track_batch_insert_row_response,
-  tracks_batch_insert_row_response,
-]);
-export const DeleteRowResponse = z.discriminatedUnion("table", [
-  albums_delete_row_response,
-  artists_delete_row_response,
-  customers_delete_row_response,
-  employees_delete_row_response,
-  genres_delete_row_response,
-  invoices_delete_row_response,
-  invoice_items_delete_row_response,
-  media_types_delete_row_response,
-  playlists_delete_row_response,
-  playlist_track_delete_row_response,
-  tracks_delete_row_response,
-]);
-export const UpdateRowResponse = z.discriminatedUnion("table", [
-  albums_update_row_response,
-  artists_update_row_response,
-  customers_update_row_response,
-  employees_update_row_response,
-  genres_update_row_response,
-  invoices_update_row_response,
-  invoice_items_update_row_response,
-  media_types_update_row_response,
-  playlists_update_row_response,
-  playlist_track_update_row_response,
-  tracks_update_row_response,
-]);
-export const ApiResponse = z.union([
-  ListRowsResponse,
-  GetRowResponse,
-  InsertRowResponse,
-  BatchInsertRowResponse,
-  DeleteRowResponse,
-  UpdateRowResponse,
-]);
+export const ListRowsResponse = z.discriminatedUnion('table', [albums_list_rows_response,artists_list_rows_response,customers_list_rows_response,employees_list_rows_response,genres_list_rows_response,invoices_list_rows_response,invoice_items_list_rows_response,media_types_list_rows_response,playlists_list_rows_response,playlist_track_list_rows_response,tracks_list_rows_response]);
+export const ListRowsChunkResponse = z.discriminatedUnion('table', [albums_list_rows_chunk_response,artists_list_rows_chunk_response,customers_list_rows_chunk_response,employees_list_rows_chunk_response,genres_list_rows_chunk_response,invoices_list_rows_chunk_response,invoice_items_list_rows_chunk_response,media_types_list_rows_chunk_response,playlists_list_rows_chunk_response,playlist_track_list_rows_chunk_response,tracks_list_rows_chunk_response]);
+export const ListRowsEndResponse = z.discriminatedUnion('table', [albums_list_rows_end_response,artists_list_rows_end_response,customers_list_rows_end_response,employees_list_rows_end_response,genres_list_rows_end_response,invoices_list_rows_end_response,invoice_items_list_rows_end_response,media_types_list_rows_end_response,playlists_list_rows_end_response,playlist_track_list_rows_end_response,tracks_list_rows_end_response]);
+export const GetRowResponse = z.discriminatedUnion('table', [albums_get_row_response,artists_get_row_response,customers_get_row_response,employees_get_row_response,genres_get_row_response,invoices_get_row_response,invoice_items_get_row_response,media_types_get_row_response,playlists_get_row_response,playlist_track_get_row_response,tracks_get_row_response]);
+export const InsertRowResponse = z.discriminatedUnion('table', [albums_insert_row_response,artists_insert_row_response,customers_insert_row_response,employees_insert_row_response,genres_insert_row_response,invoices_insert_row_response,invoice_items_insert_row_response,media_types_insert_row_response,playlists_insert_row_response,playlist_track_insert_row_response,tracks_insert_row_response]);
+export const BatchInsertRowResponse = z.discriminatedUnion('table', [albums_batch_insert_row_response,artists_batch_insert_row_response,customers_batch_insert_row_response,employees_batch_insert_row_response,genres_batch_insert_row_response,invoices_batch_insert_row_response,invoice_items_batch_insert_row_response,media_types_batch_insert_row_response,playlists_batch_insert_row_response,playlist_track_batch_insert_row_response,tracks_batch_insert_row_response]);
+export const DeleteRowResponse = z.discriminatedUnion('table', [albums_delete_row_response,artists_delete_row_response,customers_delete_row_response,employees_delete_row_response,genres_delete_row_response,invoices_delete_row_response,invoice_items_delete_row_response,media_types_delete_row_response,playlists_delete_row_response,playlist_track_delete_row_response,tracks_delete_row_response]);
+export const UpdateRowResponse = z.discriminatedUnion('table', [albums_update_row_response,artists_update_row_response,customers_update_row_response,employees_update_row_response,genres_update_row_response,invoices_update_row_response,invoice_items_update_row_response,media_types_update_row_response,playlists_update_row_response,playlist_track_update_row_response,tracks_update_row_response]);
+
+export const tracks_search_markup = z.object({  Name: z.string().nullable(),
+  Composer: z.string().nullable(),
+});
+
+export const tracks_search_response = z.object({
+  type: z.literal('Search'),
+  table: z.literal('tracks'),
+  hits: z.array(z.object({
+    row: tracks_schema_optional,
+    rank: z.number(),
+    highlights: tracks_search_markup.optional(),
+    snippets: tracks_search_markup.optional(),
+  })),
+  request_id: z.string().default(() => nanoid()),
+});
+
+export const SearchResponse = z.discriminatedUnion('table', [tracks_search_response]);
+export const ApiResponse = z.union([ListRowsResponse, ListRowsChunkResponse, ListRowsEndResponse, GetRowResponse, InsertRowResponse, BatchInsertRowResponse, DeleteRowResponse, UpdateRowResponse, DescribeSchemaResponse, DescribeTableResponse, CreateTableResponse, AddColumnResponse, CreateIndexResponse, DropTableResponse, SearchResponse]);
 
 export const BadRequest = z.object({
   message: z.string(),
@@ -1524,91 +1774,1609 @@ export const SortColumnNotFound = z.object({
   column: z.string(),
 });
 
+export const InvalidColumn = z.object({
+  column: z.string(),
+  row: z.number().optional(),
+  message: z.string(),
+});
+
 export const ErrorResponse = z.discriminatedUnion("type", [
   z.object({ type: z.literal("BadRequest"), ...BadRequest.shape }),
   z.object({ type: z.literal("NonTextMessage") }),
   z.object({ type: z.literal("TableNotFound"), ...TableNotFound.shape }),
   z.object({ type: z.literal("ColumnsNotFound"), ...ColumnsNotFound.shape }),
-  z.object({
-    type: z.literal("SortColumnNotFound"),
-    ...SortColumnNotFound.shape,
-  }),
+  z.object({ type: z.literal("SortColumnNotFound"), ...SortColumnNotFound.shape }),
   z.object({ type: z.literal("PageNumberCanNotBeZero") }),
   z.object({ type: z.literal("RowNotFound") }),
   z.object({ type: z.literal("DatabaseError") }),
+  z.object({ type: z.literal("ValidationFailed"), errors: z.array(InvalidColumn) }),
+  z.object({
+    type: z.literal("InvalidValue"),
+    column: z.string().optional(),
+    message: z.string(),
+  }),
+  z.object({ type: z.literal("TableNotSearchable"), table: z.string() }),
+  z.object({ type: z.literal("InvalidSearch"), message: z.string() }),
+  z.object({ type: z.literal("NotABlob"), column: z.string() }),
+  z.object({ type: z.literal("BlobOutOfBounds"), size: z.number() }),
+  z.object({ type: z.literal("Forbidden") }),
+  z.object({ type: z.literal("InvalidSchemaChange"), message: z.string() }),
+  z.object({ type: z.literal("SchemaMismatch"), server: z.string(), client: z.string() }),
+  z.object({ type: z.literal("UnsupportedProtocolVersion"), supported: z.array(z.number()) }),
+  z.object({ type: z.literal("DatabaseNotFound"), database: z.string() }),
 ]);
+export type Request = z.infer<typeof ApiRequest>;
+export type ApiError = z.infer<typeof ErrorResponse>;
+export type Response =
+  | { data: z.infer<typeof ApiResponse> }
+  | { error: ApiError };
+
+const newRequestId = () => nanoid();
+
+function narrow<T extends z.ZodTypeAny>(
+  resp: Response,
+  schema: T,
+): Result<z.infer<T>> {
+  return "data" in resp ? { data: schema.parse(resp.data) } : resp;
+}
+
+function parseMessage(message: unknown): Response {
+  const error = ErrorResponse.safeParse(message);
+  if (error.data) {
+    return { error: error.data };
+  }
+
+  return { data: ApiResponse.parse(message) };
+}
+
+
+/** Identifies the schema this client was generated for. */
+export const SCHEMA_FINGERPRINT = "23ede50b07d4828efb46de1d7fce3161db5cf590fd3c1a3f0389b891d8672126";
+/** The database this client connects to, the server's default when `null`. */
+export const DATABASE: string | null = null;
 
 export type MakeFetchOptions = {
   url: string;
   connectionCount: number;
+  /** Default time in milliseconds to wait for a response, no limit when unset. */
+  timeout?: number;
+  /** Delay in milliseconds before reconnecting a closed socket, doubled after every failed attempt. */
+  reconnectDelay?: number;
+  /** Upper bound for the reconnection delay. */
+  maxReconnectDelay?: number;
+  /** Called when an admin changed the schema, once per connection in the pool. */
+  onSchemaChange?: (change: SchemaChanged) => void;
+  /**
+   * Encode messages with MessagePack instead of JSON, so blob columns arrive as `Uint8Array`s.
+   * Needs a server with the `message_pack` capability, older ones refuse the connection.
+   */
+  messagePack?: boolean;
 };
 
-export type Request = z.infer<typeof ApiRequest>;
-export type Response =
-  | { data: z.infer<typeof ApiResponse> }
-  | { error: z.infer<typeof ErrorResponse> };
+export type FetchOptions = {
+  /** Time in milliseconds to wait for a response, overrides the pool default. */
+  timeout?: number;
+  signal?: AbortSignal;
+  /**
+   * Send the request in a binary frame, so blob columns in the response arrive as `Uint8Array`s.
+   * Requests containing `Uint8Array`s are always sent in binary frames.
+   */
+  binary?: boolean;
+};
+
+export type Result<T> = { data: T } | { error: ApiError };
+
+export class RequestTimeoutError extends Error {
+  constructor(public request_id: string, timeout: number) {
+    super(`request ${request_id} timed out after ${timeout}ms`);
+    this.name = "RequestTimeoutError";
+  }
+}
+
+export class ConnectionClosedError extends Error {
+  constructor(public request_id: string) {
+    super(`connection closed before request ${request_id} was answered`);
+    this.name = "ConnectionClosedError";
+  }
+}
+
+export class SchemaMismatchError extends Error {
+  constructor(public server: string, public client: string) {
+    super(`the client was generated for schema ${client} but the server has ${server}, regenerate it`);
+    this.name = "SchemaMismatchError";
+  }
+}
+
+/** Errors are wrapped in an `Error` message from version 2 on. */
+export const PROTOCOL_VERSION = 2;
+
+/** The WebSocket subprotocol asking the server for MessagePack messages. */
+export const MESSAGE_PACK_PROTOCOL = "jabroni.msgpack";
+
+/** The server's answer to the `Hello` every connection starts with. */
+export type HelloResponse = {
+  type: "Hello";
+  protocol_version: number;
+  capabilities: string[];
+  request_id: string;
+};
+
+type PendingRequest = {
+  resolve: (response: Response) => void;
+  reject: (error: unknown) => void;
+};
+
+type Connection = {
+  socket: WebSocket;
+  requests: Map<string, PendingRequest>;
+  retries: number;
+  /** Set until the server answered the `Hello`, the connection isn't used before that. */
+  hello?: { request_id: string; done: () => void };
+  capabilities: string[];
+  messagePack: boolean;
+};
 
 export async function makeWebSocketFetch(
-  { url, connectionCount }: MakeFetchOptions,
+  {
+    url,
+    connectionCount,
+    timeout: defaultTimeout,
+    reconnectDelay = 250,
+    maxReconnectDelay = 10_000,
+    onSchemaChange,
+    messagePack = false,
+  }: MakeFetchOptions,
 ) {
-  let sockets: WebSocket[] = [];
+  if (connectionCount < 1) {
+    throw new Error("WebSocket pool needs at least one connection.");
+  }
+
+  const connections: Connection[] = [];
   let connectionIndex = 0;
   const openPromises: (Promise<void>)[] = [];
+  // Requests waiting for any socket in the pool to open.
+  const queued = new Set<() => void>();
+  // Set once the server rejected the client, nothing can be sent after that.
+  let schemaMismatch: SchemaMismatchError | undefined;
+
+  function connect(connection: Connection, i: number, onOpen: () => void) {
+    const protocols = messagePack ? [MESSAGE_PACK_PROTOCOL] : [];
+    const socket = new WebSocket(connectionUrl(url), protocols);
+    connection.socket = socket;
+
+    socket.onopen = () => {
+      connection.messagePack = socket.protocol === MESSAGE_PACK_PROTOCOL;
+      const request_id = newRequestId();
+      connection.hello = {
+        request_id,
+        done: () => {
+          connection.hello = undefined;
+          connection.retries = 0;
+          onOpen();
+          for (const send of [...queued]) {
+            send();
+          }
+        },
+      };
+      socket.send(encode(connection, { type: "Hello", protocol_version: PROTOCOL_VERSION, request_id }));
+    };
+    socket.binaryType = "arraybuffer";
+    socket.onmessage = (event: MessageEvent<string | ArrayBuffer>) =>
+      handleMessage(connection, event);
+    // Some runtimes only report a failed connection attempt through `onerror`.
+    let closed = false;
+    const reconnect = () => {
+      if (closed) {
+        return;
+      }
+      closed = true;
 
-  sockets = new Array(connectionCount).fill(0).map((_, i) => {
-    const socket = new WebSocket(url);
-
-    openPromises.push(
-      new Promise((res) => {
-        socket.onopen = () => {
-          res();
-        };
-      }),
-    );
+      for (const [request_id, request] of connection.requests) {
+        request.reject(new ConnectionClosedError(request_id));
+      }
+      if (schemaMismatch) {
+        return;
+      }
 
-    socket.onclose = () => console.log(i, "WebSocket disconnected");
-    socket.onerror = (error) => console.error(i, "WebSocket error:", error);
+      const delay = Math.min(
+        reconnectDelay * 2 ** connection.retries,
+        maxReconnectDelay,
+      );
+      connection.retries += 1;
+      setTimeout(() => connect(connection, i, onOpen), delay);
+    };
+
+    socket.onclose = () => {
+      console.log(i, "WebSocket disconnected");
+      reconnect();
+    };
+    socket.onerror = (error) => {
+      console.error(i, "WebSocket error:", error);
+      reconnect();
+    };
+  }
 
-    return socket;
-  });
+  for (let i = 0; i < connectionCount; i++) {
+    const connection = { requests: new Map(), retries: 0, capabilities: [] } as Connection;
+    connections.push(connection);
+    openPromises.push(new Promise((res) => connect(connection, i, res)));
+  }
 
   await Promise.all(openPromises);
 
-  function getWebSocket(): WebSocket {
-    if (sockets.length === 0) {
-      throw new Error(
-        "WebSocket pool is not initialized.",
-      );
+  function handleMessage(
+    connection: Connection,
+    event: MessageEvent<string | ArrayBuffer>,
+  ) {
+    const { requests } = connection;
+    const message: unknown = typeof event.data === "string"
+      ? JSON.parse(event.data)
+      : connection.messagePack
+      ? decodeMessagePack(event.data)
+      : decodeFrame(event.data);
+    const type = typeof message === "object" && message !== null && "type" in message
+      ? message.type
+      : undefined;
+    // Broadcast to every connection, not an answer to any request.
+    if (type === "SchemaChanged") {
+      onSchemaChange?.(message as SchemaChanged);
+      return;
+    }
+    // Sent right before the server closes the connection.
+    if (type === "SchemaMismatch") {
+      const { server, client } = message as { server: string; client: string };
+      schemaMismatch = new SchemaMismatchError(server, client);
+      for (const connection of connections) {
+        for (const request of connection.requests.values()) {
+          request.reject(schemaMismatch);
+        }
+        connection.hello?.done();
+      }
+      for (const send of [...queued]) {
+        send();
+      }
+      return;
+    }
+
+    const request_id = typeof message === "object" && message !== null &&
+        "request_id" in message && typeof message.request_id === "string"
+      ? message.request_id
+      // Messages without an id answer the oldest request, the server replies in order.
+      : requests.keys().next().value;
+
+    if (connection.hello && request_id === connection.hello.request_id) {
+      // Servers predating the handshake answer it with an error, and speak version 1.
+      connection.capabilities = type === "Hello" ? (message as HelloResponse).capabilities : [];
+      connection.hello.done();
+      return;
+    }
+
+    const request = request_id === undefined
+      ? undefined
+      : requests.get(request_id);
+    if (!request) {
+      console.warn("Received a response for an unknown request:", message);
+      return;
     }
 
-    const socket = sockets[connectionIndex];
-    connectionIndex = (connectionIndex + 1) % sockets.length;
-    return socket;
+    try {
+      request.resolve(parseMessage(type === "Error" ? (message as { error: unknown }).error : message));
+    } catch (error) {
+      request.reject(error);
+    }
   }
 
-  function $fetch(request: Request): Promise<Response> {
-    const socket = getWebSocket();
-    const request_id = request.request_id;
+  // Round robin over the open sockets, skipping the ones that are reconnecting.
+  function getConnection(): Connection | undefined {
+    for (let i = 0; i < connections.length; i++) {
+      const connection = connections[connectionIndex];
+      connectionIndex = (connectionIndex + 1) % connections.length;
 
-    const promise = new Promise<Response>((resolve) => {
-      function handleMessage(event: MessageEvent<string>) {
-        const error = ErrorResponse.safeParse(JSON.parse(event.data));
-        if (error.data) {
-          socket.removeEventListener("message", handleMessage);
-          return resolve({ error: error.data });
-        }
+      if (!connection.hello && connection.socket.readyState === WebSocket.OPEN) {
+        return connection;
+      }
+    }
+  }
 
-        const resp = ApiResponse.parse(JSON.parse(event.data));
-        socket.removeEventListener("message", handleMessage);
-        return resolve({ data: resp });
+  // Sends the request, or queues it until a socket opens, and hands its responses to
+  // `onResponse` up to the one `isLast` accepts. The timeout restarts after every response.
+  function dispatch(
+    request: Request,
+    { timeout = defaultTimeout, signal, binary }: FetchOptions,
+    isLast: (response: Response) => boolean,
+    onResponse: (response: Response) => void,
+    onError: (error: unknown) => void,
+  ) {
+    const request_id = request.request_id ?? newRequestId();
+
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    let connection: Connection | undefined;
+    const onAbort = () => settle(() => onError(signal?.reason), true);
+
+    // Requests given up on before their last response stay registered until it
+    // arrives, so their remaining responses are dropped without a warning.
+    function settle(done: () => void, abandoned = false) {
+      clearTimeout(timer);
+      signal?.removeEventListener("abort", onAbort);
+      queued.delete(send);
+
+      const requests = connection?.requests;
+      if (abandoned && requests?.has(request_id)) {
+        requests.set(request_id, {
+          resolve: (response) => {
+            if (isLast(response)) requests.delete(request_id);
+          },
+          reject: () => requests.delete(request_id),
+        });
+      } else {
+        requests?.delete(request_id);
       }
 
-      socket.addEventListener("message", handleMessage);
+      done();
+    }
+
+    function startTimer() {
+      clearTimeout(timer);
+      if (timeout !== undefined) {
+        timer = setTimeout(
+          () => settle(() => onError(new RequestTimeoutError(request_id, timeout)), true),
+          timeout,
+        );
+      }
+    }
+
+    function send() {
+      if (schemaMismatch) {
+        settle(() => onError(schemaMismatch));
+        return;
+      }
+
+      connection = getConnection();
+      if (!connection) {
+        queued.add(send);
+        return;
+      }
+
+      queued.delete(send);
+      connection.requests.set(request_id, {
+        resolve: (response) => {
+          if (isLast(response)) {
+            settle(() => onResponse(response));
+          } else {
+            onResponse(response);
+            startTimer();
+          }
+        },
+        reject: (error) => settle(() => onError(error)),
+      });
+      connection.socket.send(encode(connection, { ...request, request_id }, binary));
+    }
+
+    if (signal?.aborted) {
+      onError(signal.reason);
+      return;
+    }
+
+    startTimer();
+    signal?.addEventListener("abort", onAbort, { once: true });
+    send();
+  }
+
+  function $fetch(
+    request: Request,
+    options: FetchOptions = {},
+  ): Promise<Response> {
+    return new Promise<Response>((resolve, reject) => {
+      dispatch(request, options, () => true, resolve, reject);
     });
+  }
+
+  // Yields the `ListRowsChunk` responses of a streamed `ListRows` request, ending after
+  // the `ListRowsEnd` or an error. Chunks arriving after the loop is left are dropped.
+  async function* stream(
+    request: ListRows,
+    options: FetchOptions = {},
+  ): AsyncGenerator<Response> {
+    const responses: Response[] = [];
+    let failure: { error: unknown } | undefined;
+    let stopped = false;
+    let wake = () => {};
+
+    dispatch(
+      { ...request, chunk_size: request.chunk_size ?? DEFAULT_CHUNK_SIZE },
+      options,
+      (response) => !isChunk(response),
+      (response) => {
+        if (!stopped) {
+          responses.push(response);
+          wake();
+        }
+      },
+      (error) => {
+        failure = { error };
+        wake();
+      },
+    );
+
+    try {
+      while (true) {
+        const response = responses.shift();
+        if (response) {
+          if ("data" in response && response.data.type === "ListRowsEnd") {
+            return;
+          }
+          yield response;
+          if (!isChunk(response)) {
+            return;
+          }
+        } else if (failure) {
+          throw failure.error;
+        } else {
+          await new Promise<void>((resolve) => (wake = resolve));
+        }
+      }
+    } finally {
+      stopped = true;
+    }
+  }
+
+  /** What the server supports, as it answered the `Hello`. */
+  function capabilities(): string[] {
+    return connections[0].capabilities;
+  }
+
+  return Object.assign($fetch, { stream, capabilities });
+}
 
-    socket.send(JSON.stringify({ ...request, request_id }));
-    return promise;
+type ListRows = Extract<Request, { type: "ListRows" }>;
+
+function encode(connection: Connection, message: unknown, binary?: boolean) {
+  return connection.messagePack ? encodeMessagePack(message) : encodeFrame(message, binary);
+}
+
+function connectionUrl(url: string): string {
+  const connectionUrl = new URL(url);
+  connectionUrl.searchParams.set("schema", SCHEMA_FINGERPRINT);
+  if (DATABASE !== null) {
+    connectionUrl.pathname = `${connectionUrl.pathname.replace(/\/$/, "")}/${DATABASE}`;
   }
+  return connectionUrl.toString();
+}
+
+const DEFAULT_CHUNK_SIZE = 1000;
+
+function isChunk(response: Response) {
+  return "data" in response && response.data.type === "ListRowsChunk";
+}
+
+async function* mapStream<T>(
+  responses: AsyncIterable<Response>,
+  map: (response: Response) => T,
+): AsyncGenerator<T> {
+  for await (const response of responses) {
+    yield map(response);
+  }
+}
+
+// Binary frames are a big endian u32 length, that many bytes of JSON and the raw bytes
+// of blobs, which the JSON refers to as `{ "$binary": [offset, length] }`.
+function encodeFrame(request: unknown, binary = false): string | Uint8Array {
+  const attachments: Uint8Array[] = [];
+  let length = 0;
+  const json = JSON.stringify(request, (_key, value) => {
+    if (!(value instanceof Uint8Array)) {
+      return value;
+    }
+    attachments.push(value);
+    length += value.length;
+    return { $binary: [length - value.length, value.length] };
+  });
+
+  if (!binary && attachments.length === 0) {
+    return json;
+  }
+
+  const header = new TextEncoder().encode(json);
+  const frame = new Uint8Array(4 + header.length + length);
+  new DataView(frame.buffer).setUint32(0, header.length);
+  frame.set(header, 4);
+
+  let offset = 4 + header.length;
+  for (const attachment of attachments) {
+    frame.set(attachment, offset);
+    offset += attachment.length;
+  }
+  return frame;
+}
+
+function decodeFrame(frame: ArrayBuffer): unknown {
+  const length = new DataView(frame).getUint32(0);
+  const json = new TextDecoder().decode(new Uint8Array(frame, 4, length));
+  return JSON.parse(json, (_key, value) => {
+    const range = value?.$binary;
+    return Array.isArray(range)
+      ? new Uint8Array(frame, 4 + length + range[0], range[1])
+      : value;
+  });
+}
+
+// Covers what the server sends and accepts: nil, booleans, numbers, strings, `bin` values
+// as `Uint8Array`s, arrays and maps with string keys.
+function encodeMessagePack(value: unknown): Uint8Array {
+  let buffer = new Uint8Array(256);
+  let view = new DataView(buffer.buffer);
+  let length = 0;
+
+  // Grows the buffer before `set` writes `size` bytes at the end of it.
+  function put(size: number, set: (view: DataView, offset: number) => void) {
+    if (length + size > buffer.length) {
+      const grown = new Uint8Array(Math.max(buffer.length * 2, length + size));
+      grown.set(buffer);
+      buffer = grown;
+      view = new DataView(buffer.buffer);
+    }
+    set(view, length);
+    length += size;
+  }
+
+  const u8 = (n: number) => put(1, (v, at) => v.setUint8(at, n));
+  const u16 = (n: number) => put(2, (v, at) => v.setUint16(at, n));
+  const u32 = (n: number) => put(4, (v, at) => v.setUint32(at, n));
+  const bytes = (data: Uint8Array) => put(data.length, (_, at) => buffer.set(data, at));
+
+  function header(size: number, fixed: number, fixedMax: number, codes: number[]) {
+    if (size < fixedMax) u8(fixed | size);
+    else if (size < 0x100 && codes[0]) { u8(codes[0]); u8(size); }
+    else if (size < 0x10000) { u8(codes[1]); u16(size); }
+    else { u8(codes[2]); u32(size); }
+  }
+
+  function write(value: unknown) {
+    if (value === null || value === undefined) {
+      u8(0xc0);
+    } else if (typeof value === "boolean") {
+      u8(value ? 0xc3 : 0xc2);
+    } else if (typeof value === "number" && !Number.isSafeInteger(value)) {
+      u8(0xcb);
+      put(8, (v, at) => v.setFloat64(at, value));
+    } else if (typeof value === "number" && value >= 0) {
+      if (value < 0x80) u8(value);
+      else if (value < 0x100) { u8(0xcc); u8(value); }
+      else if (value < 0x10000) { u8(0xcd); u16(value); }
+      else if (value < 0x100000000) { u8(0xce); u32(value); }
+      else { u8(0xcf); put(8, (v, at) => v.setBigUint64(at, BigInt(value))); }
+    } else if (typeof value === "number") {
+      if (value >= -0x20) u8(value & 0xff);
+      else if (value >= -0x80) { u8(0xd0); put(1, (v, at) => v.setInt8(at, value)); }
+      else if (value >= -0x8000) { u8(0xd1); put(2, (v, at) => v.setInt16(at, value)); }
+      else if (value >= -0x80000000) { u8(0xd2); put(4, (v, at) => v.setInt32(at, value)); }
+      else { u8(0xd3); put(8, (v, at) => v.setBigInt64(at, BigInt(value))); }
+    } else if (typeof value === "string") {
+      const data = new TextEncoder().encode(value);
+      header(data.length, 0xa0, 0x20, [0xd9, 0xda, 0xdb]);
+      bytes(data);
+    } else if (value instanceof Uint8Array) {
+      header(value.length, 0, 0, [0xc4, 0xc5, 0xc6]);
+      bytes(value);
+    } else if (Array.isArray(value)) {
+      header(value.length, 0x90, 0x10, [0, 0xdc, 0xdd]);
+      value.forEach(write);
+    } else if (typeof value === "object" && value !== null && "toJSON" in value) {
+      write((value as { toJSON: () => unknown }).toJSON());
+    } else if (typeof value === "object" && value !== null) {
+      const entries = Object.entries(value).filter(([, v]) => v !== undefined);
+      header(entries.length, 0x80, 0x10, [0, 0xde, 0xdf]);
+      for (const [key, v] of entries) {
+        write(key);
+        write(v);
+      }
+    } else {
+      throw new TypeError(`can't encode a ${typeof value} as MessagePack`);
+    }
+  }
+
+  write(value);
+  return buffer.subarray(0, length);
+}
+
+function decodeMessagePack(frame: ArrayBuffer): unknown {
+  const bytes = new Uint8Array(frame);
+  const view = new DataView(frame);
+  let offset = 0;
+
+  function number(size: number, get: (offset: number) => number | bigint): number {
+    offset += size;
+    return Number(get.call(view, offset - size));
+  }
+  const u8 = () => number(1, view.getUint8);
+  const u16 = () => number(2, view.getUint16);
+  const u32 = () => number(4, view.getUint32);
+
+  function take(size: number): Uint8Array {
+    offset += size;
+    return bytes.subarray(offset - size, offset);
+  }
+  const str = (size: number) => new TextDecoder().decode(take(size));
+
+  function array(size: number): unknown[] {
+    return Array.from({ length: size }, read);
+  }
+
+  function map(size: number): Record<string, unknown> {
+    const object: Record<string, unknown> = {};
+    for (let i = 0; i < size; i++) {
+      const key = String(read());
+      object[key] = read();
+    }
+    return object;
+  }
+
+  function read(): unknown {
+    const code = u8();
+    if (code < 0x80) return code;
+    if (code < 0x90) return map(code & 0x0f);
+    if (code < 0xa0) return array(code & 0x0f);
+    if (code < 0xc0) return str(code & 0x1f);
+    if (code >= 0xe0) return code - 0x100;
+
+    switch (code) {
+      case 0xc0: return null;
+      case 0xc2: return false;
+      case 0xc3: return true;
+      case 0xc4: return take(u8());
+      case 0xc5: return take(u16());
+      case 0xc6: return take(u32());
+      case 0xca: return number(4, view.getFloat32);
+      case 0xcb: return number(8, view.getFloat64);
+      case 0xcc: return u8();
+      case 0xcd: return u16();
+      case 0xce: return u32();
+      case 0xcf: return number(8, view.getBigUint64);
+      case 0xd0: return number(1, view.getInt8);
+      case 0xd1: return number(2, view.getInt16);
+      case 0xd2: return number(4, view.getInt32);
+      case 0xd3: return number(8, view.getBigInt64);
+      case 0xd9: return str(u8());
+      case 0xda: return str(u16());
+      case 0xdb: return str(u32());
+      case 0xdc: return array(u16());
+      case 0xdd: return array(u32());
+      case 0xde: return map(u16());
+      case 0xdf: return map(u32());
+    }
+    throw new Error(`unsupported MessagePack type 0x${code.toString(16)}`);
+  }
+
+  return read();
+}
+
+export type Fetch = {
+  (request: Request, options?: FetchOptions): Promise<Response>;
+  stream(request: ListRows, options?: FetchOptions): AsyncGenerator<Response>;
+};
 
-  return $fetch;
+export function makeTableClient($fetch: Fetch) {
+  return {
+    albums: {
+      list: (
+        options: {
+          select?: z.infer<typeof albums_columns>[];
+          sort?: z.infer<typeof albums_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "albums",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, albums_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof albums_columns>[];
+          sort?: z.infer<typeof albums_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "albums",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, albums_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof albums_primary_key>,
+        options: { select?: z.infer<typeof albums_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "albums",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, albums_get_row_response)),
+      insert: (data: z.infer<typeof albums_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "albums",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, albums_insert_row_response)),
+      batchInsert: (data: z.infer<typeof albums_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "albums",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, albums_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof albums_primary_key>,
+        data: z.infer<typeof albums_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "albums",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, albums_update_row_response)),
+      delete: (key: z.infer<typeof albums_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "albums",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, albums_delete_row_response)),
+    },
+
+    artists: {
+      list: (
+        options: {
+          select?: z.infer<typeof artists_columns>[];
+          sort?: z.infer<typeof artists_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "artists",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, artists_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof artists_columns>[];
+          sort?: z.infer<typeof artists_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "artists",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, artists_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof artists_primary_key>,
+        options: { select?: z.infer<typeof artists_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "artists",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, artists_get_row_response)),
+      insert: (data: z.infer<typeof artists_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "artists",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, artists_insert_row_response)),
+      batchInsert: (data: z.infer<typeof artists_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "artists",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, artists_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof artists_primary_key>,
+        data: z.infer<typeof artists_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "artists",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, artists_update_row_response)),
+      delete: (key: z.infer<typeof artists_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "artists",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, artists_delete_row_response)),
+    },
+
+    customers: {
+      list: (
+        options: {
+          select?: z.infer<typeof customers_columns>[];
+          sort?: z.infer<typeof customers_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "customers",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, customers_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof customers_columns>[];
+          sort?: z.infer<typeof customers_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "customers",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, customers_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof customers_primary_key>,
+        options: { select?: z.infer<typeof customers_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "customers",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, customers_get_row_response)),
+      insert: (data: z.infer<typeof customers_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "customers",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, customers_insert_row_response)),
+      batchInsert: (data: z.infer<typeof customers_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "customers",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, customers_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof customers_primary_key>,
+        data: z.infer<typeof customers_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "customers",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, customers_update_row_response)),
+      delete: (key: z.infer<typeof customers_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "customers",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, customers_delete_row_response)),
+    },
+
+    employees: {
+      list: (
+        options: {
+          select?: z.infer<typeof employees_columns>[];
+          sort?: z.infer<typeof employees_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "employees",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, employees_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof employees_columns>[];
+          sort?: z.infer<typeof employees_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "employees",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, employees_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof employees_primary_key>,
+        options: { select?: z.infer<typeof employees_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "employees",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, employees_get_row_response)),
+      insert: (data: z.infer<typeof employees_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "employees",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, employees_insert_row_response)),
+      batchInsert: (data: z.infer<typeof employees_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "employees",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, employees_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof employees_primary_key>,
+        data: z.infer<typeof employees_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "employees",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, employees_update_row_response)),
+      delete: (key: z.infer<typeof employees_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "employees",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, employees_delete_row_response)),
+    },
+
+    genres: {
+      list: (
+        options: {
+          select?: z.infer<typeof genres_columns>[];
+          sort?: z.infer<typeof genres_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "genres",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, genres_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof genres_columns>[];
+          sort?: z.infer<typeof genres_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "genres",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, genres_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof genres_primary_key>,
+        options: { select?: z.infer<typeof genres_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "genres",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, genres_get_row_response)),
+      insert: (data: z.infer<typeof genres_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "genres",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, genres_insert_row_response)),
+      batchInsert: (data: z.infer<typeof genres_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "genres",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, genres_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof genres_primary_key>,
+        data: z.infer<typeof genres_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "genres",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, genres_update_row_response)),
+      delete: (key: z.infer<typeof genres_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "genres",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, genres_delete_row_response)),
+    },
+
+    invoices: {
+      list: (
+        options: {
+          select?: z.infer<typeof invoices_columns>[];
+          sort?: z.infer<typeof invoices_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "invoices",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoices_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof invoices_columns>[];
+          sort?: z.infer<typeof invoices_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "invoices",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, invoices_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof invoices_primary_key>,
+        options: { select?: z.infer<typeof invoices_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "invoices",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoices_get_row_response)),
+      insert: (data: z.infer<typeof invoices_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "invoices",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoices_insert_row_response)),
+      batchInsert: (data: z.infer<typeof invoices_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "invoices",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoices_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof invoices_primary_key>,
+        data: z.infer<typeof invoices_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "invoices",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoices_update_row_response)),
+      delete: (key: z.infer<typeof invoices_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "invoices",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoices_delete_row_response)),
+    },
+
+    invoice_items: {
+      list: (
+        options: {
+          select?: z.infer<typeof invoice_items_columns>[];
+          sort?: z.infer<typeof invoice_items_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "invoice_items",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoice_items_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof invoice_items_columns>[];
+          sort?: z.infer<typeof invoice_items_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "invoice_items",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, invoice_items_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof invoice_items_primary_key>,
+        options: { select?: z.infer<typeof invoice_items_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "invoice_items",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoice_items_get_row_response)),
+      insert: (data: z.infer<typeof invoice_items_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "invoice_items",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoice_items_insert_row_response)),
+      batchInsert: (data: z.infer<typeof invoice_items_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "invoice_items",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoice_items_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof invoice_items_primary_key>,
+        data: z.infer<typeof invoice_items_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "invoice_items",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoice_items_update_row_response)),
+      delete: (key: z.infer<typeof invoice_items_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "invoice_items",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, invoice_items_delete_row_response)),
+    },
+
+    media_types: {
+      list: (
+        options: {
+          select?: z.infer<typeof media_types_columns>[];
+          sort?: z.infer<typeof media_types_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "media_types",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, media_types_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof media_types_columns>[];
+          sort?: z.infer<typeof media_types_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "media_types",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, media_types_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof media_types_primary_key>,
+        options: { select?: z.infer<typeof media_types_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "media_types",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, media_types_get_row_response)),
+      insert: (data: z.infer<typeof media_types_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "media_types",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, media_types_insert_row_response)),
+      batchInsert: (data: z.infer<typeof media_types_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "media_types",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, media_types_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof media_types_primary_key>,
+        data: z.infer<typeof media_types_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "media_types",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, media_types_update_row_response)),
+      delete: (key: z.infer<typeof media_types_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "media_types",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, media_types_delete_row_response)),
+    },
+
+    playlists: {
+      list: (
+        options: {
+          select?: z.infer<typeof playlists_columns>[];
+          sort?: z.infer<typeof playlists_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "playlists",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlists_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof playlists_columns>[];
+          sort?: z.infer<typeof playlists_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "playlists",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, playlists_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof playlists_primary_key>,
+        options: { select?: z.infer<typeof playlists_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "playlists",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlists_get_row_response)),
+      insert: (data: z.infer<typeof playlists_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "playlists",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlists_insert_row_response)),
+      batchInsert: (data: z.infer<typeof playlists_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "playlists",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlists_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof playlists_primary_key>,
+        data: z.infer<typeof playlists_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "playlists",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlists_update_row_response)),
+      delete: (key: z.infer<typeof playlists_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "playlists",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlists_delete_row_response)),
+    },
+
+    playlist_track: {
+      list: (
+        options: {
+          select?: z.infer<typeof playlist_track_columns>[];
+          sort?: z.infer<typeof playlist_track_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "playlist_track",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlist_track_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof playlist_track_columns>[];
+          sort?: z.infer<typeof playlist_track_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "playlist_track",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, playlist_track_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof playlist_track_primary_key>,
+        options: { select?: z.infer<typeof playlist_track_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "playlist_track",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlist_track_get_row_response)),
+      insert: (data: z.infer<typeof playlist_track_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "playlist_track",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlist_track_insert_row_response)),
+      batchInsert: (data: z.infer<typeof playlist_track_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "playlist_track",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlist_track_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof playlist_track_primary_key>,
+        data: z.infer<typeof playlist_track_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "playlist_track",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlist_track_update_row_response)),
+      delete: (key: z.infer<typeof playlist_track_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "playlist_track",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, playlist_track_delete_row_response)),
+    },
+
+    tracks: {
+      list: (
+        options: {
+          select?: z.infer<typeof tracks_columns>[];
+          sort?: z.infer<typeof tracks_sort_options>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "ListRows",
+          table: "tracks",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_list_rows_response)),
+      stream: (
+        options: {
+          select?: z.infer<typeof tracks_columns>[];
+          sort?: z.infer<typeof tracks_sort_options>;
+          page?: z.infer<typeof Pagination>;
+          chunkSize?: number;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        mapStream($fetch.stream({
+          type: "ListRows",
+          table: "tracks",
+          select: options.select ?? [],
+          sort: options.sort,
+          page: options.page,
+          chunk_size: options.chunkSize,
+          request_id: newRequestId(),
+        }, fetchOptions), (resp) => narrow(resp, tracks_list_rows_chunk_response)),
+      get: (
+        key: z.infer<typeof tracks_primary_key>,
+        options: { select?: z.infer<typeof tracks_columns>[] } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "GetRow",
+          table: "tracks",
+          key,
+          select: options.select ?? [],
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_get_row_response)),
+      insert: (data: z.infer<typeof tracks_schema>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "InsertRow",
+          table: "tracks",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_insert_row_response)),
+      batchInsert: (data: z.infer<typeof tracks_schema>[], fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "BatchInsertRow",
+          table: "tracks",
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_batch_insert_row_response)),
+      update: (
+        key: z.infer<typeof tracks_primary_key>,
+        data: z.infer<typeof tracks_schema_optional>,
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "UpdateRow",
+          table: "tracks",
+          key,
+          data,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_update_row_response)),
+      delete: (key: z.infer<typeof tracks_primary_key>, fetchOptions?: FetchOptions) =>
+        $fetch({
+          type: "DeleteRow",
+          table: "tracks",
+          key,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_delete_row_response)),
+      search: (
+        query: string,
+        options: {
+          select?: z.infer<typeof tracks_columns>[];
+          highlight?: z.infer<typeof Highlight>;
+          snippet?: z.infer<typeof Snippet>;
+          page?: z.infer<typeof Pagination>;
+        } = {},
+        fetchOptions?: FetchOptions,
+      ) =>
+        $fetch({
+          type: "Search",
+          table: "tracks",
+          query,
+          select: options.select ?? [],
+          highlight: options.highlight,
+          snippet: options.snippet,
+          page: options.page,
+          request_id: newRequestId(),
+        }, fetchOptions).then((resp) => narrow(resp, tracks_search_response)),
+    },
+  };
 }
@@ -1,4 +1,10 @@
-import { makeWebSocketFetch, nanoid, snapshotTest } from "./wrapper.ts";
+import {
+  assert,
+  assertEquals,
+  makeWebSocketFetch,
+  nanoid,
+  snapshotTest,
+} from "./wrapper.ts";
 
 const $fetch = await makeWebSocketFetch({
   url: "ws://127.0.0.1:3030",
@@ -80,3 +86,74 @@ Deno.test(
     request_id: nanoid(),
   }),
 );
+
+Deno.test("stream a table in chunks", async () => {
+  const chunks = [];
+  for await (
+    const resp of $fetch.stream({
+      type: "ListRows",
+      table: "genres",
+      select: ["GenreId"],
+      chunk_size: 10,
+      request_id: nanoid(),
+    })
+  ) {
+    assert("data" in resp && resp.data.type === "ListRowsChunk");
+    assert(resp.data.table === "genres");
+    chunks.push(resp.data.rows.map((row) => row.GenreId));
+  }
+
+  assertEquals(chunks.map((chunk) => chunk.length), [10, 10, 5]);
+  assertEquals(chunks.flat(), Array.from({ length: 25 }, (_, i) => i + 1));
+});
+
+Deno.test("stream a sorted page in chunks", async () => {
+  const resp = await $fetch({
+    type: "ListRows",
+    table: "tracks",
+    select: ["TrackId"],
+    sort: { column: "Name", order: "Desc" },
+    page: { number: 3, size: 10 },
+    request_id: nanoid(),
+  });
+  assert("data" in resp && resp.data.type === "ListRows");
+
+  const chunks = [];
+  for await (
+    const chunk of $fetch.stream({
+      type: "ListRows",
+      table: "tracks",
+      select: ["TrackId"],
+      sort: { column: "Name", order: "Desc" },
+      page: { number: 3, size: 10 },
+      chunk_size: 4,
+      request_id: nanoid(),
+    })
+  ) {
+    assert("data" in chunk && chunk.data.type === "ListRowsChunk");
+    chunks.push(chunk.data.rows);
+  }
+
+  assertEquals(chunks.map((chunk) => chunk.length), [4, 4, 2]);
+  assertEquals(chunks.flat(), resp.data.rows);
+});
+
+Deno.test("stream a table that does not exist", async () => {
+  const responses = [];
+  for await (
+    const resp of $fetch.stream({
+      type: "ListRows",
+      // @ts-ignore: type safety will not let us do this
+      table: "cats",
+      select: [],
+      chunk_size: 10,
+      request_id: nanoid(),
+    })
+  ) {
+    responses.push(resp);
+  }
+
+  assertEquals(responses, [
+    { error: { type: "TableNotFound", table: "cats" } },
+  ]);
+});
@@ -0,0 +1,101 @@
+import { assert, assertEquals, nanoid, openRawSocket } from "./wrapper.ts";
+
+const url = "ws://127.0.0.1:3030";
+
+Deno.test("errors are sent as they are before the hello", async () => {
+  const socket = await openRawSocket(url);
+  try {
+    const request_id = nanoid();
+    const resp = await socket.request({
+      type: "GetRow",
+      table: "cats",
+      key: 1,
+      select: [],
+      request_id,
+    });
+    assertEquals(resp, { type: "TableNotFound", table: "cats", request_id });
+  } finally {
+    await socket.close();
+  }
+});
+
+Deno.test("errors are wrapped from version 2 on", async () => {
+  const socket = await openRawSocket(url);
+  try {
+    const hello = await socket.request({
+      type: "Hello",
+      protocol_version: 2,
+      request_id: nanoid(),
+    });
+    assertEquals(hello.protocol_version, 2);
+    assert(Array.isArray(hello.capabilities));
+    assert(hello.capabilities.includes("streaming"));
+
+    const request_id = nanoid();
+    const resp = await socket.request({
+      type: "GetRow",
+      table: "cats",
+      key: 1,
+      select: [],
+      request_id,
+    });
+    assertEquals(resp, {
+      type: "Error",
+      error: { type: "TableNotFound", table: "cats" },
+      request_id,
+    });
+  } finally {
+    await socket.close();
+  }
+});
+
+Deno.test("unsupported protocol versions are refused", async () => {
+  const socket = await openRawSocket(url);
+  try {
+    const request_id = nanoid();
+    const resp = await socket.request({
+      type: "Hello",
+      protocol_version: 0,
+      request_id,
+    });
+    assertEquals(resp, {
+      type: "UnsupportedProtocolVersion",
+      supported: [1, 2],
+      request_id,
+    });
+  } finally {
+    await socket.close();
+  }
+});
+
+Deno.test("capabilities left out of the hello are refused", async () => {
+  const socket = await openRawSocket(url);
+  try {
+    const hello = await socket.request({
+      type: "Hello",
+      protocol_version: 2,
+      capabilities: ["search"],
+      request_id: nanoid(),
+    });
+    assertEquals(hello.capabilities, ["search"]);
+
+    const request_id = nanoid();
+    const resp = await socket.request({
+      type: "ListRows",
+      table: "genres",
+      select: [],
+      chunk_size: 10,
+      request_id,
+    });
+    assertEquals(resp, {
+      type: "Error",
+      error: {
+        type: "BadRequest",
+        message: "the streaming capability was not negotiated",
+      },
+      request_id,
+    });
+  } finally {
+    await socket.close();
+  }
+});
@@ -0,0 +1,68 @@
+import { assert, assertEquals, makeWebSocketFetch, nanoid } from "./wrapper.ts";
+
+// Needs the server started with `--search-index "tracks:Name,Composer"`, like
+// `just serve` does.
+const $fetch = await makeWebSocketFetch({
+  url: "ws://127.0.0.1:3030",
+  connectionCount: 10,
+});
+
+Deno.test("search a table", async () => {
+  const resp = await $fetch({
+    type: "Search",
+    table: "tracks",
+    query: "balls",
+    select: ["TrackId", "Name"],
+    request_id: nanoid(),
+  });
+
+  assert("data" in resp && resp.data.type === "Search");
+  assertEquals(resp.data.hits.map((hit) => hit.row), [
+    { TrackId: 2, Name: "Balls to the Wall" },
+  ]);
+});
+
+Deno.test("search a column and highlight the matches", async () => {
+  const resp = await $fetch({
+    type: "Search",
+    table: "tracks",
+    query: "Name:balls",
+    select: ["TrackId"],
+    highlight: { open: "[", close: "]" },
+    request_id: nanoid(),
+  });
+
+  assert("data" in resp && resp.data.type === "Search");
+  assertEquals(resp.data.hits.map((hit) => hit.highlights?.Name), [
+    "[Balls] to the Wall",
+  ]);
+});
+
+Deno.test("search with an invalid query", async () => {
+  const resp = await $fetch({
+    type: "Search",
+    table: "tracks",
+    query: '"unterminated',
+    select: [],
+    request_id: nanoid(),
+  });
+
+  assertEquals(resp, {
+    error: { type: "InvalidSearch", message: "unterminated string" },
+  });
+});
+
+Deno.test("search a table without an index", async () => {
+  const resp = await $fetch({
+    type: "Search",
+    // @ts-ignore: type safety will not let us do this
+    table: "albums",
+    query: "rock",
+    select: [],
+    request_id: nanoid(),
+  });
+
+  assertEquals(resp, {
+    error: { type: "TableNotSearchable", table: "albums" },
+  });
+});
@@ -1,9 +1,9 @@
 import { nanoid } from "https://deno.land/x/nanoid@v3.0.0/mod.ts";
-import { assertEquals } from "https://deno.land/std@0.224.0/assert/mod.ts";
+import { assert, assertEquals } from "https://deno.land/std@0.224.0/assert/mod.ts";
 import { assertSnapshot } from "https://deno.land/std@0.224.0/testing/snapshot.ts";
 import { makeWebSocketFetch, Request } from "./jabroni.ts";
 
-export { assertEquals, assertSnapshot, makeWebSocketFetch, nanoid };
+export { assert, assertEquals, assertSnapshot, makeWebSocketFetch, nanoid };
 
 export function snapshotTest(
   $fetch: Awaited<ReturnType<typeof makeWebSocketFetch>>,
@@ -19,3 +19,43 @@ export function snapshotTest(
     await assertSnapshot(t, resp);
   });
 }
+
+/**
+ * A WebSocket that sends and receives plain JSON, for messages the generated
+ * client doesn't know about. Requests wait for the message with their
+ * `request_id`, anything else that arrives in between is dropped.
+ */
+export async function openRawSocket(url: string) {
+  const socket = new WebSocket(url);
+  const messages: Record<string, unknown>[] = [];
+  let wake = () => {};
+
+  socket.onmessage = (event) => {
+    messages.push(JSON.parse(event.data));
+    wake();
+  };
+  await new Promise((resolve, reject) => {
+    socket.onopen = resolve;
+    socket.onerror = reject;
+  });
+
+  async function request(message: { request_id: string }) {
+    socket.send(JSON.stringify(message));
+    while (true) {
+      const response = messages.shift();
+      if (!response) {
+        await new Promise<void>((resolve) => (wake = resolve));
+      } else if (response.request_id === message.request_id) {
+        return response;
+      }
+    }
+  }
+
+  function close() {
+    const closed = new Promise((resolve) => (socket.onclose = resolve));
+    socket.close();
+    return closed;
+  }
+
+  return { request, close };
+}